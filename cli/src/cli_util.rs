@@ -15,6 +15,7 @@
 use std::borrow::Cow;
 use std::cell::OnceCell;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
@@ -73,6 +74,7 @@ use jj_lib::fileset::FilesetAliasesMap;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
 use jj_lib::fileset::FilesetParseContext;
+use jj_lib::fileset::UnresolvedFilesetExpression;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::IdPrefixContext;
@@ -84,12 +86,14 @@ use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_heads_store;
+use jj_lib::op_store::OpStore;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_store::OperationId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RefNameBuf;
 use jj_lib::ref_name::RemoteName;
@@ -128,6 +132,7 @@ use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
 use jj_lib::str_util::StringExpression;
 use jj_lib::str_util::StringMatcher;
+use jj_lib::submodule_store;
 use jj_lib::transaction::Transaction;
 use jj_lib::transaction::TransactionCommitError;
 use jj_lib::working_copy;
@@ -180,6 +185,8 @@ use crate::diff_util::DiffRenderer;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
+use crate::hooks;
+use crate::hooks::HookEvent;
 use crate::merge_tools::DiffEditor;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
@@ -196,10 +203,15 @@ use crate::templater::TemplateRenderer;
 use crate::templater::WrapTemplateProperty;
 use crate::text_util;
 use crate::ui::ColorChoice;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 const SHORT_CHANGE_ID_TEMPLATE_TEXT: &str = "format_short_change_id_with_change_offset(self)";
 
+/// Template that renders each item as a line of JSON. Used in place of the
+/// configured template when `ui.output-format` is `json`.
+pub const JSON_LINE_TEMPLATE_TEXT: &str = r#"json(self) ++ "\n""#;
+
 #[derive(Clone)]
 struct ChromeTracingFlushGuard {
     _inner: Option<Rc<tracing_chrome::FlushGuard>>,
@@ -796,6 +808,17 @@ pub async fn merge_operations(
     Ok(merged_repo.operation().clone())
 }
 
+/// Parses and evaluates the operation set expression. Operations reachable
+/// from the `head_ops` are returned in reverse topological order.
+pub async fn resolve_opset(
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+    text: &str,
+) -> Result<Vec<Operation>, CommandError> {
+    let expression = opset::parse(text)?;
+    Ok(expression.evaluate(op_store, head_ops).await?)
+}
+
 /// A ReadonlyRepo along with user-config-dependent derived data. The derived
 /// data is lazily loaded.
 struct ReadonlyUserRepo {
@@ -1551,6 +1574,28 @@ to the current parents may contain changes from multiple commits.
         }
     }
 
+    /// Parses the given strings as file patterns, which may also use functions
+    /// like `executable()`. The caller has to resolve them by
+    /// `UnresolvedFilesetExpression::resolve_predicates()` before building a
+    /// matcher.
+    pub fn parse_file_patterns_with_predicates(
+        &self,
+        ui: &Ui,
+        values: &[String],
+    ) -> Result<UnresolvedFilesetExpression, CommandError> {
+        if values.is_empty() {
+            return Ok(FilesetExpression::all().into());
+        }
+        let mut diagnostics = FilesetDiagnostics::new();
+        let context = self.env.fileset_parse_context();
+        let expressions: Vec<_> = values
+            .iter()
+            .map(|arg| fileset::parse_maybe_bare_with_predicates(&mut diagnostics, arg, &context))
+            .try_collect()?;
+        print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
+        Ok(UnresolvedFilesetExpression::union_all(expressions))
+    }
+
     /// Parses the given fileset expressions and concatenates them all.
     pub fn parse_union_filesets(
         &self,
@@ -1853,6 +1898,25 @@ to the current parents may contain changes from multiple commits.
             .get_or_init(|| self.env.new_id_prefix_context())
     }
 
+    /// Runs the hooks configured for the `event` against the `affected`
+    /// commits in the current repo.
+    pub async fn run_hooks(
+        &self,
+        ui: &Ui,
+        event: HookEvent,
+        affected: &Arc<UserRevsetExpression>,
+    ) -> Result<(), CommandError> {
+        hooks::run_hooks(
+            ui,
+            self,
+            event,
+            affected,
+            &|expression| self.attach_revset_evaluator(expression),
+            &|commit| self.format_commit_summary(commit),
+        )
+        .await
+    }
+
     /// Parses template of the given language into evaluation tree.
     pub fn parse_template<'a, C, L>(
         &self,
@@ -2212,7 +2276,43 @@ to the current parents may contain changes from multiple commits.
             new_commit,
         )
         .await?;
-        self.print_updated_working_copy_stats(ui, maybe_old_commit, new_commit, &stats)
+        self.print_updated_working_copy_stats(ui, maybe_old_commit, new_commit, &stats)?;
+        if self.settings().get_bool("working-copy.update-submodules")? {
+            self.update_submodules(ui, maybe_old_commit, new_commit)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Checks out the submodules whose pinned commits changed.
+    async fn update_submodules(
+        &self,
+        ui: &Ui,
+        maybe_old_commit: Option<&Commit>,
+        new_commit: &Commit,
+    ) -> Result<(), CommandError> {
+        use std::error::Error as _;
+        let old_tree = maybe_old_commit.map(|commit| commit.tree());
+        let stats = submodule_store::update_submodules(
+            self.repo().submodule_store().as_ref(),
+            old_tree.as_ref(),
+            &new_commit.tree(),
+            self.workspace_root(),
+        )
+        .await
+        .map_err(|err| internal_error_with_message("Failed to update submodules", err))?;
+        if !stats.updated.is_empty() {
+            writeln!(ui.status(), "Updated {} submodules", stats.updated.len())?;
+        }
+        for (path, err) in &stats.failed {
+            writeln!(
+                ui.warning_default(),
+                "Failed to update submodule {}: {err}",
+                self.format_file_path(path)
+            )?;
+            print_error_sources(ui, err.source())?;
+        }
+        Ok(())
     }
 
     fn print_updated_working_copy_stats(
@@ -2317,7 +2417,25 @@ to the current parents may contain changes from multiple commits.
 
         self.report_repo_changes(ui, &old_repo).await?;
 
-        if !self.env.command.should_commit_transaction() {
+        if self.env.command.should_commit_transaction() {
+            let old_heads = old_repo.view().heads().iter().cloned().collect_vec();
+            let new_heads = self.repo().view().heads().iter().cloned().collect_vec();
+            let added_commits =
+                RevsetExpression::commits(old_heads).range(&RevsetExpression::commits(new_heads));
+            // The operation is already committed, so errors can only be
+            // reported as warnings.
+            if let Err(err) = self
+                .run_hooks(ui, HookEvent::PostOperation, &added_commits)
+                .await
+            {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to run post-operation hooks: {}",
+                    err.error
+                )?;
+                print_error_sources(ui, err.error.source())?;
+            }
+        } else {
             writeln!(
                 ui.status(),
                 "Operation left uncommitted because --no-integrate-operation was requested: {}",
@@ -2728,6 +2846,41 @@ impl WorkspaceCommandTransaction<'_> {
         self.helper.env.parse_template(ui, &language, template_text)
     }
 
+    /// Creates revset expression evaluator with the current transaction state.
+    pub fn attach_revset_evaluator(
+        &self,
+        expression: Arc<UserRevsetExpression>,
+    ) -> RevsetExpressionEvaluator<'_> {
+        let id_prefix_context = self
+            .id_prefix_context
+            .get_or_init(|| self.helper.env.new_id_prefix_context());
+        RevsetExpressionEvaluator::new(
+            self.tx.repo(),
+            self.helper.env.command.revset_extensions().clone(),
+            id_prefix_context,
+            expression,
+        )
+    }
+
+    /// Runs the hooks configured for the `event` against the `affected`
+    /// commits in the transaction.
+    pub async fn run_hooks(
+        &self,
+        ui: &Ui,
+        event: HookEvent,
+        affected: &Arc<UserRevsetExpression>,
+    ) -> Result<(), CommandError> {
+        hooks::run_hooks(
+            ui,
+            self.helper,
+            event,
+            affected,
+            &|expression| self.attach_revset_evaluator(expression),
+            &|commit| self.format_commit_summary(commit),
+        )
+        .await
+    }
+
     pub async fn finish(self, ui: &Ui, description: impl Into<String>) -> Result<(), CommandError> {
         let Self { helper, mut tx, .. } = self;
         if !tx.repo().has_changes() {
@@ -3098,6 +3251,59 @@ pub fn print_conflicted_paths(
     Ok(())
 }
 
+/// Writes conflicted paths as JSON lines. This is the `--output=json`
+/// counterpart of [`print_conflicted_paths()`].
+pub fn write_conflicted_paths_json(
+    conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
+    formatter: &mut dyn Formatter,
+) -> Result<(), CommandError> {
+    #[derive(serde::Serialize)]
+    struct ConflictItem<'a> {
+        r#type: &'static str,
+        path: &'a str,
+        sides: usize,
+        deletions: usize,
+        special: BTreeSet<&'static str>,
+    }
+
+    for (path, conflict) in conflicts {
+        let conflict = conflict?.simplify();
+        let sides = conflict.num_sides();
+        let special = itertools::chain(conflict.removes(), conflict.adds())
+            .flatten()
+            .filter_map(|term| match term {
+                TreeValue::File {
+                    executable: false, ..
+                } => None,
+                TreeValue::File {
+                    executable: true, ..
+                } => Some("executable"),
+                TreeValue::Symlink(_) => Some("symlink"),
+                TreeValue::Tree(_) => Some("directory"),
+                TreeValue::GitSubmodule(_) => Some("git_submodule"),
+            })
+            .collect();
+        let item = ConflictItem {
+            r#type: "conflict",
+            path: path.as_internal_file_string(),
+            sides,
+            deletions: sides - conflict.adds().flatten().count(),
+            special,
+        };
+        write_json_line(formatter, &item)?;
+    }
+    Ok(())
+}
+
+/// Writes `value` as a single line of JSON.
+pub fn write_json_line(
+    formatter: &mut dyn Formatter,
+    value: &impl serde::Serialize,
+) -> io::Result<()> {
+    serde_json::to_writer(&mut *formatter, value)?;
+    writeln!(formatter)
+}
+
 /// Build human-readable messages explaining why the file was not tracked
 fn build_untracked_reason_message(reason: &UntrackedReason) -> Option<String> {
     match reason {
@@ -3239,7 +3445,18 @@ pub fn print_unmatched_explicit_paths<'a>(
     expression: &FilesetExpression,
     trees: impl IntoIterator<Item = &'a MergedTree>,
 ) -> io::Result<()> {
-    let mut explicit_paths = expression.explicit_paths().collect_vec();
+    print_unmatched_paths(ui, workspace_command, expression.explicit_paths(), trees)
+}
+
+/// Prints a warning for the given `paths` which don't exist in any of the
+/// `trees`.
+pub fn print_unmatched_paths<'a, 'b>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    paths: impl IntoIterator<Item = &'b RepoPath>,
+    trees: impl IntoIterator<Item = &'a MergedTree>,
+) -> io::Result<()> {
+    let mut explicit_paths = paths.into_iter().collect_vec();
     for tree in trees {
         // TODO: propagate errors
         explicit_paths.retain(|&path| tree.path_value(path).block_on().unwrap().is_absent());
@@ -3696,6 +3913,14 @@ pub struct EarlyArgs {
     // Option<bool>.
     pub no_pager: Option<bool>,
 
+    /// Format of the primary command output
+    ///
+    /// With `json`, supported commands print one JSON object per line instead
+    /// of text, and errors are reported as JSON objects on stderr. See
+    /// https://docs.jj-vcs.dev/latest/json-output/ for details.
+    #[arg(long, value_name = "FORMAT", global = true)]
+    pub output: Option<OutputFormat>,
+
     /// Additional configuration options (can be repeated)
     ///
     /// The name should be specified as TOML dotted keys. The value should be
@@ -3980,6 +4205,11 @@ fn parse_early_args(
     if args.no_pager.unwrap_or_default() {
         layer.set_value("ui.paginate", "never").unwrap();
     }
+    if let Some(format) = args.output {
+        layer
+            .set_value("ui.output-format", format.to_string())
+            .unwrap();
+    }
     if !layer.is_empty() {
         config_layers.push(layer);
     }
@@ -4263,6 +4493,8 @@ impl<'a> CliRunner<'a> {
     pub fn init() -> Self {
         let tracing_subscription = TracingSubscription::init();
         crate::cleanup_guard::init();
+        let mut revset_extensions = RevsetExtensions::default();
+        crate::gerrit_util::add_revset_functions(&mut revset_extensions);
         Self {
             tracing_subscription,
             app: crate::commands::default_app(),
@@ -4271,7 +4503,7 @@ impl<'a> CliRunner<'a> {
             store_factories: StoreFactories::default(),
             working_copy_factories: default_working_copy_factories(),
             workspace_loader_factory: Box::new(DefaultWorkspaceLoaderFactory),
-            revset_extensions: Default::default(),
+            revset_extensions,
             commit_template_extensions: vec![],
             operation_template_extensions: vec![],
             dispatch: Box::new(AsyncCliDispatchFn(crate::commands::run_command)),
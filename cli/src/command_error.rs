@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::error;
 use std::error::Error as _;
 use std::io;
@@ -28,6 +29,7 @@ use jj_lib::config::ConfigFileSaveError;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
 use jj_lib::config::ConfigMigrateError;
+use jj_lib::converge::ConvergeError;
 use jj_lib::dsl_util::Diagnostics;
 use jj_lib::evolution::WalkPredecessorsError;
 use jj_lib::fileset::FilePatternParseError;
@@ -40,6 +42,8 @@ use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::opset::OpsetParseError;
+use jj_lib::opset::OpsetParseErrorKind;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
@@ -81,6 +85,7 @@ use crate::revset_util::TagNameParseError;
 use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_parser::TemplateParseError;
 use crate::template_parser::TemplateParseErrorKind;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -450,10 +455,11 @@ impl From<ConflictResolveError> for CommandError {
         match err {
             ConflictResolveError::Backend(err) => err.into(),
             ConflictResolveError::Io(err) => err.into(),
+            ConflictResolveError::TextEditor(err) => err.into(),
             _ => {
                 let hint = match &err {
                     ConflictResolveError::ConflictTooComplicated { .. } => {
-                        Some("Edit the conflict markers manually to resolve this.".to_owned())
+                        Some("Use `jj resolve --editor` to edit the conflict markers.".to_owned())
                     }
                     ConflictResolveError::ExecutableConflict { .. } => {
                         Some("Use `jj file chmod` to update the executable bit.".to_owned())
@@ -565,6 +571,7 @@ jj currently does not support partial clones. To use jj with this repository, tr
                     user_error(err).hinted("Run `jj git remote rename` to give a different name.")
                 }
                 GitFetchError::RejectedUpdates(_) | GitFetchError::Subprocess(_) => user_error(err),
+                GitFetchError::Backend(_) => internal_error(err),
             }
         }
     }
@@ -636,6 +643,16 @@ impl From<FilesetParseError> for CommandError {
     }
 }
 
+impl From<OpsetParseError> for CommandError {
+    fn from(err: OpsetParseError) -> Self {
+        let hint = opset_parse_error_hint(&err);
+        let mut cmd_err =
+            user_error_with_message(format!("Failed to parse opset: {}", err.kind()), err);
+        cmd_err.extend_hints(hint);
+        cmd_err
+    }
+}
+
 impl From<RecoverWorkspaceError> for CommandError {
     fn from(err: RecoverWorkspaceError) -> Self {
         match err {
@@ -742,6 +759,21 @@ impl From<FixError> for CommandError {
     }
 }
 
+impl From<ConvergeError> for CommandError {
+    fn from(err: ConvergeError) -> Self {
+        match err {
+            ConvergeError::Backend(err) => err.into(),
+            ConvergeError::Index(err) => err.into(),
+            ConvergeError::RevsetEvaluation(err) => err.into(),
+            ConvergeError::WalkPredecessors(err) => err.into(),
+            ConvergeError::IO(err) => err.into(),
+            ConvergeError::Other(err) => {
+                internal_error_with_message("Failed to converge divergent commits", err)
+            }
+        }
+    }
+}
+
 impl From<BisectionError> for CommandError {
     fn from(err: BisectionError) -> Self {
         match err {
@@ -825,6 +857,8 @@ fn file_pattern_parse_error_hint(err: &FilePatternParseError) -> Option<String>
         }
         FilePatternParseError::RelativePath(_) => None,
         FilePatternParseError::GlobPattern(_) => None,
+        FilePatternParseError::Regex(_) => None,
+        FilePatternParseError::InvalidFileName(_) => None,
     }
 }
 
@@ -847,6 +881,21 @@ fn fileset_parse_error_hint(err: &FilesetParseError) -> Option<String> {
     }
 }
 
+fn opset_parse_error_hint(err: &OpsetParseError) -> Option<String> {
+    match err.kind() {
+        OpsetParseErrorKind::SyntaxError => Some(String::from(
+            "See https://docs.jj-vcs.dev/latest/operation-log/#opset-language for opset syntax.",
+        )),
+        OpsetParseErrorKind::NoSuchFunction {
+            name: _,
+            candidates,
+        } => format_similarity_hint(candidates),
+        OpsetParseErrorKind::InvalidArguments { .. } | OpsetParseErrorKind::Expression(_) => {
+            find_source_parse_error_hint(err)
+        }
+    }
+}
+
 fn opset_resolution_error_hint(err: &OpsetResolutionError) -> Option<String> {
     match err {
         OpsetResolutionError::MultipleOperations {
@@ -982,6 +1031,9 @@ fn template_parse_error_hint(err: &TemplateParseError) -> Option<String> {
 
 const BROKEN_PIPE_EXIT_CODE: u8 = 3;
 
+const CONFIG_ERROR_HINT: &str =
+    "For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`.";
+
 pub(crate) fn handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> u8 {
     try_handle_command_result(ui, result).unwrap_or(BROKEN_PIPE_EXIT_CODE)
 }
@@ -990,6 +1042,9 @@ fn try_handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> i
     let Err(cmd_err) = &result else {
         return Ok(0);
     };
+    if ui.output_format() == OutputFormat::Json {
+        return handle_command_error_as_json(ui, cmd_err);
+    }
     let err = &cmd_err.error;
     let hints = &cmd_err.hints;
     match cmd_err.kind {
@@ -999,10 +1054,7 @@ fn try_handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> i
         }
         CommandErrorKind::Config => {
             print_error(ui, "Config error: ", err, hints)?;
-            writeln!(
-                ui.stderr_formatter().labeled("hint"),
-                "For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`."
-            )?;
+            writeln!(ui.stderr_formatter().labeled("hint"), "{CONFIG_ERROR_HINT}")?;
             Ok(1)
         }
         CommandErrorKind::Cli => {
@@ -1024,6 +1076,72 @@ fn try_handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> i
     }
 }
 
+/// Prints the error as a single line of JSON to stderr. Exit codes are the
+/// same as the text output.
+fn handle_command_error_as_json(ui: &mut Ui, cmd_err: &CommandError) -> io::Result<u8> {
+    #[derive(serde::Serialize)]
+    struct ErrorItem<'a> {
+        r#type: &'static str,
+        kind: &'static str,
+        message: String,
+        sources: Vec<String>,
+        hints: Vec<Cow<'a, str>>,
+    }
+
+    let err = &cmd_err.error;
+    let mut hints = cmd_err
+        .hints
+        .iter()
+        .map(|hint| match hint {
+            ErrorHint::PlainText(message) => Cow::Borrowed(message.as_str()),
+            ErrorHint::Formatted(recorded) => {
+                let text = String::from_utf8_lossy(recorded.data());
+                Cow::Owned(text.trim_end().to_owned())
+            }
+        })
+        .collect_vec();
+    let (kind, exit_code) = match cmd_err.kind {
+        CommandErrorKind::User => ("user", 1),
+        CommandErrorKind::Config => {
+            hints.push(Cow::Borrowed(CONFIG_ERROR_HINT));
+            ("config", 1)
+        }
+        CommandErrorKind::Cli => {
+            if let Some(err) = err.downcast_ref::<clap::Error>()
+                && matches!(
+                    err.kind(),
+                    clap::error::ErrorKind::DisplayHelp
+                        | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+                        | clap::error::ErrorKind::DisplayVersion
+                )
+            {
+                return handle_clap_error(ui, err, &cmd_err.hints);
+            }
+            ("cli", 2)
+        }
+        CommandErrorKind::BrokenPipe => return Ok(BROKEN_PIPE_EXIT_CODE),
+        CommandErrorKind::Internal => ("internal", 255),
+    };
+    // clap errors are rendered with their own "error: " prefix and usage.
+    let message = match err.downcast_ref::<clap::Error>() {
+        Some(err) => err.render().to_string().trim_end().to_owned(),
+        None => err.to_string(),
+    };
+    let item = ErrorItem {
+        r#type: "error",
+        kind,
+        message,
+        sources: iter::successors(err.source(), |&err| err.source())
+            .map(|err| err.to_string())
+            .collect(),
+        hints,
+    };
+    let mut stderr = ui.stderr();
+    serde_json::to_writer(&mut stderr, &item)?;
+    writeln!(stderr)?;
+    Ok(exit_code)
+}
+
 fn print_error(
     ui: &Ui,
     heading: &str,
@@ -0,0 +1,40 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tracing::instrument;
+
+use super::session::BisectSession;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show the steps of the bisection session
+///
+/// The output can be saved to a file and passed to `jj bisect replay` to
+/// restore the session later, possibly after editing it.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectLogArgs {}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_bisect_log(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectLogArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui).await?;
+    let session = BisectSession::load_existing(&workspace_command)?;
+    ui.request_pager();
+    session.write_log(&mut ui.stdout())?;
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::object_id::ObjectId as _;
+use tracing::instrument;
+
+use super::session::BisectMark;
+use super::session::BisectMarkKind;
+use super::session::BisectSession;
+use super::session::check_can_mark;
+use super::session::parse_commit_id;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Mark a revision as good and continue the bisection
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectGoodArgs {
+    /// The revision to mark (default: the revision being evaluated)
+    #[arg(value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: Option<RevisionArg>,
+}
+
+/// Mark a revision as bad and continue the bisection
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectBadArgs {
+    /// The revision to mark (default: the revision being evaluated)
+    #[arg(value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: Option<RevisionArg>,
+}
+
+/// Skip a revision that can't be tested and continue the bisection
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectSkipArgs {
+    /// The revision to skip (default: the revision being evaluated)
+    #[arg(value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: Option<RevisionArg>,
+}
+
+pub(crate) async fn cmd_bisect_good(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectGoodArgs,
+) -> Result<(), CommandError> {
+    mark_and_advance(ui, command, args.revision.as_ref(), BisectMarkKind::Good).await
+}
+
+pub(crate) async fn cmd_bisect_bad(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectBadArgs,
+) -> Result<(), CommandError> {
+    mark_and_advance(ui, command, args.revision.as_ref(), BisectMarkKind::Bad).await
+}
+
+pub(crate) async fn cmd_bisect_skip(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectSkipArgs,
+) -> Result<(), CommandError> {
+    mark_and_advance(ui, command, args.revision.as_ref(), BisectMarkKind::Skip).await
+}
+
+#[instrument(skip_all)]
+async fn mark_and_advance(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    revision: Option<&RevisionArg>,
+    kind: BisectMarkKind,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    let mut session = BisectSession::load_existing(&workspace_command)?;
+    let commit_id = if let Some(revision) = revision {
+        workspace_command
+            .resolve_single_rev(ui, revision)
+            .await?
+            .id()
+            .clone()
+    } else if let Some(current) = &session.current {
+        parse_commit_id(current)?
+    } else {
+        return Err(user_error("No revision is being evaluated")
+            .hinted("Specify the revision to mark, or use `jj bisect reset` to end the session."));
+    };
+    {
+        let repo = workspace_command.repo().clone();
+        let bisector = session
+            .bisector(ui, &workspace_command, repo.as_ref())
+            .await?;
+        check_can_mark(&bisector, &commit_id, kind)?;
+    }
+    session.marks.push(BisectMark {
+        commit: commit_id.hex(),
+        kind,
+    });
+    session.advance(ui, &mut workspace_command).await
+}
@@ -12,16 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod log;
+mod mark;
+mod replay;
+mod reset;
 mod run;
+mod session;
+mod start;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Find a bad revision by bisection.
+///
+/// Use `jj bisect run` to automatically test revisions with a command, or `jj
+/// bisect start` to test them manually.
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum BisectCommand {
+    Bad(mark::BisectBadArgs),
+    Good(mark::BisectGoodArgs),
+    Log(log::BisectLogArgs),
+    Replay(replay::BisectReplayArgs),
+    Reset(reset::BisectResetArgs),
     Run(run::BisectRunArgs),
+    Skip(mark::BisectSkipArgs),
+    Start(start::BisectStartArgs),
 }
 
 pub async fn cmd_bisect(
@@ -30,6 +46,13 @@ pub async fn cmd_bisect(
     subcommand: &BisectCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        BisectCommand::Bad(args) => mark::cmd_bisect_bad(ui, command, args).await,
+        BisectCommand::Good(args) => mark::cmd_bisect_good(ui, command, args).await,
+        BisectCommand::Log(args) => log::cmd_bisect_log(ui, command, args).await,
+        BisectCommand::Replay(args) => replay::cmd_bisect_replay(ui, command, args).await,
+        BisectCommand::Reset(args) => reset::cmd_bisect_reset(ui, command, args).await,
         BisectCommand::Run(args) => run::cmd_bisect_run(ui, command, args).await,
+        BisectCommand::Skip(args) => mark::cmd_bisect_skip(ui, command, args).await,
+        BisectCommand::Start(args) => start::cmd_bisect_start(ui, command, args).await,
     }
 }
@@ -0,0 +1,55 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use tracing::instrument;
+
+use super::session::BisectSession;
+use super::session::read_log;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+/// Restore a bisection session from a log
+///
+/// Starts a new session from the output of `jj bisect log`, applying all the
+/// recorded marks, and checks out the next revision to test.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectReplayArgs {
+    /// File containing the output of `jj bisect log`
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    file: PathBuf,
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_bisect_replay(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectReplayArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    if BisectSession::load(&workspace_command)?.is_some() {
+        return Err(user_error("A bisection is already in progress")
+            .hinted("Use `jj bisect reset` to end it first."));
+    }
+    let log = read_log(&args.file)?;
+    let mut session = BisectSession::new(&workspace_command, log.range);
+    // Evaluate the range at the operation the original session started at, not
+    // at the current one.
+    session.start_operation = log.start_operation;
+    session.marks = log.marks;
+    session.advance(ui, &mut workspace_command).await
+}
@@ -0,0 +1,66 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::TryStreamExt as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use tracing::instrument;
+
+use super::session::BisectSession;
+use super::session::parse_commit_id;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// End the bisection session
+///
+/// The revision that was the working copy when `jj bisect start` was run is
+/// edited again, if it still exists.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectResetArgs {}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_bisect_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectResetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    let session = BisectSession::load_existing(&workspace_command)?;
+    BisectSession::remove(&workspace_command)?;
+
+    let Some(original) = &session.original_working_copy else {
+        return Ok(());
+    };
+    let original_id = parse_commit_id(original)?;
+    if workspace_command.get_wc_commit_id() == Some(&original_id) {
+        return Ok(());
+    }
+    let repo = workspace_command.repo().clone();
+    let is_visible = RevsetExpression::commit(original_id.clone())
+        .intersection(&RevsetExpression::visible_heads().ancestors())
+        .evaluate(repo.as_ref())?
+        .stream()
+        .try_next()
+        .await?
+        .is_some();
+    if !is_visible {
+        return Ok(());
+    }
+    let original = repo.store().get_commit_async(&original_id).await?;
+    let mut tx = workspace_command.start_transaction();
+    tx.edit(&original)?;
+    tx.finish(ui, "end bisection").await?;
+    Ok(())
+}
@@ -13,21 +13,22 @@
 // limitations under the License.
 
 use clap_complete::ArgValueCompleter;
-use jj_lib::bisect::BisectionResult;
 use jj_lib::bisect::Bisector;
 use jj_lib::bisect::Evaluation;
 use jj_lib::commit::Commit;
 use jj_lib::object_id::ObjectId as _;
 use tracing::instrument;
 
+use super::session::check_out_for_evaluation;
+use super::session::write_bisection_progress;
+use super::session::write_bisection_result;
+use super::session::write_restore_hint;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
-use crate::cli_util::short_operation_hash;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
 use crate::command_error::internal_error_with_message;
-use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
 use crate::complete;
 use crate::config::CommandNameAndArgs;
@@ -136,29 +137,7 @@ pub(crate) async fn cmd_bisect_run(
     let bisection_result = loop {
         match bisector.next_step().await? {
             jj_lib::bisect::NextStep::Evaluate(commit) => {
-                {
-                    let mut formatter = ui.stdout_formatter();
-                    let (lower, upper) = bisector.remaining_revset().await?.count_estimate()?;
-                    let lower_steps = ((lower + 1) as f64).log2().ceil() as usize;
-                    if upper == Some(lower) {
-                        writeln!(
-                            formatter,
-                            "Bisecting: {lower} revisions left to test after this (roughly \
-                             {lower_steps} steps)"
-                        )?;
-                    } else {
-                        writeln!(
-                            formatter,
-                            "Bisecting: at least {lower} revisions left to test after this (at \
-                             least roughly {lower_steps} steps)"
-                        )?;
-                    }
-                    // TODO: Show a graph of the current range instead?
-                    let commit_template = workspace_command.commit_summary_template();
-                    write!(formatter, "Now evaluating: ")?;
-                    commit_template.format(&commit, formatter.as_mut())?;
-                    writeln!(formatter)?;
-                }
+                write_bisection_progress(ui, &workspace_command, &bisector, &commit).await?;
 
                 let cmd = get_command(args);
                 let evaluation = evaluate_commit(ui, &mut workspace_command, cmd, &commit).await?;
@@ -197,42 +176,9 @@ pub(crate) async fn cmd_bisect_run(
         }
     };
 
-    let mut formatter = ui.stdout_formatter();
-    writeln!(
-        formatter,
-        "Search complete. To discard any revisions created during search, run:"
-    )?;
-    writeln!(
-        formatter,
-        "  jj op restore {}",
-        short_operation_hash(initial_repo.op_id())
-    )?;
-
+    write_restore_hint(ui, initial_repo.op_id())?;
     let target = if args.find_good { "good" } else { "bad" };
-    match bisection_result {
-        BisectionResult::Abort => {
-            return Err(user_error("Bisection aborted"));
-        }
-        BisectionResult::Indeterminate => {
-            return Err(user_error(format!(
-                "Could not find the first {target} revision. Was the input range empty?"
-            )));
-        }
-        BisectionResult::Found(first_target_commits) => {
-            let commit_template = workspace_command.commit_summary_template();
-            if let [first_target_commit] = first_target_commits.as_slice() {
-                write!(formatter, "The first {target} revision is: ")?;
-                commit_template.format(first_target_commit, formatter.as_mut())?;
-                writeln!(formatter)?;
-            } else {
-                writeln!(formatter, "The first {target} revisions are:")?;
-                for first_target_commit in first_target_commits {
-                    commit_template.format(&first_target_commit, formatter.as_mut())?;
-                    writeln!(formatter)?;
-                }
-            }
-        }
-    }
+    write_bisection_result(ui, &workspace_command, bisection_result, target)?;
 
     Ok(())
 }
@@ -253,14 +199,8 @@ async fn evaluate_commit(
     mut cmd: std::process::Command,
     commit: &Commit,
 ) -> Result<Evaluation, CommandError> {
-    let mut tx = workspace_command.start_transaction();
+    check_out_for_evaluation(ui, workspace_command, commit).await?;
     let commit_id_hex = commit.id().hex();
-    tx.check_out(commit)?;
-    tx.finish(
-        ui,
-        format!("Updated to revision {commit_id_hex} for bisection"),
-    )
-    .await?;
 
     let jj_executable_path = std::env::current_exe().map_err(|err| {
         internal_error_with_message("Could not get path for the jj executable", err)
@@ -0,0 +1,449 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State of a manual (`jj bisect start`) bisection session.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectionResult;
+use jj_lib::bisect::Bisector;
+use jj_lib::bisect::Evaluation;
+use jj_lib::bisect::NextStep;
+use jj_lib::commit::Commit;
+use jj_lib::file_util::persist_temp_file;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::OperationId;
+use jj_lib::repo::Repo;
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::short_operation_hash;
+use crate::command_error::CommandError;
+use crate::command_error::internal_error_with_message;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::ui::Ui;
+
+/// How a commit was marked by the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum BisectMarkKind {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMarkKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Skip => "skip",
+        }
+    }
+
+    fn to_evaluation(self) -> Evaluation {
+        match self {
+            Self::Good => Evaluation::Good,
+            Self::Bad => Evaluation::Bad,
+            Self::Skip => Evaluation::Skip,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BisectMark {
+    /// Hex-encoded commit ID.
+    pub commit: String,
+    pub kind: BisectMarkKind,
+}
+
+/// Bisection session persisted in `.jj/repo/bisect/` between commands.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BisectSession {
+    /// Revsets passed to `jj bisect start --range`.
+    pub range: Vec<String>,
+    /// Hex-encoded operation ID at which `range` is evaluated.
+    pub start_operation: String,
+    /// Hex-encoded working-copy commit ID when the session was started.
+    pub original_working_copy: Option<String>,
+    /// Hex-encoded ID of the commit that is currently being evaluated.
+    pub current: Option<String>,
+    /// Commits marked by the user, in order.
+    pub marks: Vec<BisectMark>,
+}
+
+impl BisectSession {
+    pub fn new(workspace_command: &WorkspaceCommandHelper, range: Vec<String>) -> Self {
+        let repo = workspace_command.repo();
+        let original_working_copy = workspace_command
+            .get_wc_commit_id()
+            .map(|commit_id| commit_id.hex());
+        Self {
+            range,
+            start_operation: repo.op_id().hex(),
+            original_working_copy,
+            current: None,
+            marks: vec![],
+        }
+    }
+
+    fn state_path(workspace_command: &WorkspaceCommandHelper) -> PathBuf {
+        workspace_command
+            .repo_path()
+            .join("bisect")
+            .join("state.json")
+    }
+
+    /// Loads the session in progress, if any.
+    pub fn load(workspace_command: &WorkspaceCommandHelper) -> Result<Option<Self>, CommandError> {
+        let path = Self::state_path(workspace_command);
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(internal_error_with_message(
+                    format!("Failed to read bisection state from {}", path.display()),
+                    err,
+                ));
+            }
+        };
+        let session = serde_json::from_slice(&content).map_err(|err| {
+            internal_error_with_message(
+                format!("Failed to parse bisection state in {}", path.display()),
+                err,
+            )
+        })?;
+        Ok(Some(session))
+    }
+
+    /// Loads the session in progress, or fails if there's none.
+    pub fn load_existing(workspace_command: &WorkspaceCommandHelper) -> Result<Self, CommandError> {
+        Self::load(workspace_command)?.ok_or_else(|| {
+            user_error("No bisection in progress").hinted("Use `jj bisect start` to start one.")
+        })
+    }
+
+    pub fn save(&self, workspace_command: &WorkspaceCommandHelper) -> Result<(), CommandError> {
+        let path = Self::state_path(workspace_command);
+        let dir = path.parent().unwrap();
+        let write = || -> io::Result<()> {
+            fs::create_dir_all(dir)?;
+            let mut temp_file = NamedTempFile::new_in(dir)?;
+            serde_json::to_writer_pretty(temp_file.as_file_mut(), self)?;
+            temp_file.as_file_mut().write_all(b"\n")?;
+            persist_temp_file(temp_file, &path)?;
+            Ok(())
+        };
+        write().map_err(|err| {
+            internal_error_with_message(
+                format!("Failed to write bisection state to {}", path.display()),
+                err,
+            )
+        })
+    }
+
+    pub fn remove(workspace_command: &WorkspaceCommandHelper) -> Result<(), CommandError> {
+        let path = Self::state_path(workspace_command);
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(internal_error_with_message(
+                format!("Failed to remove bisection state {}", path.display()),
+                err,
+            )),
+        }
+    }
+
+    /// Revset selecting the input range as it was when the session started.
+    fn range_revset(&self) -> RevisionArg {
+        let range = self
+            .range
+            .iter()
+            .map(|expr| format!("({expr})"))
+            .join(" | ");
+        RevisionArg::from(format!("at_operation({}, {range})", self.start_operation))
+    }
+
+    /// Creates a bisector with all marks of this session applied.
+    pub async fn bisector<'repo>(
+        &self,
+        ui: &Ui,
+        workspace_command: &WorkspaceCommandHelper,
+        repo: &'repo dyn Repo,
+    ) -> Result<Bisector<'repo>, CommandError> {
+        let input_range = workspace_command
+            .parse_revset(ui, &self.range_revset())?
+            .resolve()?;
+        let mut bisector = Bisector::new(repo, input_range).await?;
+        for mark in &self.marks {
+            let commit_id = parse_commit_id(&mark.commit)?;
+            check_can_mark(&bisector, &commit_id, mark.kind)?;
+            bisector.mark(commit_id, mark.kind.to_evaluation());
+        }
+        Ok(bisector)
+    }
+
+    /// Finds the next commit to evaluate and checks it out, or reports the
+    /// result if bisection is complete.
+    pub async fn advance(
+        &mut self,
+        ui: &Ui,
+        workspace_command: &mut WorkspaceCommandHelper,
+    ) -> Result<(), CommandError> {
+        let repo = workspace_command.repo().clone();
+        let mut bisector = self.bisector(ui, workspace_command, repo.as_ref()).await?;
+        match bisector.next_step().await? {
+            NextStep::Evaluate(commit) => {
+                write_bisection_progress(ui, workspace_command, &bisector, &commit).await?;
+                drop(bisector);
+                self.current = Some(commit.id().hex());
+                self.save(workspace_command)?;
+                check_out_for_evaluation(ui, workspace_command, &commit).await?;
+                writeln!(
+                    ui.hint_default(),
+                    "Test the revision and mark it with `jj bisect good`, `jj bisect bad`, or `jj \
+                     bisect skip`."
+                )?;
+            }
+            NextStep::Done(result) => {
+                self.current = None;
+                self.save(workspace_command)?;
+                write_bisection_result(ui, workspace_command, result, "bad")?;
+                writeln!(
+                    ui.hint_default(),
+                    "Use `jj bisect reset` to end the bisection session."
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the session as a sequence of commands that can be passed to
+    /// `jj bisect replay`.
+    pub fn write_log(&self, output: &mut dyn io::Write) -> io::Result<()> {
+        let range_args = self
+            .range
+            .iter()
+            .map(|expr| format!("--range={}", shlex::try_quote(expr).unwrap_or(expr.into())))
+            .join(" ");
+        writeln!(output, "# start operation: {}", self.start_operation)?;
+        writeln!(output, "jj bisect start {range_args}")?;
+        for mark in &self.marks {
+            writeln!(output, "jj bisect {} {}", mark.kind.name(), mark.commit)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse_commit_id(hex: &str) -> Result<CommitId, CommandError> {
+    CommitId::try_from_hex(hex)
+        .ok_or_else(|| user_error(format!("Invalid commit ID in bisection state: {hex}")))
+}
+
+/// Fails if the commit has already been marked (or assumed to be) something
+/// else.
+pub(crate) fn check_can_mark(
+    bisector: &Bisector<'_>,
+    commit_id: &CommitId,
+    kind: BisectMarkKind,
+) -> Result<(), CommandError> {
+    let existing = if bisector.good_commits().contains(commit_id) {
+        Some(BisectMarkKind::Good)
+    } else if bisector.bad_commits().contains(commit_id) {
+        Some(BisectMarkKind::Bad)
+    } else if bisector.skipped_commits().contains(commit_id) {
+        Some(BisectMarkKind::Skip)
+    } else {
+        None
+    };
+    match existing {
+        Some(existing) if existing != kind => Err(user_error(format!(
+            "Commit {commit_id:.12} is already marked as {}",
+            existing.name()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) async fn write_bisection_progress(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    bisector: &Bisector<'_>,
+    commit: &Commit,
+) -> Result<(), CommandError> {
+    let mut formatter = ui.stdout_formatter();
+    let (lower, upper) = bisector.remaining_revset().await?.count_estimate()?;
+    let lower_steps = ((lower + 1) as f64).log2().ceil() as usize;
+    if upper == Some(lower) {
+        writeln!(
+            formatter,
+            "Bisecting: {lower} revisions left to test after this (roughly {lower_steps} steps)"
+        )?;
+    } else {
+        writeln!(
+            formatter,
+            "Bisecting: at least {lower} revisions left to test after this (at least roughly \
+             {lower_steps} steps)"
+        )?;
+    }
+    // TODO: Show a graph of the current range instead?
+    let commit_template = workspace_command.commit_summary_template();
+    write!(formatter, "Now evaluating: ")?;
+    commit_template.format(commit, formatter.as_mut())?;
+    writeln!(formatter)?;
+    Ok(())
+}
+
+pub(crate) fn write_bisection_result(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    result: BisectionResult,
+    target: &str,
+) -> Result<(), CommandError> {
+    let mut formatter = ui.stdout_formatter();
+    match result {
+        BisectionResult::Abort => Err(user_error("Bisection aborted")),
+        BisectionResult::Indeterminate => Err(user_error(format!(
+            "Could not find the first {target} revision. Was the input range empty?"
+        ))),
+        BisectionResult::Found(first_target_commits) => {
+            let commit_template = workspace_command.commit_summary_template();
+            if let [first_target_commit] = first_target_commits.as_slice() {
+                write!(formatter, "The first {target} revision is: ")?;
+                commit_template.format(first_target_commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            } else {
+                writeln!(formatter, "The first {target} revisions are:")?;
+                for first_target_commit in first_target_commits {
+                    commit_template.format(&first_target_commit, formatter.as_mut())?;
+                    writeln!(formatter)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Makes `commit` the parent of the working-copy commit.
+pub(crate) async fn check_out_for_evaluation(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    commit: &Commit,
+) -> Result<(), CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    let commit_id_hex = commit.id().hex();
+    tx.check_out(commit)?;
+    tx.finish(
+        ui,
+        format!("Updated to revision {commit_id_hex} for bisection"),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Prints how to discard the revisions created since `op_id`.
+pub(crate) fn write_restore_hint(ui: &Ui, op_id: &OperationId) -> io::Result<()> {
+    let mut formatter = ui.stdout_formatter();
+    writeln!(
+        formatter,
+        "Search complete. To discard any revisions created during search, run:"
+    )?;
+    writeln!(formatter, "  jj op restore {}", short_operation_hash(op_id))?;
+    Ok(())
+}
+
+/// Session recorded by [`BisectSession::write_log()`].
+pub(crate) struct BisectLog {
+    pub range: Vec<String>,
+    /// Hex-encoded operation ID at which the session was started.
+    pub start_operation: String,
+    pub marks: Vec<BisectMark>,
+}
+
+/// Reads a session log written by [`BisectSession::write_log()`].
+pub(crate) fn read_log(path: &Path) -> Result<BisectLog, CommandError> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        user_error_with_message(format!("Failed to read {}", path.display()), err)
+    })?;
+    let mut range = None;
+    let mut start_operation = None;
+    let mut marks = vec![];
+    for (line_number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(op_id) = line.strip_prefix("# start operation:") {
+            let op_id = op_id.trim();
+            if OperationId::try_from_hex(op_id).is_none() {
+                return Err(user_error(format!(
+                    "Invalid operation ID in bisection log line {}: {op_id}",
+                    line_number + 1
+                )));
+            }
+            start_operation = Some(op_id.to_owned());
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid_line = || {
+            user_error(format!(
+                "Invalid bisection log line {}: {line}",
+                line_number + 1
+            ))
+        };
+        let words = shlex::split(line).ok_or_else(invalid_line)?;
+        let words = words.iter().map(String::as_str).collect_vec();
+        match words.as_slice() {
+            ["jj", "bisect", "start", args @ ..] => {
+                let exprs = args
+                    .iter()
+                    .map(|arg| arg.strip_prefix("--range=").map(str::to_owned))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid_line)?;
+                range = Some(exprs);
+            }
+            ["jj", "bisect", kind @ ("good" | "bad" | "skip"), commit] if range.is_some() => {
+                let kind = match *kind {
+                    "good" => BisectMarkKind::Good,
+                    "bad" => BisectMarkKind::Bad,
+                    _ => BisectMarkKind::Skip,
+                };
+                parse_commit_id(commit)?;
+                marks.push(BisectMark {
+                    commit: (*commit).to_owned(),
+                    kind,
+                });
+            }
+            _ => return Err(invalid_line()),
+        }
+    }
+    let range =
+        range.ok_or_else(|| user_error("The bisection log has no `jj bisect start` line"))?;
+    let start_operation = start_operation
+        .ok_or_else(|| user_error("The bisection log has no `# start operation:` line"))?;
+    Ok(BisectLog {
+        range,
+        start_operation,
+        marks,
+    })
+}
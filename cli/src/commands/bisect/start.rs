@@ -0,0 +1,70 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use tracing::instrument;
+
+use super::session::BisectSession;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Start a manual bisection session
+///
+/// Checks out a revision in the middle of the given range. Test it, then mark
+/// it with `jj bisect good`, `jj bisect bad`, or `jj bisect skip` to move on to
+/// the next revision to test. Use `jj bisect reset` when you're done.
+///
+/// The session is saved in the repo, so you can run other commands between
+/// the steps.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectStartArgs {
+    /// Range of revisions to bisect (can be repeated)
+    ///
+    /// This is typically a range like `v1.0..main`. The heads of the range are
+    /// assumed to be bad. Ancestors of the range that are not also in the range
+    /// are assumed to be good.
+    ///
+    /// The union of all given ranges are used as the input for the bisection.
+    #[arg(long, short, value_name = "REVSETS", required = true)]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    range: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_bisect_start(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectStartArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    if BisectSession::load(&workspace_command)?.is_some() {
+        return Err(user_error("A bisection is already in progress")
+            .hinted("Use `jj bisect reset` to end it first."));
+    }
+    // Check that the range is valid before saving it.
+    workspace_command
+        .parse_union_revsets(ui, &args.range)?
+        .resolve()?;
+    let range = args
+        .range
+        .iter()
+        .map(|arg| arg.as_ref().to_owned())
+        .collect();
+    let mut session = BisectSession::new(&workspace_command, range);
+    session.advance(ui, &mut workspace_command).await
+}
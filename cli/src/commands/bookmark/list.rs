@@ -23,6 +23,7 @@ use jj_lib::str_util::StringExpression;
 
 use super::warn_unmatched_local_or_remote_bookmarks;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::JSON_LINE_TEMPLATE_TEXT;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::default_ignored_remote_name;
 use crate::command_error::CommandError;
@@ -33,6 +34,7 @@ use crate::commit_templater::CommitRef;
 use crate::complete;
 use crate::revset_util::parse_union_name_patterns;
 use crate::templater::TemplateRenderer;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 /// List bookmarks and their targets
@@ -153,6 +155,7 @@ pub async fn cmd_bookmark_list(
     let template: TemplateRenderer<Rc<CommitRef>> = {
         let language = workspace_command.commit_template_language();
         let text = match &args.template {
+            _ if ui.output_format() == OutputFormat::Json => JSON_LINE_TEMPLATE_TEXT.to_owned(),
             Some(value) => value.to_owned(),
             None => workspace_command
                 .settings()
@@ -0,0 +1,460 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::io::Write as _;
+
+use bstr::ByteSlice as _;
+use clap_complete::ArgValueCompleter;
+use futures::future::try_join_all;
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::Signature;
+use jj_lib::commit::Commit;
+use jj_lib::conflict_labels::ConflictLabels;
+use jj_lib::conflicts::ConflictMaterializeOptions;
+use jj_lib::conflicts::choose_materialized_conflict_marker_len;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::parse_conflict;
+use jj_lib::converge::ConvergeResult;
+use jj_lib::converge::ConvergedAttribute;
+use jj_lib::converge::TreeIdsAndLabels;
+use jj_lib::converge::TruncatedEvolutionGraph;
+use jj_lib::converge::apply_solution;
+use jj_lib::converge::converge_change;
+use jj_lib::converge::find_divergent_changes;
+use jj_lib::merge::MergeBuilder;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::short_change_hash;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::description_util::TextEditor;
+use crate::description_util::edit_description;
+use crate::formatter::Formatter;
+use crate::ui::Ui;
+
+/// Resolve divergent changes
+///
+/// Finds changes that have more than one visible commit ("divergent changes")
+/// among the given revisions, and replaces the divergent commits of each such
+/// change with a single new commit. The author, description, parents and
+/// contents of the new commit are merged from the divergent commits, using
+/// their evolution history to find a common base.
+///
+/// You are only asked for input when an attribute cannot be merged
+/// automatically: conflicting descriptions are opened in the editor with
+/// conflict markers, and conflicting authors or parents are chosen from a
+/// prompt. Conflicting file contents are kept as conflicts in the new commit.
+///
+/// Descendants of the divergent commits are rebased onto the new commit.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ConvergeArgs {
+    /// Converge divergent changes among these revisions
+    ///
+    /// If not provided, this defaults to the `revsets.converge` setting, or
+    /// `mutable()` if it is not set.
+    #[arg(long = "revision", short, value_name = "REVSETS", alias = "revisions")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
+    revisions: Vec<RevisionArg>,
+
+    /// Only print the proposed solutions without modifying the repo
+    ///
+    /// Attributes that cannot be merged automatically are reported as
+    /// unresolved instead of prompting for input.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn cmd_converge(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConvergeArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    let target_expr = if args.revisions.is_empty() {
+        let revs = workspace_command
+            .settings()
+            .get_string("revsets.converge")?;
+        workspace_command.parse_revset(ui, &RevisionArg::from(revs))?
+    } else {
+        workspace_command.parse_union_revsets(ui, &args.revisions)?
+    }
+    .resolve()?;
+    let repo = workspace_command.repo().clone();
+    let divergent_changes = find_divergent_changes(&repo, target_expr).await?;
+    if divergent_changes.is_empty() {
+        writeln!(ui.status(), "No divergent changes found.")?;
+        return Ok(());
+    }
+
+    // Process changes and commits in a stable order so that the output (and
+    // the tie-breaking done by the converge algorithm) doesn't depend on hash
+    // map iteration order. Newer commits come first, like in `jj log`.
+    let divergent_changes = divergent_changes
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(change_id, commits)| {
+            let commits = commits
+                .into_values()
+                .sorted_by_key(|commit| {
+                    (
+                        Reverse(commit.committer().timestamp.timestamp),
+                        commit.id().clone(),
+                    )
+                })
+                .collect_vec();
+            (change_id, commits)
+        })
+        .collect_vec();
+    if !args.dry_run {
+        workspace_command
+            .check_rewritable(
+                divergent_changes
+                    .iter()
+                    .flat_map(|(_, commits)| commits.iter().map(|commit| commit.id())),
+            )
+            .await?;
+    }
+
+    let mut proposals = vec![];
+    for (change_id, commits) in divergent_changes {
+        let graph = TruncatedEvolutionGraph::new(repo.clone(), commits).await?;
+        let result = converge_change(&graph, None, None, None, None).await?;
+        proposals.push((change_id, graph, result));
+    }
+
+    if args.dry_run {
+        if let Some(mut formatter) = ui.status_formatter() {
+            for (change_id, graph, result) in &proposals {
+                write_proposal(
+                    formatter.as_mut(),
+                    &workspace_command,
+                    change_id,
+                    graph,
+                    result,
+                )
+                .await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let text_editor = workspace_command.text_editor()?;
+    let mut solutions = vec![];
+    for (change_id, graph, result) in proposals {
+        let solution =
+            complete_solution(ui, &workspace_command, &text_editor, &graph, result).await?;
+        solutions.push((change_id, graph, solution));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let mut num_rebased = 0;
+    let mut converged_commits = vec![];
+    for (change_id, graph, solution) in solutions {
+        let (commit, rebased) = apply_solution(
+            solution.author,
+            solution.description,
+            solution.parents,
+            solution.tree,
+            change_id,
+            graph.divergent_commit_ids(),
+            tx.repo_mut(),
+        )
+        .await?;
+        num_rebased += rebased;
+        converged_commits.push((graph.divergent_commits().len(), commit));
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (num_divergent, commit) in &converged_commits {
+            write!(
+                formatter,
+                "Converged {num_divergent} divergent commits into "
+            )?;
+            tx.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    let num_changes = converged_commits.len();
+    let tx_description = if let [(_, commit)] = converged_commits.as_slice() {
+        format!("converge divergent change {}", commit.change_id())
+    } else {
+        format!("converge {num_changes} divergent changes")
+    };
+    tx.finish(ui, tx_description).await?;
+    Ok(())
+}
+
+/// Fully-resolved attributes of the commit replacing the divergent commits.
+struct Solution {
+    author: Signature,
+    description: String,
+    parents: Vec<CommitId>,
+    tree: TreeIdsAndLabels,
+}
+
+/// Asks the user for the attributes that couldn't be converged automatically.
+async fn complete_solution(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    text_editor: &TextEditor,
+    graph: &TruncatedEvolutionGraph,
+    result: ConvergeResult,
+) -> Result<Solution, CommandError> {
+    let change_hash = short_change_hash(graph.change_id());
+    let author = match result.author {
+        ConvergedAttribute::Solved(author) => author,
+        ConvergedAttribute::Unsolved {
+            excluded_divergent_commits,
+            ..
+        } => {
+            let candidates = included_commits(graph, &excluded_divergent_commits)
+                .map(|commit| commit.author().clone())
+                .unique_by(|author| (author.name.clone(), author.email.clone()))
+                .collect_vec();
+            let choices = candidates
+                .iter()
+                .map(|author| format!("{} <{}>", author.name, author.email))
+                .collect_vec();
+            let index = prompt_for_choice(
+                ui,
+                &format!("The divergent commits of change {change_hash} have different authors"),
+                &choices,
+            )?;
+            candidates[index].clone()
+        }
+    };
+
+    let description = match result.description {
+        ConvergedAttribute::Solved(description) => description,
+        ConvergedAttribute::Unsolved {
+            base_commit,
+            excluded_divergent_commits,
+        } => {
+            let base_commit = graph.repo().store().get_commit_async(&base_commit).await?;
+            edit_conflicting_descriptions(
+                workspace_command,
+                text_editor,
+                &change_hash,
+                &base_commit,
+                included_commits(graph, &excluded_divergent_commits),
+            )?
+        }
+    };
+
+    let (parents, tree) = match (result.parents, result.tree) {
+        (ConvergedAttribute::Solved(parents), Some(tree)) => (parents, tree),
+        (ConvergedAttribute::Solved(_), None) => unreachable!("tree is set if parents are solved"),
+        (
+            ConvergedAttribute::Unsolved {
+                excluded_divergent_commits,
+                ..
+            },
+            _,
+        ) => {
+            let candidates = included_commits(graph, &excluded_divergent_commits)
+                .map(|commit| commit.parent_ids().to_vec())
+                .unique()
+                .collect_vec();
+            let mut choices = vec![];
+            for parent_ids in &candidates {
+                let parents = try_join_all(
+                    parent_ids
+                        .iter()
+                        .map(|id| graph.repo().store().get_commit_async(id)),
+                )
+                .await?;
+                choices.push(
+                    parents
+                        .iter()
+                        .map(|parent| workspace_command.format_commit_summary(parent))
+                        .join(" + "),
+                );
+            }
+            let index = prompt_for_choice(
+                ui,
+                &format!("The divergent commits of change {change_hash} have different parents"),
+                &choices,
+            )?;
+            let parents = candidates[index].clone();
+            // Now that the parents are known, the contents can be merged.
+            let result = converge_change(graph, None, None, Some(parents.clone()), None).await?;
+            (
+                parents,
+                result.tree.expect("tree is set if parents are given"),
+            )
+        }
+    };
+
+    Ok(Solution {
+        author,
+        description,
+        parents,
+        tree,
+    })
+}
+
+fn included_commits<'a>(
+    graph: &'a TruncatedEvolutionGraph,
+    excluded_divergent_commits: &'a HashSet<CommitId>,
+) -> impl Iterator<Item = &'a Commit> {
+    graph
+        .divergent_commits()
+        .iter()
+        .filter(|commit| !excluded_divergent_commits.contains(commit.id()))
+}
+
+/// Prints a numbered list of `choices` and asks the user to pick one. Returns
+/// the index of the chosen item.
+fn prompt_for_choice(ui: &Ui, heading: &str, choices: &[String]) -> Result<usize, CommandError> {
+    if !ui.can_prompt() {
+        return Err(user_error(format!("{heading}.")).hinted(
+            "Run this command in an interactive terminal, or rewrite the divergent commits so \
+             that they agree.",
+        ));
+    }
+    writeln!(ui.stderr(), "{heading}:")?;
+    for (i, choice) in choices.iter().enumerate() {
+        writeln!(ui.stderr(), "{}: {choice}", i + 1)?;
+    }
+    let index = ui.prompt_choice_with("Enter the number of your choice", None, |input| {
+        input
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=choices.len()).contains(n))
+            .map(|n| n - 1)
+            .ok_or("unrecognized response")
+    })?;
+    Ok(index)
+}
+
+/// Opens the conflicting descriptions in the editor, with conflict markers
+/// relative to the description of `base_commit`.
+fn edit_conflicting_descriptions<'a>(
+    workspace_command: &WorkspaceCommandHelper,
+    text_editor: &TextEditor,
+    change_hash: &str,
+    base_commit: &Commit,
+    divergent_commits: impl IntoIterator<Item = &'a Commit>,
+) -> Result<String, CommandError> {
+    // The divergent descriptions are the sides, separated by the base
+    // description: [d1, base, d2, base, ..., dN]
+    let mut builder = MergeBuilder::default();
+    let mut labels = vec![];
+    for (i, commit) in divergent_commits.into_iter().enumerate() {
+        if i > 0 {
+            builder.extend([base_commit.description().to_owned()]);
+            labels.push(format!("base: {}", base_commit.conflict_label()));
+        }
+        builder.extend([commit.description().to_owned()]);
+        labels.push(format!("divergent commit: {}", commit.conflict_label()));
+    }
+    let merge = builder.build();
+    let num_sides = merge.num_sides();
+    let marker_len = choose_materialized_conflict_marker_len(&merge);
+    let options = ConflictMaterializeOptions {
+        marker_style: workspace_command.env().conflict_marker_style(),
+        marker_len: Some(marker_len),
+        merge: workspace_command.repo().store().merge_options().clone(),
+    };
+    let materialized =
+        materialize_merge_result_to_bytes(&merge, &ConflictLabels::from_vec(labels), &options);
+    let mut text = format!(
+        "JJ: The divergent commits of change {change_hash} have conflicting descriptions.\nJJ: \
+         Resolve the conflict below.\n"
+    );
+    text.push_str(&materialized.to_str_lossy());
+    let description = edit_description(text_editor, &text)?;
+    if parse_conflict(description.as_bytes(), num_sides, marker_len).is_some() {
+        return Err(user_error(
+            "The description still contains conflict markers",
+        ));
+    }
+    Ok(description)
+}
+
+async fn write_proposal(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    change_id: &ChangeId,
+    graph: &TruncatedEvolutionGraph,
+    result: &ConvergeResult,
+) -> Result<(), CommandError> {
+    let repo = graph.repo();
+    writeln!(
+        formatter,
+        "Divergent change {} ({} commits):",
+        short_change_hash(change_id),
+        graph.divergent_commits().len()
+    )?;
+    for commit in graph.divergent_commits() {
+        write!(formatter, "  ")?;
+        workspace_command.write_commit_summary(formatter, commit)?;
+        writeln!(formatter)?;
+    }
+    writeln!(formatter, "Proposed solution:")?;
+    match &result.author {
+        ConvergedAttribute::Solved(author) => {
+            writeln!(formatter, "  Author: {} <{}>", author.name, author.email)?;
+        }
+        ConvergedAttribute::Unsolved { .. } => {
+            writeln!(formatter, "  Author: (unresolved, would prompt)")?;
+        }
+    }
+    match &result.description {
+        ConvergedAttribute::Solved(description) => {
+            let first_line = description.lines().next().unwrap_or_default();
+            writeln!(formatter, "  Description: {first_line}")?;
+        }
+        ConvergedAttribute::Unsolved { .. } => {
+            writeln!(formatter, "  Description: (unresolved, would open editor)")?;
+        }
+    }
+    match &result.parents {
+        ConvergedAttribute::Solved(parent_ids) => {
+            let parents = try_join_all(
+                parent_ids
+                    .iter()
+                    .map(|id| repo.store().get_commit_async(id)),
+            )
+            .await?;
+            for parent in &parents {
+                write!(formatter, "  Parent: ")?;
+                workspace_command.write_commit_summary(formatter, parent)?;
+                writeln!(formatter)?;
+            }
+        }
+        ConvergedAttribute::Unsolved { .. } => {
+            writeln!(formatter, "  Parents: (unresolved, would prompt)")?;
+        }
+    }
+    match &result.tree {
+        Some(tree) if tree.tree_ids.is_resolved() => writeln!(formatter, "  Content: merged")?,
+        Some(_) => writeln!(formatter, "  Content: merged with conflicts")?,
+        None => writeln!(formatter, "  Content: (depends on the chosen parents)")?,
+    }
+    Ok(())
+}
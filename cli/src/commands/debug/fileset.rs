@@ -28,6 +28,10 @@ use crate::ui::Ui;
 pub struct DebugFilesetArgs {
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     path: String,
+
+    /// Print the expression rewritten to optimized form
+    #[arg(long)]
+    optimized: bool,
 }
 
 pub async fn cmd_debug_fileset(
@@ -39,12 +43,32 @@ pub async fn cmd_debug_fileset(
 
     let mut diagnostics = FilesetDiagnostics::new();
     let context = workspace_command.env().fileset_parse_context();
-    let expression = fileset::parse_maybe_bare(&mut diagnostics, &args.path, &context)?;
+    let expression =
+        fileset::parse_maybe_bare_with_predicates(&mut diagnostics, &args.path, &context)?;
     print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
+    // Predicates depend on the tree, so there's no matcher to show.
+    let Some(expression) = expression.as_resolved() else {
+        writeln!(ui.stdout(), "-- Parsed:")?;
+        writeln!(ui.stdout(), "{expression:#?}")?;
+        writeln!(ui.stdout())?;
+        if args.optimized {
+            let optimized = expression.optimize();
+            writeln!(ui.stdout(), "-- Optimized:")?;
+            writeln!(ui.stdout(), "{optimized:#?}")?;
+            writeln!(ui.stdout())?;
+        }
+        return Ok(());
+    };
     writeln!(ui.stdout(), "-- Parsed:")?;
     writeln!(ui.stdout(), "{expression:#?}")?;
     writeln!(ui.stdout())?;
 
+    if args.optimized {
+        let optimized = fileset::optimize(expression.clone());
+        writeln!(ui.stdout(), "-- Optimized:")?;
+        writeln!(ui.stdout(), "{optimized:#?}")?;
+        writeln!(ui.stdout())?;
+    }
     let matcher = expression.to_matcher();
     writeln!(ui.stdout(), "-- Matcher:")?;
     writeln!(ui.stdout(), "{matcher:#?}")?;
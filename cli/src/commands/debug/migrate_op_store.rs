@@ -0,0 +1,116 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::file_util::PathError;
+use jj_lib::op_store;
+use jj_lib::op_store::OpStore;
+use jj_lib::op_store::RootOperationData;
+use jj_lib::packed_op_store::PackedOpStore;
+use jj_lib::simple_op_store::SimpleOpStore;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::cli_error;
+use crate::command_error::internal_error;
+use crate::ui::Ui;
+
+/// Migrate the operation log to another operation store type
+///
+/// All operations reachable from the current operation heads are copied to
+/// the new store, and the repo is switched to it. The files of the old store
+/// are left in place. Other jj processes shouldn't be running on the repo
+/// during the migration.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugMigrateOpStoreArgs {
+    /// Type of the new operation store
+    #[arg(value_parser = [SimpleOpStore::name(), PackedOpStore::name()])]
+    store_type: String,
+}
+
+pub async fn cmd_debug_migrate_op_store(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugMigrateOpStoreArgs,
+) -> Result<(), CommandError> {
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let old_op_store = repo_loader.op_store();
+    if old_op_store.name() == args.store_type {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    // Block concurrent updates of the operation heads until the repo is
+    // switched to the new store. Otherwise, operations written to the old
+    // store in the meantime would be lost.
+    let op_heads_store = repo_loader.op_heads_store();
+    let lock = op_heads_store.lock().await?;
+    let op_store_path = workspace.repo_path().join("op_store");
+    let root_data = RootOperationData {
+        root_commit_id: repo_loader.store().root_commit_id().clone(),
+    };
+    let new_op_store = init_or_load_op_store(&args.store_type, &op_store_path, root_data)
+        .map_err(internal_error)?;
+    let head_ids = op_heads_store.get_op_heads().await?;
+    let num_operations =
+        op_store::copy_operations(old_op_store.as_ref(), new_op_store.as_ref(), &head_ids).await?;
+    let type_path = op_store_path.join("type");
+    fs::write(&type_path, new_op_store.name())
+        .context(&type_path)
+        .map_err(internal_error)?;
+    drop(lock);
+
+    writeln!(
+        ui.status(),
+        "Migrated {num_operations} operations to {}.",
+        new_op_store.name()
+    )?;
+    Ok(())
+}
+
+fn init_or_load_op_store(
+    store_type: &str,
+    store_path: &Path,
+    root_data: RootOperationData,
+) -> Result<Box<dyn OpStore>, PathError> {
+    // The store may have been created by a previous migration.
+    let is_already_exists = |err: &PathError| err.source.kind() == io::ErrorKind::AlreadyExists;
+    if store_type == PackedOpStore::name() {
+        match PackedOpStore::init(store_path, root_data.clone()) {
+            Ok(store) => Ok(Box::new(store)),
+            Err(err) if is_already_exists(&err.0) => {
+                Ok(Box::new(PackedOpStore::load(store_path, root_data)))
+            }
+            Err(err) => Err(err.0),
+        }
+    } else {
+        match SimpleOpStore::init(store_path, root_data.clone()) {
+            Ok(store) => Ok(Box::new(store)),
+            Err(err) if is_already_exists(&err.0) => {
+                Ok(Box::new(SimpleOpStore::load(store_path, root_data)))
+            }
+            Err(err) => Err(err.0),
+        }
+    }
+}
@@ -18,6 +18,7 @@ mod index;
 mod index_changed_paths;
 mod init_simple;
 mod local_working_copy;
+mod migrate_op_store;
 mod object;
 mod reindex;
 mod revset;
@@ -44,6 +45,8 @@ use self::init_simple::DebugInitSimpleArgs;
 use self::init_simple::cmd_debug_init_simple;
 use self::local_working_copy::DebugLocalWorkingCopyArgs;
 use self::local_working_copy::cmd_debug_local_working_copy;
+use self::migrate_op_store::DebugMigrateOpStoreArgs;
+use self::migrate_op_store::cmd_debug_migrate_op_store;
 use self::object::DebugObjectArgs;
 use self::object::cmd_debug_object;
 use self::reindex::DebugReindexArgs;
@@ -77,6 +80,7 @@ pub enum DebugCommand {
     IndexChangedPaths(DebugIndexChangedPathsArgs),
     InitSimple(DebugInitSimpleArgs),
     LocalWorkingCopy(DebugLocalWorkingCopyArgs),
+    MigrateOpStore(DebugMigrateOpStoreArgs),
     #[command(subcommand)]
     Object(DebugObjectArgs),
     Reindex(DebugReindexArgs),
@@ -106,6 +110,7 @@ pub async fn cmd_debug(
         DebugCommand::LocalWorkingCopy(args) => {
             cmd_debug_local_working_copy(ui, command, args).await
         }
+        DebugCommand::MigrateOpStore(args) => cmd_debug_migrate_op_store(ui, command, args).await,
         DebugCommand::Object(args) => cmd_debug_object(ui, command, args).await,
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args).await,
         DebugCommand::Revset(args) => cmd_debug_revset(ui, command, args).await,
@@ -23,6 +23,7 @@ use futures::future::try_join_all;
 use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetStreamExt as _;
 use tracing::instrument;
 
@@ -38,6 +39,7 @@ use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
 use crate::description_util::join_message_paragraphs;
 use crate::description_util::parse_trailers_template;
+use crate::hooks::HookEvent;
 use crate::text_util::complete_newline;
 use crate::ui::Ui;
 
@@ -225,7 +227,7 @@ pub(crate) async fn cmd_describe(
         .map(|(old_commit, commit_builder)| (old_commit.id(), commit_builder))
         .collect();
 
-    let mut num_described = 0;
+    let mut described_ids = vec![];
     let mut num_reparented = 0;
     // Even though `MutableRepo::rewrite_commit` and
     // `MutableRepo::rebase_descendants` can handle rewriting of a commit even
@@ -240,11 +242,11 @@ pub(crate) async fn cmd_describe(
                 let old_commit_id = rewriter.old_commit().id().clone();
                 let commit_builder = rewriter.reparent();
                 if let Some(temp_builder) = commit_builders.get(&old_commit_id) {
-                    commit_builder
+                    let new_commit = commit_builder
                         .set_description(temp_builder.description())
                         .write()
                         .await?;
-                    num_described += 1;
+                    described_ids.push(new_commit.id().clone());
                 } else {
                     commit_builder.write().await?;
                     num_reparented += 1;
@@ -253,12 +255,18 @@ pub(crate) async fn cmd_describe(
             },
         )
         .await?;
-    if num_described > 1 {
-        writeln!(ui.status(), "Updated {num_described} commits.")?;
+    if described_ids.len() > 1 {
+        writeln!(ui.status(), "Updated {} commits.", described_ids.len())?;
     }
     if num_reparented > 0 {
         writeln!(ui.status(), "Rebased {num_reparented} descendant commits.")?;
     }
+    tx.run_hooks(
+        ui,
+        HookEvent::PreDescribe,
+        &RevsetExpression::commits(described_ids),
+    )
+    .await?;
     tx.finish(ui, tx_description).await?;
     Ok(())
 }
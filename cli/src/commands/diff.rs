@@ -19,6 +19,7 @@ use futures::future::try_join_all;
 use indexmap::IndexSet;
 use itertools::Itertools as _;
 use jj_lib::copies::CopyRecords;
+use jj_lib::copies::get_copy_records;
 use jj_lib::merge::Diff;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite::merge_commit_trees;
@@ -26,13 +27,12 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
-use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::print_unmatched_paths;
 use crate::cli_util::short_commit_hash;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
-use crate::diff_util::get_copy_records;
 use crate::diff_util::show_templated;
 use crate::ui::Ui;
 
@@ -121,12 +121,13 @@ pub(crate) async fn cmd_diff(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui).await?;
     let repo = workspace_command.repo();
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
-    let matcher = fileset_expression.to_matcher();
+    let fileset_expression =
+        workspace_command.parse_file_patterns_with_predicates(ui, &args.paths)?;
 
     let from_tree;
     let to_tree;
-    let mut copy_records = CopyRecords::default();
+    // Pairs of commits to look up copy records between
+    let mut copy_commit_pairs = vec![];
     if args.from.is_some() || args.to.is_some() {
         let resolve_revision = async |r: &Option<RevisionArg>| {
             workspace_command
@@ -137,9 +138,7 @@ pub(crate) async fn cmd_diff(
         let to = resolve_revision(&args.to).await?;
         from_tree = from.tree();
         to_tree = to.tree();
-
-        let records = get_copy_records(repo.store(), from.id(), to.id(), &matcher).await?;
-        copy_records.add_records(records);
+        copy_commit_pairs.push((from.id().clone(), to.id().clone()));
     } else {
         let revision_args = args
             .revisions
@@ -186,12 +185,21 @@ pub(crate) async fn cmd_diff(
 
         for p in &parents {
             for to in &heads {
-                let records = get_copy_records(repo.store(), p.id(), to.id(), &matcher).await?;
-                copy_records.add_records(records);
+                copy_commit_pairs.push((p.id().clone(), to.id().clone()));
             }
         }
     }
 
+    let matcher = fileset_expression
+        .resolve_predicates(&[from_tree.clone(), to_tree.clone()])
+        .await?
+        .to_matcher();
+    let mut copy_records = CopyRecords::default();
+    for (from_id, to_id) in &copy_commit_pairs {
+        let records = get_copy_records(repo.store(), from_id, to_id, &matcher).await?;
+        copy_records.add_records(records);
+    }
+
     // -T disables both short/long rendering formats, but it might be okay to
     // enable long format if explicitly specified (assuming -T is for short or
     // summary output.)
@@ -224,10 +232,10 @@ pub(crate) async fn cmd_diff(
             ui.term_width(),
         )
         .await?;
-    print_unmatched_explicit_paths(
+    print_unmatched_paths(
         ui,
         &workspace_command,
-        &fileset_expression,
+        fileset_expression.explicit_paths(),
         [&from_tree, &to_tree],
     )?;
     Ok(())
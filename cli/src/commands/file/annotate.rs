@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
+
+use bstr::BString;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::annotate::FileAnnotator;
 use jj_lib::annotate::LineOrigin;
+use jj_lib::backend::CommitId;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use tracing::instrument;
@@ -27,6 +31,7 @@ use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::commit_templater::AnnotationLine;
 use crate::complete;
+use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
@@ -46,6 +51,22 @@ pub(crate) struct FileAnnotateArgs {
     #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
     revision: Option<RevisionArg>,
 
+    /// Only search for the origins of lines in these revisions
+    ///
+    /// Lines that originate from revisions outside the domain are attributed
+    /// to the root commit.
+    #[arg(long, value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    domain: Option<RevisionArg>,
+
+    /// Only annotate lines in the given range
+    ///
+    /// Line numbers are 1-based, and both `START` and `END` lines are
+    /// included. The range is clamped to the end of the file.
+    #[arg(long = "lines", short = 'L', value_name = "START,END")]
+    #[arg(value_parser = parse_line_range)]
+    line_range: Option<Range<usize>>,
+
     /// Render each line using the given template
     ///
     /// All 0-argument methods of the [`AnnotationLine` type] are available as
@@ -98,54 +119,158 @@ pub(crate) async fn cmd_file_annotate(
     let language = workspace_command.commit_template_language();
     let template = workspace_command.parse_template(ui, &language, &template_text)?;
 
-    // TODO: Should we add an option to limit the domain to e.g. recent commits?
-    // Note that this is probably different from "--skip REVS", which won't
-    // exclude the revisions, but will ignore diffs in those revisions as if
-    // ancestor revisions had new content.
+    let domain = match &args.domain {
+        Some(domain) => workspace_command.parse_revset(ui, domain)?.resolve()?,
+        None => RevsetExpression::all(),
+    };
+    // Changes in the ignored revisions are looked through as if the ancestor
+    // revisions had the new content.
+    let ignored_revisions = workspace_command
+        .settings()
+        .get_string("annotate.ignore-revisions")?;
+    let ignored_revisions = workspace_command
+        .parse_revset(ui, &RevisionArg::from(ignored_revisions))?
+        .resolve()?;
     let mut annotator = FileAnnotator::from_commit(&starting_commit, &file_path).await?;
+    annotator.set_ignored_revisions(ignored_revisions);
+    if let Some(line_range) = &args.line_range {
+        annotator.set_line_range(line_range.clone());
+    }
+
+    let first_line_number = args.line_range.as_ref().map_or(0, |range| range.start);
+    let mut printer = AnnotationPrinter::new(
+        repo.as_ref(),
+        &template,
+        &annotator.to_annotation(),
+        first_line_number,
+    );
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    // Lines are printed as soon as the origins of all preceding lines are
+    // found, so the output can be paged while older ancestors are searched.
+    let mut print_result = Ok(());
     annotator
-        .compute(repo.as_ref(), &RevsetExpression::all())
+        .compute_with(repo.as_ref(), &domain, |line_number, line_origin| {
+            if print_result.is_ok() {
+                print_result = printer.resolve(formatter.as_mut(), line_number, line_origin);
+            }
+        })
         .await?;
-    let annotation = annotator.to_annotation();
-
-    render_file_annotation(repo.as_ref(), ui, &template, &annotation).await?;
+    print_result?;
+    printer.finish(formatter.as_mut(), &annotator.to_annotation())?;
     Ok(())
 }
 
-async fn render_file_annotation(
-    repo: &dyn Repo,
-    ui: &mut Ui,
-    template_render: &TemplateRenderer<'_, AnnotationLine>,
-    annotation: &FileAnnotation,
-) -> Result<(), CommandError> {
-    ui.request_pager();
-    let mut formatter = ui.stdout_formatter();
-    let mut last_id = None;
-    // At least in cases where the repository was jj-initialized shallowly,
-    // then unshallow'd with git, some changes will not have a commit id
-    // because jj does not import the unshallow'd commits. So we default
-    // to the root commit id for now.
-    let default_line_origin = LineOrigin {
-        commit_id: repo.store().root_commit_id().clone(),
-        line_number: 0,
-    };
-    for (line_number, (line_origin, content)) in annotation.line_origins().enumerate() {
-        let line_origin = line_origin.unwrap_or(&default_line_origin);
-        let commit = repo
-            .store()
-            .get_commit_async(&line_origin.commit_id)
-            .await?;
-        let first_line_in_hunk = last_id != Some(&line_origin.commit_id);
-        let annotation_line = AnnotationLine {
-            commit,
-            content: content.to_owned(),
-            line_number: line_number + 1,
-            original_line_number: line_origin.line_number + 1,
-            first_line_in_hunk,
+/// Parses 1-based inclusive `START,END` line range into 0-based range.
+fn parse_line_range(text: &str) -> Result<Range<usize>, String> {
+    let (start, end) = text
+        .split_once(',')
+        .ok_or_else(|| "Expected line range in the form START,END".to_owned())?;
+    let start: usize = start
+        .parse()
+        .map_err(|err| format!("Invalid start line {start:?}: {err}"))?;
+    let end: usize = end
+        .parse()
+        .map_err(|err| format!("Invalid end line {end:?}: {err}"))?;
+    if start == 0 || end < start {
+        return Err(format!(
+            "Invalid line range {text:?}: lines are 1-based, and END must not be less than START"
+        ));
+    }
+    Ok(start - 1..end)
+}
+
+/// Renders annotated lines in order as their origins are found.
+struct AnnotationPrinter<'a> {
+    repo: &'a dyn Repo,
+    template: &'a TemplateRenderer<'a, AnnotationLine>,
+    /// Origin and content of each line in the annotated range.
+    lines: Vec<(Option<LineOrigin>, BString)>,
+    /// 0-based line number of the first line in the starting file.
+    first_line_number: usize,
+    /// Index of the next line to be printed.
+    next_index: usize,
+    last_id: Option<CommitId>,
+    /// Origin of the lines whose originator commits weren't found.
+    default_line_origin: LineOrigin,
+}
+
+impl<'a> AnnotationPrinter<'a> {
+    fn new(
+        repo: &'a dyn Repo,
+        template: &'a TemplateRenderer<'a, AnnotationLine>,
+        annotation: &FileAnnotation,
+        first_line_number: usize,
+    ) -> Self {
+        let lines = annotation
+            .line_origins()
+            .map(|(_, content)| (None, content.to_owned()))
+            .collect();
+        // At least in cases where the repository was jj-initialized shallowly,
+        // then unshallow'd with git, some changes will not have a commit id
+        // because jj does not import the unshallow'd commits. So we default
+        // to the root commit id for now.
+        let default_line_origin = LineOrigin {
+            commit_id: repo.store().root_commit_id().clone(),
+            line_number: 0,
         };
-        template_render.format(&annotation_line, formatter.as_mut())?;
-        last_id = Some(&line_origin.commit_id);
+        Self {
+            repo,
+            template,
+            lines,
+            first_line_number,
+            next_index: 0,
+            last_id: None,
+            default_line_origin,
+        }
     }
 
-    Ok(())
+    /// Records the origin of the line, and prints the lines which are ready.
+    fn resolve(
+        &mut self,
+        formatter: &mut dyn Formatter,
+        line_number: usize,
+        line_origin: Result<&LineOrigin, &LineOrigin>,
+    ) -> Result<(), CommandError> {
+        let Some(index) = line_number.checked_sub(self.first_line_number) else {
+            return Ok(());
+        };
+        if let Some((origin, _)) = self.lines.get_mut(index) {
+            *origin = Some(line_origin.unwrap_or(&self.default_line_origin).clone());
+        }
+        self.print_ready_lines(formatter)
+    }
+
+    /// Prints the remaining lines with the origins in the final `annotation`.
+    fn finish(
+        &mut self,
+        formatter: &mut dyn Formatter,
+        annotation: &FileAnnotation,
+    ) -> Result<(), CommandError> {
+        for ((origin, _), (line_origin, _)) in self.lines.iter_mut().zip(annotation.line_origins())
+        {
+            if origin.is_none() {
+                *origin = Some(line_origin.unwrap_or(&self.default_line_origin).clone());
+            }
+        }
+        self.print_ready_lines(formatter)
+    }
+
+    fn print_ready_lines(&mut self, formatter: &mut dyn Formatter) -> Result<(), CommandError> {
+        while let Some((Some(line_origin), content)) = self.lines.get(self.next_index) {
+            let commit = self.repo.store().get_commit(&line_origin.commit_id)?;
+            let first_line_in_hunk = self.last_id.as_ref() != Some(&line_origin.commit_id);
+            let annotation_line = AnnotationLine {
+                commit,
+                content: content.clone(),
+                line_number: self.first_line_number + self.next_index + 1,
+                original_line_number: line_origin.line_number + 1,
+                first_line_in_hunk,
+            };
+            self.template.format(&annotation_line, formatter)?;
+            self.last_id = Some(line_origin.commit_id.clone());
+            self.next_index += 1;
+        }
+        Ok(())
+    }
 }
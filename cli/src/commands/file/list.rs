@@ -18,7 +18,7 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
-use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::print_unmatched_paths;
 use crate::command_error::CommandError;
 use crate::commit_templater::TreeEntry;
 use crate::complete;
@@ -68,8 +68,12 @@ pub(crate) async fn cmd_file_list(
         .resolve_single_rev(ui, &args.revision)
         .await?;
     let tree = commit.tree();
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
-    let matcher = fileset_expression.to_matcher();
+    let fileset_expression =
+        workspace_command.parse_file_patterns_with_predicates(ui, &args.paths)?;
+    let matcher = fileset_expression
+        .resolve_predicates(std::slice::from_ref(&tree))
+        .await?
+        .to_matcher();
     let template: TemplateRenderer<TreeEntry> = {
         let language = workspace_command.commit_template_language();
         let text = match &args.template {
@@ -90,6 +94,11 @@ pub(crate) async fn cmd_file_list(
         };
         template.format(&entry, formatter.as_mut())?;
     }
-    print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
+    print_unmatched_paths(
+        ui,
+        &workspace_command,
+        fileset_expression.explicit_paths(),
+        [&tree],
+    )?;
     Ok(())
 }
@@ -140,7 +140,11 @@ fn get_single_path(expression: &FilesetExpression) -> Option<&RepoPath> {
             // Not using pattern.as_path() because files-in:<path> shouldn't
             // select the literal <path> itself.
             FilePattern::FilePath(path) | FilePattern::PrefixPath(path) => Some(path),
-            FilePattern::FileGlob { .. } | FilePattern::PrefixGlob { .. } => None,
+            FilePattern::FileGlob { .. }
+            | FilePattern::PrefixGlob { .. }
+            | FilePattern::FilesInPath(_)
+            | FilePattern::FileName { .. }
+            | FilePattern::FileRegex { .. } => None,
         },
         _ => None,
     }
@@ -46,7 +46,7 @@ use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
-use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::print_unmatched_paths;
 use crate::command_error::CommandError;
 use crate::command_error::config_error;
 use crate::command_error::print_parse_diagnostics;
@@ -226,8 +226,12 @@ pub(crate) async fn cmd_fix(
 
     let trees: Vec<_> = commits.iter().map(|commit| commit.tree()).collect();
 
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
-    let matcher = fileset_expression.to_matcher();
+    let fileset_expression =
+        workspace_command.parse_file_patterns_with_predicates(ui, &args.paths)?;
+    let matcher = fileset_expression
+        .resolve_predicates(&trees)
+        .await?
+        .to_matcher();
 
     let mut tx = workspace_command.start_transaction();
     let mut parallel_fixer = ParallelFileFixer::new(|store, file_to_fix| {
@@ -243,7 +247,12 @@ pub(crate) async fn cmd_fix(
         .block_on()
     });
 
-    print_unmatched_explicit_paths(ui, tx.base_workspace_helper(), &fileset_expression, &trees)?;
+    print_unmatched_paths(
+        ui,
+        tx.base_workspace_helper(),
+        fileset_expression.explicit_paths(),
+        &trees,
+    )?;
 
     let summary = fix_files(
         commit_ids,
@@ -0,0 +1,262 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::dag_walk;
+use jj_lib::git::GitFetch;
+use jj_lib::git::GitSettings;
+use jj_lib::ref_name::RemoteName;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::commands::gerrit::fetch::gerrit_url;
+use crate::commands::gerrit::upload::calculate_push_remote;
+use crate::gerrit_util::GerritClient;
+use crate::gerrit_util::change_id_from_gerrit_change_id;
+use crate::gerrit_util::gerrit_change_id;
+use crate::gerrit_util::gerrit_change_id_from_trailers;
+use crate::git_util::GitSubprocessUi;
+use crate::git_util::load_git_import_options;
+use crate::ui::Ui;
+
+/// Download a change from Gerrit
+///
+/// This fetches `refs/changes/NN/CHANGE/PATCHSET` from the Gerrit remote and
+/// adds the fetched commits to the repo. If no patchset is specified, the
+/// current patchset is looked up using the Gerrit REST API at `gerrit.url`.
+///
+/// The downloaded commits are associated with the local changes by their
+/// `Change-Id` trailer. If a mutable local commit has the same `Change-Id`, or
+/// if the `Change-Id` was derived from a change ID by `jj gerrit upload`, the
+/// downloaded commit becomes a new version of that change and descendants of
+/// the local commit are rebased onto it. Otherwise, a new change is created.
+///
+/// The remote is selected the same way as by `jj gerrit upload`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DownloadArgs {
+    /// The change number, optionally followed by `/` and the patchset number
+    #[arg(value_name = "CHANGE[/PATCHSET]", value_parser = parse_change_patchset)]
+    change: ChangePatchset,
+
+    /// The Git remote to download from
+    #[arg(long)]
+    remote: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ChangePatchset {
+    number: u64,
+    patchset: Option<u64>,
+}
+
+fn parse_change_patchset(text: &str) -> Result<ChangePatchset, String> {
+    let parse_number = |s: &str| {
+        s.parse::<u64>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("Invalid number: {s}"))
+    };
+    let (number, patchset) = match text.split_once('/') {
+        Some((number, patchset)) => (parse_number(number)?, Some(parse_number(patchset)?)),
+        None => (parse_number(text)?, None),
+    };
+    Ok(ChangePatchset { number, patchset })
+}
+
+pub async fn cmd_gerrit_download(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DownloadArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    let number = args.change.number;
+    let patchset = if let Some(patchset) = args.change.patchset {
+        patchset
+    } else {
+        let client = GerritClient::new(&gerrit_url(command.settings())?);
+        let review = client
+            .query_change_number(number)
+            .map_err(|err| user_error_with_message("Failed to look up change on Gerrit", err))?
+            .ok_or_else(|| user_error(format!("Change {number} not found on Gerrit")))?;
+        review.patchset
+    };
+    let remote = calculate_push_remote(
+        workspace_command.repo().store(),
+        command.settings(),
+        args.remote.as_deref(),
+    )?;
+
+    // Gerrit Change-Ids of the local changes which the downloaded commits can
+    // be associated with.
+    let local_changes: HashMap<String, ChangeId> = workspace_command
+        .parse_revset(ui, &RevisionArg::from("mutable()".to_owned()))?
+        .evaluate_to_commits()?
+        .try_filter(|commit| futures::future::ready(!commit.description().is_empty()))
+        .map_ok(|commit| (gerrit_change_id(&commit), commit.change_id().clone()))
+        .try_collect()
+        .await?;
+
+    let mut tx = workspace_command.start_transaction();
+    let remote_settings = tx.settings().remote_settings()?;
+    let git_settings = GitSettings::from_settings(tx.settings())?;
+    let import_options = load_git_import_options(ui, &git_settings, &remote_settings)?;
+    let mut git_fetch = GitFetch::new(
+        tx.repo_mut(),
+        git_settings.to_subprocess_options(),
+        &import_options,
+    )?;
+    let remote_ref = format!("refs/changes/{:02}/{number}/{patchset}", number % 100);
+    let fetched_id = git_fetch
+        .fetch_commit(
+            RemoteName::new(&remote),
+            remote_ref.as_ref(),
+            &mut GitSubprocessUi::new(ui),
+        )?
+        .ok_or_else(|| {
+            user_error(format!(
+                "Patchset {number}/{patchset} not found on remote '{remote}'"
+            ))
+        })?;
+
+    let store = tx.repo().store().clone();
+
+    // Collect the fetched commits which aren't in the repo yet.
+    let mut fetched_commits: HashMap<CommitId, Commit> = HashMap::new();
+    let mut to_visit = vec![fetched_id.clone()];
+    while let Some(id) = to_visit.pop() {
+        if fetched_commits.contains_key(&id) || tx.repo().index().has_id(&id)? {
+            continue;
+        }
+        let commit = store.get_commit_async(&id).await?;
+        to_visit.extend(commit.parent_ids().iter().cloned());
+        fetched_commits.insert(id, commit);
+    }
+    let ordered_commits = dag_walk::topo_order_forward(
+        fetched_commits.get(&fetched_id).cloned(),
+        |commit| commit.id().clone(),
+        |commit| {
+            commit
+                .parent_ids()
+                .iter()
+                .filter_map(|id| fetched_commits.get(id))
+                .cloned()
+                .collect_vec()
+        },
+        |_| internal_error("Cycle detected in fetched commits"),
+    )?;
+
+    // Add the fetched commits as new versions of the local changes, parents
+    // first so the children can be rebased onto the new versions.
+    let mut new_ids: HashMap<CommitId, CommitId> = HashMap::new();
+    let mut rewritten: Vec<(CommitId, CommitId)> = vec![];
+    for fetched in &ordered_commits {
+        let change_id = gerrit_change_id_from_trailers(fetched.description())
+            .and_then(|id| {
+                local_changes
+                    .get(&id)
+                    .cloned()
+                    .or_else(|| change_id_from_gerrit_change_id(&id))
+            })
+            .unwrap_or_else(|| fetched.change_id().clone());
+        let parent_ids = fetched
+            .parent_ids()
+            .iter()
+            .map(|id| new_ids.get(id).unwrap_or(id).clone())
+            .collect_vec();
+        let local_ids = match tx.base_repo().resolve_change_id(&change_id)? {
+            Some(targets) => targets
+                .visible_with_offsets()
+                .map(|(_, id)| id.clone())
+                .collect_vec(),
+            None => vec![],
+        };
+
+        // The same patchset may have been downloaded before.
+        let mut existing = None;
+        for id in &local_ids {
+            let local = tx.repo().store().get_commit_async(id).await?;
+            if local.parent_ids() == parent_ids
+                && local.tree_ids() == fetched.tree_ids()
+                && local.description() == fetched.description()
+                && local.author() == fetched.author()
+            {
+                existing = Some(local);
+                break;
+            }
+        }
+        let new_commit = if let Some(commit) = existing {
+            commit
+        } else if change_id == *fetched.change_id()
+            && parent_ids == fetched.parent_ids()
+            && local_ids.is_empty()
+        {
+            tx.repo_mut().add_head(fetched).await?;
+            fetched.clone()
+        } else {
+            tx.repo_mut()
+                .new_commit(parent_ids, fetched.tree())
+                .set_change_id(change_id)
+                .set_predecessors(local_ids.clone())
+                .set_author(fetched.author().clone())
+                .set_committer(fetched.committer().clone())
+                .set_description(fetched.description())
+                .write()
+                .await?
+        };
+        for id in local_ids {
+            if id != *new_commit.id() {
+                rewritten.push((id, new_commit.id().clone()));
+            }
+        }
+        new_ids.insert(fetched.id().clone(), new_commit.id().clone());
+    }
+
+    tx.base_workspace_helper()
+        .check_rewritable(rewritten.iter().map(|(old_id, _)| old_id))
+        .await?;
+    for (old_id, new_id) in rewritten {
+        tx.repo_mut().set_rewritten_commit(old_id, new_id);
+    }
+    let num_rebased = tx.repo_mut().rebase_descendants().await?;
+
+    let head_id = new_ids.get(&fetched_id).unwrap_or(&fetched_id);
+    let head = tx.repo().store().get_commit_async(head_id).await?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(
+            formatter,
+            "Downloaded change {number} patchset {patchset}: "
+        )?;
+        tx.write_commit_summary(formatter.as_mut(), &head)?;
+        writeln!(formatter)?;
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits.")?;
+        }
+    }
+    tx.finish(ui, format!("download gerrit change {number}/{patchset}"))
+        .await?;
+    Ok(())
+}
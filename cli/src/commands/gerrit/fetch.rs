@@ -0,0 +1,125 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::settings::UserSettings;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::gerrit_util::GerritClient;
+use crate::gerrit_util::GerritReviews;
+use crate::gerrit_util::gerrit_change_id;
+use crate::ui::Ui;
+
+/// Fetch the review status of changes from Gerrit
+///
+/// This looks up the changes with the same `Change-Id` as the given revisions
+/// using the Gerrit REST API, and stores their current patchset number, votes,
+/// and submittable state in the repo. Revisions without a `Change-Id` trailer
+/// are looked up by the `Change-Id` that `jj gerrit upload` derives from the
+/// change ID.
+///
+/// The stored status is shown by `jj gerrit status`, by the
+/// `gerrit_review()` template method, and by the `gerrit_changes()`,
+/// `gerrit_approved()`, and `gerrit_submittable()` revset functions.
+///
+/// Requests are sent with `curl` to the server configured by `gerrit.url`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct FetchArgs {
+    /// The revisions to fetch the review status of
+    #[arg(
+        long = "revision",
+        short,
+        default_value = "mutable()",
+        value_name = "REVSETS",
+        alias = "revisions"
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+pub async fn cmd_gerrit_fetch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FetchArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui).await?;
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()
+        .await?;
+    fetch_reviews(ui, command.settings(), &workspace_command, &commits)?;
+    Ok(())
+}
+
+/// Fetches the review status of the commits from Gerrit, and updates the
+/// stored status.
+pub(super) fn fetch_reviews(
+    ui: &Ui,
+    settings: &UserSettings,
+    workspace_command: &WorkspaceCommandHelper,
+    commits: &[Commit],
+) -> Result<GerritReviews, CommandError> {
+    let client = GerritClient::new(&gerrit_url(settings)?);
+    // Commits without description can't have been uploaded.
+    let change_ids = commits
+        .iter()
+        .filter(|commit| !commit.description().is_empty())
+        .map(gerrit_change_id)
+        .unique()
+        .collect_vec();
+    let mut fetched = client
+        .query_changes(&change_ids)
+        .map_err(|err| user_error_with_message("Failed to fetch review status from Gerrit", err))?;
+
+    let repo_path = workspace_command.repo_path();
+    let mut reviews = GerritReviews::load(repo_path).map_err(user_error)?;
+    let mut fetched_count = 0;
+    for change_id in change_ids {
+        if let Some(review) = fetched.remove(&change_id) {
+            fetched_count += 1;
+            reviews.insert(change_id, review);
+        } else {
+            reviews.remove(&change_id);
+        }
+    }
+    reviews.save(repo_path).map_err(user_error)?;
+
+    writeln!(
+        ui.status(),
+        "Fetched review status of {fetched_count} changes from Gerrit"
+    )?;
+    Ok(reviews)
+}
+
+pub(super) fn gerrit_url(settings: &UserSettings) -> Result<String, CommandError> {
+    if let Some(url) = settings.get_string("gerrit.url").optional()? {
+        Ok(url)
+    } else if let Some(url) = settings.get_string("gerrit.review-url").optional()? {
+        Ok(url)
+    } else {
+        Err(user_error("No Gerrit URL configured")
+            .hinted("Set `gerrit.url` to the URL of your Gerrit server"))
+    }
+}
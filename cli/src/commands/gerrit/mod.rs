@@ -23,7 +23,11 @@ use crate::ui::Ui;
 
 /// Interact with Gerrit Code Review.
 #[derive(Subcommand, Clone, Debug)]
+#[expect(clippy::large_enum_variant)]
 pub enum GerritCommand {
+    Download(gerrit::download::DownloadArgs),
+    Fetch(gerrit::fetch::FetchArgs),
+    Status(gerrit::status::StatusArgs),
     Upload(gerrit::upload::UploadArgs),
 }
 
@@ -33,10 +37,18 @@ pub async fn cmd_gerrit(
     subcommand: &GerritCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        GerritCommand::Download(args) => {
+            gerrit::download::cmd_gerrit_download(ui, command, args).await
+        }
+        GerritCommand::Fetch(args) => gerrit::fetch::cmd_gerrit_fetch(ui, command, args).await,
+        GerritCommand::Status(args) => gerrit::status::cmd_gerrit_status(ui, command, args).await,
         GerritCommand::Upload(review) => {
             gerrit::upload::cmd_gerrit_upload(ui, command, review).await
         }
     }
 }
 
+mod download;
+mod fetch;
+mod status;
 mod upload;
@@ -0,0 +1,99 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+
+use futures::TryStreamExt as _;
+use jj_lib::commit::Commit;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::commands::gerrit::fetch::fetch_reviews;
+use crate::gerrit_util::GerritReviews;
+use crate::ui::Ui;
+
+/// Show the review status of changes on Gerrit
+///
+/// This shows the change number, current patchset, and votes of the changes
+/// with the same `Change-Id` as the given revisions, as stored by the last
+/// `jj gerrit fetch`. Revisions with an empty description are not shown.
+#[derive(clap::Args, Clone, Debug)]
+pub struct StatusArgs {
+    /// The revisions to show the review status of
+    #[arg(
+        long = "revision",
+        short,
+        default_value = "mutable()",
+        value_name = "REVSETS",
+        alias = "revisions"
+    )]
+    revisions: Vec<RevisionArg>,
+
+    /// Fetch the review status from Gerrit before showing it
+    #[arg(long)]
+    fetch: bool,
+}
+
+pub async fn cmd_gerrit_status(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StatusArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui).await?;
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()
+        .await?;
+    let reviews = if args.fetch {
+        fetch_reviews(ui, command.settings(), &workspace_command, &commits)?
+    } else {
+        GerritReviews::load(workspace_command.repo_path()).map_err(user_error)?
+    };
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    for commit in commits
+        .iter()
+        .filter(|commit| !commit.description().is_empty())
+    {
+        workspace_command.write_commit_summary(formatter, commit)?;
+        writeln!(formatter)?;
+        let Some(review) = reviews.get_for_commit(commit) else {
+            writeln!(formatter, "  Not found on Gerrit")?;
+            continue;
+        };
+        write!(
+            formatter,
+            "  Change {} (patchset {}): {}",
+            review.number, review.patchset, review.status
+        )?;
+        if review.is_approved() {
+            write!(formatter, ", approved")?;
+        }
+        if review.submittable {
+            write!(formatter, ", submittable")?;
+        }
+        writeln!(formatter)?;
+        let votes = review.votes();
+        if !votes.is_empty() {
+            writeln!(formatter, "  Votes: {}", votes.join(" "))?;
+        }
+        writeln!(formatter, "  {}", review.url)?;
+    }
+    Ok(())
+}
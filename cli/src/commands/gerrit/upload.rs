@@ -43,6 +43,7 @@ use crate::command_error::CommandError;
 use crate::command_error::internal_error;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
+use crate::gerrit_util::derived_gerrit_change_id;
 use crate::git_util::GitSubprocessUi;
 use crate::git_util::print_push_stats;
 use crate::ui::Ui;
@@ -245,7 +246,7 @@ pub enum EmailNotification {
     All,
 }
 
-fn calculate_push_remote(
+pub(super) fn calculate_push_remote(
     store: &Arc<Store>,
     settings: &UserSettings,
     remote: Option<&str>,
@@ -579,10 +580,7 @@ pub async fn cmd_gerrit_upload(
 
             original_commit.description().to_owned()
         } else {
-            // Gerrit change id is 40 chars, jj change id is 32, so we need padding.
-            // To be consistent with `format_gerrit_change_id_trailer``, we pad with
-            // 6a6a6964 (hex of "jjid").
-            let gerrit_change_id = format!("I{}6a6a6964", original_commit.change_id().hex());
+            let gerrit_change_id = derived_gerrit_change_id(original_commit.change_id());
 
             let change_id_trailer =
                 if let Ok(review_url) = command.settings().get_string("gerrit.review-url") {
@@ -68,6 +68,7 @@ use crate::cli_util::has_tracked_remote_bookmarks;
 use crate::cli_util::has_tracked_remote_tags;
 use crate::cli_util::short_change_hash;
 use crate::cli_util::short_commit_hash;
+use crate::cli_util::write_json_line;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
 use crate::command_error::cli_error_with_message;
@@ -78,9 +79,11 @@ use crate::complete;
 use crate::formatter::Formatter;
 use crate::git_util::GitSubprocessUi;
 use crate::git_util::print_push_stats;
+use crate::hooks::HookEvent;
 use crate::progress::ProgressWriter;
 use crate::revset_util::parse_bookmark_name;
 use crate::revset_util::parse_union_name_patterns;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 /// Push to a Git remote
@@ -556,12 +559,18 @@ pub async fn cmd_git_push(
         )?;
         print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &ref_updates)?;
     }
-
     if args.dry_run {
+        if ui.output_format() == OutputFormat::Json {
+            let mut formatter = ui.stdout_formatter();
+            write_push_updates_json(formatter.as_mut(), tx.repo(), remote, &ref_updates)?;
+        }
         writeln!(ui.status(), "Dry-run requested, not pushing.")?;
         return Ok(());
     }
 
+    let to_push_expr = ready_to_push_revset_expression(&tx, remote, &ref_updates);
+    tx.run_hooks(ui, HookEvent::PrePush, &to_push_expr).await?;
+
     let git_settings = GitSettings::from_settings(tx.settings())?;
     let options = GitPushOptions {
         remote_push_options: args.option.clone(),
@@ -575,6 +584,31 @@ pub async fn cmd_git_push(
         &options,
     )?;
     print_push_stats(ui, &push_stats)?;
+    if ui.output_format() == OutputFormat::Json {
+        // Only report the refs accepted by the remote.
+        let is_pushed = |qualified_name: String| {
+            push_stats
+                .pushed
+                .iter()
+                .any(|name| name.as_str() == qualified_name)
+        };
+        let pushed_updates = GitPushRefTargets {
+            bookmarks: ref_updates
+                .bookmarks
+                .iter()
+                .filter(|(name, _)| is_pushed(format!("refs/heads/{}", name.as_str())))
+                .cloned()
+                .collect(),
+            tags: ref_updates
+                .tags
+                .iter()
+                .filter(|(name, _)| is_pushed(format!("refs/tags/{}", name.as_str())))
+                .cloned()
+                .collect(),
+        };
+        let mut formatter = ui.stdout_formatter();
+        write_push_updates_json(formatter.as_mut(), tx.repo(), remote, &pushed_updates)?;
+    }
     // TODO: On partial success, locally-created --change/--named bookmarks will
     // be committed. It's probably better to remove failed local bookmarks.
     if push_stats.all_ok() || push_stats.some_exported() {
@@ -885,17 +919,6 @@ fn print_commits_ready_to_push(
     repo: &dyn Repo,
     ref_updates: &GitPushRefTargets,
 ) -> Result<(), CommandError> {
-    let to_direction =
-        |old_target: &CommitId, new_target: &CommitId| -> IndexResult<BookmarkMoveDirection> {
-            assert_ne!(old_target, new_target);
-            if repo.index().is_ancestor(old_target, new_target)? {
-                Ok(BookmarkMoveDirection::Forward)
-            } else if repo.index().is_ancestor(new_target, old_target)? {
-                Ok(BookmarkMoveDirection::Backward)
-            } else {
-                Ok(BookmarkMoveDirection::Sideways)
-            }
-        };
     let describe_update = |update: &Diff<Option<CommitId>>| -> IndexResult<String> {
         let desc = match (&update.before, &update.after) {
             (Some(old_target), Some(new_target)) => {
@@ -903,7 +926,7 @@ fn print_commits_ready_to_push(
                 let new = short_commit_hash(new_target);
                 // TODO: People on Discord suggest "... forward by n commits",
                 // possibly "... sideways (X forward, Y back)".
-                match to_direction(old_target, new_target)? {
+                match get_move_direction(repo, old_target, new_target)? {
                     BookmarkMoveDirection::Forward => {
                         format!("move forward from {old} to {new}")
                     }
@@ -946,6 +969,70 @@ fn print_commits_ready_to_push(
     Ok(())
 }
 
+/// Writes the ref updates to be pushed as JSON lines.
+fn write_push_updates_json(
+    formatter: &mut dyn Formatter,
+    repo: &dyn Repo,
+    remote: &RemoteName,
+    ref_updates: &GitPushRefTargets,
+) -> Result<(), CommandError> {
+    #[derive(serde::Serialize)]
+    struct PushUpdateItem<'a> {
+        r#type: &'static str,
+        name: &'a str,
+        remote: &'a str,
+        action: &'static str,
+        old_target: Option<&'a CommitId>,
+        new_target: Option<&'a CommitId>,
+    }
+
+    let kind_updates = [
+        ("bookmark", &ref_updates.bookmarks),
+        ("tag", &ref_updates.tags),
+    ];
+    for (kind, updates) in kind_updates {
+        for (name, update) in updates {
+            let action = match (&update.before, &update.after) {
+                (Some(old_target), Some(new_target)) => {
+                    match get_move_direction(repo, old_target, new_target)? {
+                        BookmarkMoveDirection::Forward => "move_forward",
+                        BookmarkMoveDirection::Backward => "move_backward",
+                        BookmarkMoveDirection::Sideways => "move_sideways",
+                    }
+                }
+                (Some(_), None) => "delete",
+                (None, Some(_)) => "add",
+                (None, None) => panic!("Not pushing any change"),
+            };
+            let item = PushUpdateItem {
+                r#type: kind,
+                name: name.as_str(),
+                remote: remote.as_str(),
+                action,
+                old_target: update.before.as_ref(),
+                new_target: update.after.as_ref(),
+            };
+            write_json_line(formatter, &item)?;
+        }
+    }
+    Ok(())
+}
+
+fn get_move_direction(
+    repo: &dyn Repo,
+    old_target: &CommitId,
+    new_target: &CommitId,
+) -> IndexResult<BookmarkMoveDirection> {
+    assert_ne!(old_target, new_target);
+    if repo.index().is_ancestor(old_target, new_target)? {
+        Ok(BookmarkMoveDirection::Forward)
+    } else if repo.index().is_ancestor(new_target, old_target)? {
+        Ok(BookmarkMoveDirection::Backward)
+    } else {
+        Ok(BookmarkMoveDirection::Sideways)
+    }
+}
+
 fn get_default_push_remote(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
@@ -21,6 +21,7 @@ mod bisect;
 mod bookmark;
 mod commit;
 mod config;
+mod converge;
 mod debug;
 mod describe;
 mod diff;
@@ -49,7 +50,7 @@ mod resolve;
 mod restore;
 mod revert;
 mod root;
-mod run;
+pub(crate) mod run;
 mod show;
 mod sign;
 mod simplify_parents;
@@ -105,6 +106,7 @@ enum Command {
     Commit(commit::CommitArgs),
     #[command(subcommand)]
     Config(config::ConfigCommand),
+    Converge(converge::ConvergeArgs),
     #[command(subcommand)]
     Debug(debug::DebugCommand),
     Describe(describe::DescribeArgs),
@@ -176,6 +178,7 @@ pub async fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<
         Command::Bookmark(args) => bookmark::cmd_bookmark(ui, command_helper, args).await,
         Command::Commit(args) => commit::cmd_commit(ui, command_helper, args).await,
         Command::Config(args) => config::cmd_config(ui, command_helper, args).await,
+        Command::Converge(args) => converge::cmd_converge(ui, command_helper, args).await,
         Command::Debug(args) => debug::cmd_debug(ui, command_helper, args).await,
         Command::Describe(args) => describe::cmd_describe(ui, command_helper, args).await,
         Command::Diff(args) => diff::cmd_diff(ui, command_helper, args).await,
@@ -19,8 +19,10 @@ use std::slice;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::op_walk;
+use jj_lib::opset;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::resolve_opset;
 use crate::cli_util::short_operation_hash;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
@@ -44,6 +46,12 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationAbandonArgs {
     /// The operation or operation range to abandon
+    ///
+    /// The operations are specified in the [opset language], and must form a
+    /// contiguous range.
+    ///
+    /// [opset language]:
+    ///     https://docs.jj-vcs.dev/latest/operation-log/#opset-language
     #[arg(add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 }
@@ -65,30 +73,31 @@ pub async fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref()).await?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
-    let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
-            let root_op = if root_op_str.is_empty() {
-                repo_loader.root_operation().await
-            } else {
-                resolve_op(root_op_str).await?
-            };
-            let head_ops = if head_op_str.is_empty() {
-                current_head_ops.clone()
-            } else {
-                vec![resolve_op(head_op_str).await?]
-            };
-            (root_op, head_ops)
-        } else {
-            let op = resolve_op(&args.operation).await?;
-            let parent_ops = op.parents().await?;
-            let parent_op = match parent_ops.len() {
-                0 => return Err(user_error("Cannot abandon the root operation")),
-                1 => parent_ops.into_iter().next().unwrap(),
-                _ => return Err(user_error("Cannot abandon a merge operation")),
-            };
-            (parent_op, vec![op])
-        };
+    let abandon_ops = resolve_opset(op_store, &current_head_ops, &args.operation).await?;
+    if abandon_ops.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+    let Some(range) = opset::to_range(&abandon_ops).await? else {
+        return Err(user_error(
+            "Cannot abandon operations which don't form a contiguous range",
+        ));
+    };
+    let abandon_head_ops = range.heads;
+    let abandon_root_op = match <[_; 1]>::try_from(range.roots) {
+        Ok([root_op]) => root_op,
+        Err(roots) if roots.is_empty() => {
+            return Err(user_error("Cannot abandon the root operation"));
+        }
+        Err(_) if abandon_ops.len() == 1 => {
+            return Err(user_error("Cannot abandon a merge operation"));
+        }
+        Err(_) => {
+            return Err(user_error(
+                "Cannot abandon operations based on multiple parent operations",
+            ));
+        }
+    };
 
     if let Some(op) = abandon_head_ops
         .iter()
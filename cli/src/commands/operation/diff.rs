@@ -31,6 +31,10 @@ use jj_lib::matchers::EverythingMatcher;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
+use jj_lib::op_walk::OpsetEvaluationError;
+use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::opset;
+use jj_lib::opset::OpsetExpression;
 use jj_lib::refs::diff_named_commit_ids;
 use jj_lib::refs::diff_named_ref_targets;
 use jj_lib::refs::diff_named_remote_refs;
@@ -53,6 +57,7 @@ use crate::cli_util::merge_operations;
 use crate::command_error::CommandError;
 use crate::command_error::config_error_with_message;
 use crate::command_error::print_parse_diagnostics;
+use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
@@ -69,6 +74,12 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationDiffArgs {
     /// Show repository changes in this operation, compared to its parent
+    ///
+    /// If a range of operations is specified in the [opset language], the
+    /// changes made by the whole range are shown.
+    ///
+    /// [opset language]:
+    ///     https://docs.jj-vcs.dev/latest/operation-log/#opset-language
     #[arg(long, visible_alias = "op")]
     #[arg(add = ArgValueCandidates::new(complete::operations))]
     operation: Option<String>,
@@ -121,8 +132,38 @@ pub async fn cmd_op_diff(
         from_ops = vec![workspace_command.resolve_single_op(args.from.as_deref().unwrap_or("@"))?];
         to_op = workspace_command.resolve_single_op(args.to.as_deref().unwrap_or("@"))?;
     } else {
-        to_op = workspace_command.resolve_single_op(args.operation.as_deref().unwrap_or("@"))?;
-        from_ops = to_op.parents().await?;
+        let op_str = args.operation.as_deref().unwrap_or("@");
+        let expression = opset::parse(op_str)?;
+        if let OpsetExpression::Operation(op_str) = &*expression {
+            // The operation may not be an ancestor of the current operation.
+            to_op = workspace_command.resolve_single_op(op_str)?;
+            from_ops = to_op.parents().await?;
+        } else {
+            let repo = workspace_command.repo();
+            let ops = expression
+                .evaluate(repo.op_store(), slice::from_ref(repo.operation()))
+                .await?;
+            if ops.is_empty() {
+                let err = OpsetResolutionError::EmptyOperations(op_str.to_owned());
+                return Err(OpsetEvaluationError::from(err).into());
+            }
+            let Some(range) = opset::to_range(&ops).await? else {
+                return Err(user_error(format!(
+                    r#"The "{op_str}" expression doesn't form a contiguous range"#
+                )));
+            };
+            to_op = match <[_; 1]>::try_from(range.heads) {
+                Ok([head_op]) => head_op,
+                Err(heads) => {
+                    let err = OpsetResolutionError::MultipleOperations {
+                        expr: op_str.to_owned(),
+                        candidates: heads.iter().map(|op| op.id().clone()).collect(),
+                    };
+                    return Err(OpsetEvaluationError::from(err).into());
+                }
+            };
+            from_ops = range.roots;
+        }
     }
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
@@ -322,7 +363,7 @@ pub async fn show_op_diff(
             })
             .await?;
         if let Some(graph_style) = graph_style {
-            let mut raw_output = formatter.raw()?;
+            let mut raw_output = formatter.raw_formatted()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
             let mut graph_stream = TopoGroupedGraph::new(revset.stream_graph(), |id| id)
                 .stream()
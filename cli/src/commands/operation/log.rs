@@ -21,13 +21,16 @@ use futures::stream;
 use futures::stream::LocalBoxStream;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::reverse_graph;
+use jj_lib::op_store::OpStoreResult;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
 use jj_lib::repo::RepoLoader;
 
 use super::diff::parse_op_diff_changes_in;
 use super::diff::show_op_diff;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::JSON_LINE_TEMPLATE_TEXT;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::WorkspaceCommandEnvironment;
 use crate::cli_util::format_template;
@@ -42,6 +45,7 @@ use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
 use crate::operation_templater::OperationTemplateLanguage;
 use crate::templater::TemplateRenderer;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 /// Show the operation log
@@ -102,6 +106,15 @@ pub struct OperationLogArgs {
     /// `revsets.op-diff-changes-in` setting.
     #[arg(long, value_name = "REVSETS")]
     show_changes_in: Option<String>,
+
+    /// Show only operations matching the given opset expression
+    ///
+    /// See [opset language] for the syntax.
+    ///
+    /// [opset language]:
+    ///     https://docs.jj-vcs.dev/latest/operation-log/#opset-language
+    #[arg(long, short = 'r', value_name = "OPSET")]
+    operations: Option<String>,
 }
 
 pub async fn cmd_op_log(
@@ -137,6 +150,9 @@ async fn do_op_log(
     let settings = repo_loader.settings();
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
+    // JSON lines can't be decorated by graph or interleaved with diffs.
+    let json_output = ui.output_format() == OutputFormat::Json;
+    let no_graph = args.no_graph || json_output;
 
     let template: TemplateRenderer<Operation>;
     let op_node_template: TemplateRenderer<Operation>;
@@ -148,6 +164,7 @@ async fn do_op_log(
             workspace_env.operation_template_extensions(),
         );
         let text = match &args.template {
+            _ if json_output => JSON_LINE_TEMPLATE_TEXT.to_owned(),
             Some(value) => value.to_owned(),
             None => settings.get_string("templates.op_log")?,
         };
@@ -164,7 +181,9 @@ async fn do_op_log(
     }
 
     let diff_formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
-    let maybe_show_op_diff = if args.op_diff || !diff_formats.is_empty() {
+    let maybe_show_op_diff = if json_output {
+        None
+    } else if args.op_diff || !diff_formats.is_empty() {
         let template_text = settings.get_string("templates.commit_summary")?;
         let op_diff_changes_expr =
             parse_op_diff_changes_in(ui, settings, workspace_env, args.show_changes_in.as_deref())?;
@@ -220,7 +239,7 @@ async fn do_op_log(
                 &parent_repo,
                 &repo,
                 &commit_summary_template,
-                (!args.no_graph).then_some(graph_style),
+                (!no_graph).then_some(graph_style),
                 with_content_format,
                 diff_renderer.as_ref(),
                 op_diff_changes_expr.clone(),
@@ -232,20 +251,31 @@ async fn do_op_log(
         None
     };
 
+    let limit = args.limit.unwrap_or(usize::MAX);
+    let head_ops = slice::from_ref(current_op);
+    let opset_expression = args.operations.as_deref().map(opset::parse).transpose()?;
+
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    let stream =
-        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
 
-    if !args.no_graph {
-        let mut raw_output = formatter.raw()?;
+    if !no_graph {
+        let mut raw_output = formatter.raw_formatted()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let stream = stream.map_ok(|op| {
-            let ids = op.parent_ids();
-            let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
-            (op, edges)
-        });
+        let stream: LocalBoxStream<'_, OpStoreResult<_>> =
+            if let Some(expression) = &opset_expression {
+                let op_store = repo_loader.op_store();
+                let stream = expression.evaluate_graph(op_store, head_ops).await?;
+                stream.take(limit).boxed_local()
+            } else {
+                let stream = op_walk::walk_ancestors(head_ops).take(limit);
+                let stream = stream.map_ok(|op| {
+                    let ids = op.parent_ids();
+                    let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
+                    (op, edges)
+                });
+                stream.boxed_local()
+            };
         let mut stream_nodes: LocalBoxStream<'_, _> = if args.reversed {
             stream::iter(
                 reverse_graph(stream.collect::<Vec<_>>().await.into_iter(), Operation::id)?
@@ -278,12 +308,24 @@ async fn do_op_log(
             )?;
         }
     } else {
+        let stream: LocalBoxStream<'_, OpStoreResult<_>> =
+            if let Some(expression) = &opset_expression {
+                let op_store = repo_loader.op_store();
+                let stream = expression.evaluate_lazy(op_store, head_ops).await?;
+                stream.take(limit).boxed_local()
+            } else {
+                op_walk::walk_ancestors(head_ops).take(limit).boxed_local()
+            };
         let mut stream: LocalBoxStream<'_, _> = if args.reversed {
             stream::iter(stream.collect::<Vec<_>>().await.into_iter().rev()).boxed()
         } else {
             stream.boxed_local()
         };
         while let Some(op) = stream.try_next().await? {
+            if json_output {
+                template.format(&op, formatter)?;
+                continue;
+            }
             with_content_format
                 .write(formatter, async |formatter| template.format(&op, formatter))
                 .await?;
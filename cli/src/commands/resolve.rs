@@ -12,20 +12,39 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::FilesMatcher;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::rewrite::rebase_commit;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::short_change_hash;
+use crate::cli_util::write_conflicted_paths_json;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::complete;
 use crate::formatter::FormatterExt as _;
+use crate::merge_tools::edit_conflicts_in_text_editor;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 /// Resolve conflicted files with an external merge tool
@@ -36,16 +55,17 @@ use crate::ui::Ui;
 /// resolved. To stop resolving conflicts, exit the merge tool without making
 /// any changes.
 ///
+/// With `--editor`, the conflict markers are instead edited in the text editor,
+/// which also works for conflicts with more than two sides. Hunks which still
+/// have conflict markers after the editor quits are kept as conflicts.
+///
+/// With `--split`, conflicts with more than two sides in a merge commit are
+/// instead split into simpler ones. A merge commit of two of the parents is
+/// inserted, so its conflicts can be resolved with a 3-way merge first.
+///
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
-//  TODOs:
-//   - `jj resolve --editor` to resolve a conflict in the default text editor. Should work for
-//     conflicts with 3+ adds. Useful to resolve conflicts in a commit other than the current one.
-//   - A way to help split commits with conflicts that are too complicated (more than two sides)
-//     into commits with simpler conflicts. In case of a tree with many merges, we could for example
-//     point to existing commits with simpler conflicts where resolving those conflicts would help
-//     simplify the present one.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct ResolveArgs {
     #[arg(long, short, default_value = "@", value_name = "REVSET")]
@@ -66,6 +86,24 @@ pub(crate) struct ResolveArgs {
     #[arg(add = ArgValueCandidates::new(complete::merge_editors))]
     tool: Option<String>,
 
+    /// Edit the conflict markers in the default text editor instead of using
+    /// a merge tool
+    ///
+    /// Conflicts with any number of sides are supported. The configured
+    /// `ui.conflict-marker-style` is used to materialize the conflicts.
+    #[arg(long, conflicts_with_all = ["list", "tool"])]
+    editor: bool,
+
+    /// Split conflicts with more than two sides by merging two of the parents
+    /// first
+    ///
+    /// The pair of parents whose merge leaves the fewest conflicts is merged
+    /// into a new commit, which replaces them as parents of the revision. If
+    /// the revision has fewer than three parents, ancestors with conflicts at
+    /// the same paths are suggested instead.
+    #[arg(long, conflicts_with_all = ["list", "tool", "editor"])]
+    split: bool,
+
     /// Only resolve conflicts in these paths. You can use the `--list` argument
     /// to find paths to use here.
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
@@ -98,6 +136,9 @@ pub(crate) async fn cmd_resolve(
         }));
     }
     if args.list {
+        if ui.output_format() == OutputFormat::Json {
+            return write_conflicted_paths_json(conflicts, ui.stdout_formatter().as_mut());
+        }
         return print_conflicted_paths(
             conflicts,
             ui.stdout_formatter().as_mut(),
@@ -105,15 +146,38 @@ pub(crate) async fn cmd_resolve(
         );
     }
 
+    if args.split {
+        return split_conflicts(ui, workspace_command, &commit, conflicts).await;
+    }
+
     let repo_paths = conflicts
         .iter()
         .map(|(path, _)| path.as_ref())
         .collect_vec();
     workspace_command.check_rewritable([commit.id()]).await?;
-    let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+    let (new_tree, partial_resolution_error) = if args.editor {
+        let text_editor = workspace_command.text_editor()?;
+        edit_conflicts_in_text_editor(
+            ui,
+            workspace_command.path_converter(),
+            &text_editor,
+            &tree,
+            &repo_paths,
+            workspace_command.env().conflict_marker_style(),
+        )
+        .await?
+    } else {
+        let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
+        merge_editor.edit_files(ui, &tree, &repo_paths).await?
+    };
+    if new_tree.tree_ids() == tree.tree_ids() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return match partial_resolution_error {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
+        };
+    }
     let mut tx = workspace_command.start_transaction();
-    let (new_tree, partial_resolution_error) =
-        merge_editor.edit_files(ui, &tree, &repo_paths).await?;
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(&commit)
@@ -147,3 +211,142 @@ pub(crate) async fn cmd_resolve(
     }
     Ok(())
 }
+
+/// Inserts a merge commit of two of the parents of `commit` to reduce the
+/// number of sides of its conflicts.
+async fn split_conflicts(
+    ui: &mut Ui,
+    mut workspace_command: WorkspaceCommandHelper,
+    commit: &Commit,
+    conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
+) -> Result<(), CommandError> {
+    let mut complex_paths = vec![];
+    for (path, value) in conflicts {
+        if value?.simplify().num_sides() > 2 {
+            complex_paths.push(path);
+        }
+    }
+    if complex_paths.is_empty() {
+        return Err(
+            user_error("No conflicts with more than 2 sides found at this revision")
+                .hinted("Use `jj resolve` to resolve them with a 3-way merge tool."),
+        );
+    }
+    let matcher = FilesMatcher::new(&complex_paths);
+
+    let parents = commit.parents().await?;
+    if parents.len() <= 2 {
+        return Err(inherited_conflicts_error(&workspace_command, commit, &matcher).await?);
+    }
+    workspace_command.check_rewritable([commit.id()]).await?;
+
+    // Pick the pair of parents which can be merged with the fewest conflicts.
+    // Ties are broken by the order of the parents.
+    let repo = workspace_command.repo().clone();
+    let mut best_pair: Option<(usize, usize, usize, MergedTree)> = None;
+    for [i, j] in (0..parents.len()).array_combinations() {
+        let pair = [parents[i].clone(), parents[j].clone()];
+        let tree = merge_commit_trees(repo.as_ref(), &pair).await?;
+        let num_conflicts = tree.conflicts_matching(&matcher).count();
+        if best_pair
+            .as_ref()
+            .is_none_or(|&(min_conflicts, ..)| num_conflicts < min_conflicts)
+        {
+            best_pair = Some((num_conflicts, i, j, tree));
+        }
+    }
+    let (_, i, j, merged_tree) = best_pair.expect("commit should have more than 2 parents");
+
+    let mut tx = workspace_command.start_transaction();
+    let merge_commit = tx
+        .repo_mut()
+        .new_commit(
+            vec![parents[i].id().clone(), parents[j].id().clone()],
+            merged_tree,
+        )
+        .write()
+        .await?;
+    let new_parent_ids = parents
+        .iter()
+        .enumerate()
+        .filter(|&(k, _)| k != j)
+        .map(|(k, parent)| {
+            if k == i {
+                merge_commit.id().clone()
+            } else {
+                parent.id().clone()
+            }
+        })
+        .collect_vec();
+    rebase_commit(tx.repo_mut(), commit.clone(), new_parent_ids).await?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Created merge commit ")?;
+        tx.write_commit_summary(formatter.as_mut(), &merge_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!("Split conflicts in commit {}", commit.id().hex()),
+    )
+    .await?;
+
+    if merge_commit.has_conflict() {
+        writeln!(
+            ui.hint_default(),
+            "Resolve the conflicts in the new merge commit first, e.g. with `jj resolve -r {}`.",
+            short_change_hash(merge_commit.change_id())
+        )?;
+    }
+    Ok(())
+}
+
+/// Builds an error pointing to the closest ancestors whose conflicts at the
+/// matching paths were inherited by `commit`.
+async fn inherited_conflicts_error(
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    matcher: &FilesMatcher,
+) -> Result<CommandError, CommandError> {
+    let candidates: Vec<Commit> = workspace_command
+        .attach_revset_evaluator(
+            RevsetExpression::commit(commit.id().clone())
+                .parents()
+                .ancestors()
+                .filtered(RevsetFilterPredicate::HasConflict)
+                .minus(&workspace_command.env().immutable_expression()),
+        )
+        .evaluate_to_commits()?
+        .try_collect()
+        .await?;
+    let conflicted_ids = candidates
+        .iter()
+        .filter(|ancestor| ancestor.tree().conflicts_matching(matcher).next().is_some())
+        .map(|ancestor| ancestor.id().clone())
+        .collect_vec();
+    // Resolving the conflicts where they first appear helps the most.
+    let roots: Vec<Commit> = workspace_command
+        .attach_revset_evaluator(RevsetExpression::commits(conflicted_ids).roots())
+        .evaluate_to_commits()?
+        .try_collect()
+        .await?;
+
+    let mut err = user_error("Conflicts can only be split in commits with more than 2 parents");
+    if roots.is_empty() {
+        err.add_hint("Use `jj resolve --editor` to edit the conflict markers instead.");
+    } else {
+        let commands = roots
+            .iter()
+            .map(|ancestor| {
+                format!(
+                    "  jj resolve --split -r {}",
+                    short_change_hash(ancestor.change_id())
+                )
+            })
+            .join("\n");
+        err.add_hint(format!(
+            "The conflicts were inherited from these ancestors. Splitting or resolving them there \
+             would simplify the conflicts at this revision:\n{commands}"
+        ));
+    }
+    Ok(err)
+}
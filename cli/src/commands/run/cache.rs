@@ -0,0 +1,230 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persistent cache of `jj run` results.
+//!
+//! Each entry is a directory under `<repo>/run_cache/` named after the hash of
+//! the [`CacheKey`]. It contains `result.json` and the captured `stdout` and
+//! `stderr`. Entries are written to a temporary directory first and then
+//! renamed into place, so concurrent `jj run` processes never see partial
+//! entries.
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::time::SystemTime;
+
+use jj_lib::backend::TreeId;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::hex_util;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::ObjectId as _;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::CommandSpec;
+use super::executor::CommandExecutor;
+
+/// Identifies a command invocation whose result can be reused.
+///
+/// The key covers the input tree, the command and the directory it runs in,
+/// the executor configuration, the workspace pool (which may hold different
+/// build artifacts or toolchains), and the values of the environment variables
+/// listed in `run.cache.env`. The commit and change IDs only matter if they're
+/// listed there, so by default rewritten commits with the same content reuse
+/// the results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    pub fn new(
+        input_tree: &MergedTree,
+        spec: &CommandSpec,
+        executor: &dyn CommandExecutor,
+        pool_name: &str,
+        env: &[(String, Option<String>)],
+        passthrough: bool,
+    ) -> Self {
+        let tree_ids = input_tree
+            .tree_ids()
+            .iter()
+            .map(|id| id.hex())
+            .collect::<Vec<_>>();
+        let command = (
+            spec.program.clone(),
+            spec.args.clone(),
+            spec.subdir
+                .as_ref()
+                .map(|subdir| subdir.to_string_lossy().into_owned()),
+        );
+        // Passthrough runs don't capture any output to replay, so they can't
+        // share entries with regular runs.
+        let environment = (
+            executor.fingerprint(),
+            pool_name.to_owned(),
+            env.to_vec(),
+            passthrough,
+        );
+        let hash = blake2b_hash(&(tree_ids, command, environment));
+        Self(hex_util::encode_hex(hash.as_ref()))
+    }
+}
+
+/// The outcome of a command invocation, as stored in the cache.
+#[derive(Debug)]
+pub struct CachedResult {
+    /// The snapshotted tree if the command succeeded.
+    pub output_tree: Option<TreeId>,
+    /// Whether the command modified the working copy.
+    pub dirty: bool,
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ResultFile {
+    output_tree: Option<String>,
+    dirty: bool,
+    status: i32,
+}
+
+/// Directory of cached `jj run` results, shared by all workspaces of a repo.
+#[derive(Debug)]
+pub struct RunCache {
+    dir: PathBuf,
+}
+
+impl RunCache {
+    pub fn new(repo_path: &Path) -> Self {
+        Self {
+            dir: repo_path.join("run_cache"),
+        }
+    }
+
+    /// Looks up the result for `key`, refreshing the entry's timestamp so
+    /// `jj util gc` keeps entries that are still in use.
+    pub fn get(&self, key: &CacheKey) -> io::Result<Option<CachedResult>> {
+        let entry_dir = self.dir.join(&key.0);
+        let result_path = entry_dir.join("result.json");
+        let content = match fs::read(&result_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let result: ResultFile = serde_json::from_slice(&content)?;
+        let output_tree = match result.output_tree {
+            Some(hex) => Some(TreeId::try_from_hex(&hex).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("invalid tree id {hex}"))
+            })?),
+            None => None,
+        };
+        let stdout = fs::read(entry_dir.join("stdout"))?;
+        let stderr = fs::read(entry_dir.join("stderr"))?;
+        File::options()
+            .write(true)
+            .open(&result_path)?
+            .set_modified(SystemTime::now())?;
+        Ok(Some(CachedResult {
+            output_tree,
+            dirty: result.dirty,
+            status: exit_status_from_raw(result.status),
+            stdout,
+            stderr,
+        }))
+    }
+
+    pub fn insert(&self, key: &CacheKey, result: &CachedResult) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let temp_dir = tempfile::Builder::new()
+            .prefix(".tmp-")
+            .tempdir_in(&self.dir)?;
+        let result_file = ResultFile {
+            output_tree: result.output_tree.as_ref().map(|id| id.hex()),
+            dirty: result.dirty,
+            status: exit_status_to_raw(result.status),
+        };
+        fs::write(temp_dir.path().join("stdout"), &result.stdout)?;
+        fs::write(temp_dir.path().join("stderr"), &result.stderr)?;
+        fs::write(
+            temp_dir.path().join("result.json"),
+            serde_json::to_vec(&result_file)?,
+        )?;
+        let temp_path = temp_dir.keep();
+        match fs::rename(&temp_path, self.dir.join(&key.0)) {
+            Ok(()) => Ok(()),
+            // Another process stored the same result first.
+            Err(_) if self.dir.join(&key.0).is_dir() => fs::remove_dir_all(&temp_path),
+            Err(err) => {
+                fs::remove_dir_all(&temp_path).ok();
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes entries which weren't used since `keep_newer`. Returns the
+    /// number of removed entries.
+    pub fn gc(&self, keep_newer: SystemTime) -> io::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+        let mut count = 0;
+        for entry in entries {
+            let path = entry?.path();
+            // Leftover temporary directories don't have a result file, and are
+            // dated by the directory itself.
+            let modified = match fs::metadata(path.join("result.json")) {
+                Ok(metadata) => metadata.modified()?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    fs::metadata(&path)?.modified()?
+                }
+                Err(err) => return Err(err),
+            };
+            if modified < keep_newer {
+                fs::remove_dir_all(&path)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(unix)]
+fn exit_status_to_raw(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt as _;
+    status.into_raw()
+}
+
+#[cfg(unix)]
+fn exit_status_from_raw(raw: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt as _;
+    ExitStatus::from_raw(raw)
+}
+
+#[cfg(windows)]
+fn exit_status_to_raw(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(1)
+}
+
+#[cfg(windows)]
+fn exit_status_from_raw(raw: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt as _;
+    ExitStatus::from_raw(raw as u32)
+}
@@ -0,0 +1,242 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Executors which run the command of a `jj run` job.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::Output;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::settings::UserSettings;
+use tokio::process::Command;
+
+use super::CommandSpec;
+use crate::config::CommandNameAndArgs;
+
+/// Environment variables the sandboxed executor always passes through, on top
+/// of `run.sandbox.env`. Most Windows programs fail to start without them.
+const WINDOWS_REQUIRED_ENV: &[&str] = &["SystemRoot", "SystemDrive"];
+
+/// The executor selected by `run.executor`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ExecutorKind {
+    Local,
+    Sandboxed,
+    External,
+}
+
+/// A single command invocation against a checked-out commit.
+pub struct Invocation<'a> {
+    pub spec: &'a CommandSpec,
+    /// Root of the checked-out working copy. Whatever the command leaves here
+    /// is snapshotted into the rewritten commit.
+    pub working_copy_dir: &'a Path,
+    /// Directory the command should run in, somewhere below
+    /// `working_copy_dir`.
+    pub exec_dir: &'a Path,
+    /// Environment variables describing the commit, such as `JJ_COMMIT_ID`.
+    pub env: Vec<(&'static str, OsString)>,
+    /// Connect the command's stdout and stderr to the terminal instead of
+    /// capturing them.
+    pub passthrough: bool,
+}
+
+/// Runs the command of a `jj run` job.
+///
+/// Implementations decide where and how the command runs, but any changes it
+/// makes have to end up in the invocation's working copy, since that's what
+/// gets snapshotted once the returned future completes.
+pub trait CommandExecutor: fmt::Debug + Send + Sync {
+    fn execute<'a>(&'a self, invocation: &'a Invocation<'a>) -> BoxFuture<'a, io::Result<Output>>;
+
+    /// Describes the configuration which can affect the command's outcome.
+    /// Cached results are only reused if this matches.
+    fn fingerprint(&self) -> String;
+}
+
+/// Builds the executor configured by `run.executor`.
+pub fn executor_from_settings(
+    settings: &UserSettings,
+) -> Result<Arc<dyn CommandExecutor>, ConfigGetError> {
+    match settings.get::<ExecutorKind>("run.executor")? {
+        ExecutorKind::Local => Ok(Arc::new(LocalExecutor)),
+        ExecutorKind::Sandboxed => Ok(Arc::new(SandboxedExecutor {
+            wrapper: settings.get("run.sandbox.wrapper").optional()?,
+            env: settings.get("run.sandbox.env")?,
+        })),
+        ExecutorKind::External => Ok(Arc::new(ExternalExecutor {
+            command: settings.get("run.external.command")?,
+        })),
+    }
+}
+
+/// Runs the command as a child process of `jj`, inheriting its environment.
+#[derive(Debug)]
+pub struct LocalExecutor;
+
+impl CommandExecutor for LocalExecutor {
+    fn execute<'a>(&'a self, invocation: &'a Invocation<'a>) -> BoxFuture<'a, io::Result<Output>> {
+        Box::pin(async move {
+            let mut command = Command::new(&invocation.spec.program);
+            command.args(&invocation.spec.args);
+            run_process(command, invocation).await
+        })
+    }
+
+    fn fingerprint(&self) -> String {
+        "local".to_owned()
+    }
+}
+
+/// Runs the command as a child process with a scrubbed environment, optionally
+/// under a wrapper such as `bwrap` or `sandbox-exec`.
+///
+/// Only the variables listed in `run.sandbox.env` are passed through. `HOME`
+/// and `TMPDIR` point to a temporary directory which is deleted once the
+/// command exits, so the command can't keep state outside of its working copy
+/// through them.
+#[derive(Debug)]
+pub struct SandboxedExecutor {
+    /// Command prefix the job's command is appended to. `$root`, `$cwd`, and
+    /// `$tmp` are replaced with the working copy, the directory the command
+    /// runs in, and the temporary directory.
+    wrapper: Option<CommandNameAndArgs>,
+    /// Names of the environment variables to pass through.
+    env: Vec<String>,
+}
+
+impl CommandExecutor for SandboxedExecutor {
+    fn execute<'a>(&'a self, invocation: &'a Invocation<'a>) -> BoxFuture<'a, io::Result<Output>> {
+        Box::pin(async move {
+            let scratch_dir = tempfile::Builder::new().prefix("jj-run-").tempdir()?;
+            let (mut command, wrapper_env) = if let Some(wrapper) = &self.wrapper {
+                let variables = HashMap::from([
+                    ("root", invocation.working_copy_dir.to_string_lossy()),
+                    ("cwd", invocation.exec_dir.to_string_lossy()),
+                    ("tmp", scratch_dir.path().to_string_lossy()),
+                ]);
+                let wrapper = wrapper.to_command_with_variables(&variables);
+                // The wrapper's own `env` has to survive the `env_clear()`
+                // below.
+                let wrapper_env: Vec<_> = wrapper
+                    .get_envs()
+                    .filter_map(|(name, value)| Some((name.to_owned(), value?.to_owned())))
+                    .collect();
+                let mut command = Command::from(wrapper);
+                command.arg(&invocation.spec.program);
+                (command, wrapper_env)
+            } else {
+                (Command::new(&invocation.spec.program), vec![])
+            };
+            command.args(&invocation.spec.args).env_clear();
+            let required_env = if cfg!(windows) {
+                WINDOWS_REQUIRED_ENV
+            } else {
+                &[]
+            };
+            let inherited = self.env.iter().map(String::as_str);
+            for name in inherited.chain(required_env.iter().copied()) {
+                if let Some(value) = std::env::var_os(name) {
+                    command.env(name, value);
+                }
+            }
+            command
+                .envs(wrapper_env)
+                .env("HOME", scratch_dir.path())
+                .env("TMPDIR", scratch_dir.path());
+            let output = run_process(command, invocation).await;
+            drop(scratch_dir);
+            output
+        })
+    }
+
+    fn fingerprint(&self) -> String {
+        let wrapper = self
+            .wrapper
+            .as_ref()
+            .map(|wrapper| wrapper.split_name_and_args());
+        format!("sandboxed {wrapper:?} {:?}", self.env)
+    }
+}
+
+/// Hands the command to an external program configured in
+/// `run.external.command`, which gets the job's command appended to its
+/// arguments.
+///
+/// The program decides where the command runs, e.g. it can be a client which
+/// sends it to a build farm. `jj` doesn't implement any remote execution
+/// protocol itself, so the program has to write the outputs back into the
+/// working copy.
+#[derive(Debug)]
+pub struct ExternalExecutor {
+    /// Command prefix. `$root` and `$cwd` are replaced with the working copy
+    /// and the directory the command runs in.
+    command: CommandNameAndArgs,
+}
+
+impl CommandExecutor for ExternalExecutor {
+    fn execute<'a>(&'a self, invocation: &'a Invocation<'a>) -> BoxFuture<'a, io::Result<Output>> {
+        Box::pin(async move {
+            let variables = HashMap::from([
+                ("root", invocation.working_copy_dir.to_string_lossy()),
+                ("cwd", invocation.exec_dir.to_string_lossy()),
+            ]);
+            let mut command = Command::from(self.command.to_command_with_variables(&variables));
+            command
+                .arg(&invocation.spec.program)
+                .args(&invocation.spec.args);
+            run_process(command, invocation).await
+        })
+    }
+
+    fn fingerprint(&self) -> String {
+        format!("external {:?}", self.command.split_name_and_args())
+    }
+}
+
+/// Spawns `command` in the invocation's directory and waits for it to exit.
+async fn run_process(mut command: Command, invocation: &Invocation<'_>) -> io::Result<Output> {
+    command
+        .current_dir(invocation.exec_dir)
+        .envs(invocation.env.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if invocation.passthrough {
+        // Connect stdout/stderr directly to the terminal so TTY-aware
+        // programs work as expected. Capture is not possible; we wait
+        // for the process to exit and return empty stdout/stderr.
+        command.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        let status = command.status().await?;
+        Ok(Output {
+            status,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        })
+    } else {
+        // Pipe and buffer the subprocess's stdout/stderr so we can emit them
+        // atomically to the parent's stdout/stderr after the process exits.
+        // Writing concurrently from multiple jobs would interleave output.
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        command.spawn()?.wait_with_output().await
+    }
+}
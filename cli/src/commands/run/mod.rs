@@ -14,6 +14,10 @@
 
 //! This file contains the internal implementation of `run`.
 
+mod cache;
+mod executor;
+mod pool;
+
 use std::cmp::min;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -25,8 +29,6 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
-use std::process::Output;
-use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -40,8 +42,6 @@ use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreFile;
-use jj_lib::local_working_copy::EolConversionMode;
-use jj_lib::local_working_copy::ExecChangeSetting;
 use jj_lib::local_working_copy::TreeState;
 use jj_lib::local_working_copy::TreeStateError;
 use jj_lib::local_working_copy::TreeStateSettings;
@@ -55,6 +55,8 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::settings::HumanByteSize;
+use jj_lib::settings::UserSettings;
 use jj_lib::tree::Tree;
 use jj_lib::working_copy::SnapshotOptions;
 use tokio::runtime::Builder;
@@ -65,12 +67,22 @@ use tokio::task::JoinError;
 use tokio::task::JoinSet;
 use tokio::time::sleep;
 
+use self::cache::CacheKey;
+use self::cache::CachedResult;
+pub use self::cache::RunCache;
+use self::executor::CommandExecutor;
+use self::executor::Invocation;
+use self::executor::executor_from_settings;
+use self::pool::RunPoolCommand;
+use self::pool::pool_root;
+use self::pool::slot_lock_path;
+use self::pool::validate_pool_name;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
-use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::CommandError;
 use crate::command_error::CommandErrorKind;
+use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
 #[derive(Debug, thiserror::Error)]
@@ -95,6 +107,8 @@ enum RunError {
     FileLock(#[from] FileLockError),
     #[error("invalid value for `run.jobs`: {0} (must be a positive integer)")]
     InvalidJobCount(i64),
+    #[error("invalid workspace pool name {0:?}")]
+    InvalidPoolName(String),
 }
 
 impl From<RunError> for CommandError {
@@ -103,13 +117,15 @@ impl From<RunError> for CommandError {
     }
 }
 
-fn default_tree_state_settings() -> TreeStateSettings {
-    TreeStateSettings {
+/// Working-copy settings for the pool's slots. EOL conversion and the
+/// executable bit follow the user's settings, but conflicts are always
+/// materialized in the snapshot style and the slots aren't monitored.
+fn tree_state_settings(settings: &UserSettings) -> Result<TreeStateSettings, CommandError> {
+    Ok(TreeStateSettings {
         conflict_marker_style: ConflictMarkerStyle::Snapshot,
-        eol_conversion_mode: EolConversionMode::None,
-        exec_change_setting: ExecChangeSetting::Auto,
         fsmonitor_settings: FsmonitorSettings::None,
-    }
+        ..TreeStateSettings::try_from_user_settings(settings)?
+    })
 }
 
 /// A workspace that's ready for a single job to run against.
@@ -134,7 +150,7 @@ impl RunWorkspace {
 
 /// A command, its arguments, and the workspace-relative directory it should
 /// run in.
-struct CommandSpec {
+pub(crate) struct CommandSpec {
     program: String,
     args: Vec<String>,
     /// Working directory for the subprocess, relative to the workspace root.
@@ -153,16 +169,20 @@ impl fmt::Display for CommandSpec {
     }
 }
 
-/// Manages a fixed-size pool of workspaces under `.jj/run/default/`.
+/// Manages a fixed-size pool of workspaces, e.g. under `.jj/run/default/`.
 ///
-/// Each workspace lives at `.jj/run/default/N/` with subdirs `working_copy/`
-/// and `state/`. Its lockfile is the sibling `.jj/run/default/N.lock`.
-/// Workspaces persist between `jj run` invocations so build artifacts can be
-/// reused. Acquisition picks the first free workspace, so multiple concurrent
-/// `jj run` processes cooperatively share the pool.
+/// Each workspace lives at `<pool>/N/` with subdirs `working_copy/` and
+/// `state/`. Its lockfile is the sibling `<pool>/N.lock`. Workspaces persist
+/// between `jj run` invocations so build artifacts can be reused. Acquisition
+/// picks the first free workspace, so multiple concurrent `jj run` processes
+/// cooperatively share the pool.
 struct WorkspacePool {
     base_path: PathBuf,
     size: NonZeroUsize,
+    tree_state_settings: TreeStateSettings,
+    /// Untracked files larger than this are left out of the rewritten commit,
+    /// as per `snapshot.max-new-file-size`.
+    max_new_file_size: u64,
     /// Determines which untracked files left in a slot get pulled into the
     /// rewritten commit. Loaded once from `snapshot.auto-track`; essentially
     /// the user's `.gitignore` story for what counts as a build artifact.
@@ -174,17 +194,20 @@ struct WorkspacePool {
 
 impl WorkspacePool {
     fn new(
-        repo_path: &Path,
+        base_path: PathBuf,
         size: NonZeroUsize,
+        tree_state_settings: TreeStateSettings,
+        max_new_file_size: u64,
         auto_tracking_matcher: Box<dyn Matcher>,
         clean: bool,
     ) -> Result<Self, RunError> {
-        // The parent() call is needed to not write under `.jj/repo/`.
-        let base_path = repo_path.parent().unwrap().join("run").join("default");
-        fs::create_dir_all(&base_path)?;
+        fs::create_dir_all(&base_path)
+            .map_err(|e| RunError::PathCreationFailure(base_path.clone(), e))?;
         Ok(Self {
             base_path,
             size,
+            tree_state_settings,
+            max_new_file_size,
             auto_tracking_matcher,
             clean,
         })
@@ -213,7 +236,7 @@ impl WorkspacePool {
         let tree_state_path = state_dir.join("tree_state");
 
         let is_reused_workspace = tree_state_path.exists();
-        let settings = default_tree_state_settings();
+        let settings = &self.tree_state_settings;
         let mut tree_state = if !self.clean && is_reused_workspace {
             // Load the persisted tree state so `check_out` below can diff
             // against it, only touching files that changed and removing files
@@ -227,7 +250,7 @@ impl WorkspacePool {
                 commit.store().clone(),
                 working_copy_dir.clone(),
                 state_dir.clone(),
-                &settings,
+                settings,
             )?;
             fs::remove_file(&tree_state_path)?;
             ts
@@ -257,7 +280,7 @@ impl WorkspacePool {
                 commit.store().clone(),
                 working_copy_dir.clone(),
                 state_dir,
-                &settings,
+                settings,
             )
         };
 
@@ -322,8 +345,7 @@ impl WorkspacePool {
             base_ignores,
             start_tracking_matcher: self.auto_tracking_matcher.as_ref(),
             progress: None,
-            // TODO: read from current wc/settings
-            max_new_file_size: 64_000_u64, // 64 MB for now
+            max_new_file_size: self.max_new_file_size,
             force_tracking_matcher: &NothingMatcher,
         }
     }
@@ -333,7 +355,7 @@ impl WorkspacePool {
     }
 
     fn slot_lock_path(&self, index: usize) -> PathBuf {
-        self.base_path.join(format!("{index}.lock"))
+        slot_lock_path(&self.base_path, index)
     }
 
     /// Try to acquire any slot's lock without blocking. Returns the slot
@@ -374,21 +396,90 @@ struct RunJob {
     skipped: bool,
     /// Exit status of the command, if it ran.
     status: Option<ExitStatus>,
+    /// True if the result was taken from the cache instead of running the
+    /// command.
+    cached: bool,
+}
+
+/// Everything a job needs besides the commit it runs on.
+struct JobContext {
+    spec: CommandSpec,
+    executor: Arc<dyn CommandExecutor>,
+    pool: WorkspacePool,
+    pool_name: String,
+    cache: RunCache,
+    /// The variables listed in `run.cache.env`, with their values in `jj`'s
+    /// environment. Values of the per-commit `JJ_*` variables are filled in
+    /// for each job.
+    cache_env: Vec<(String, Option<String>)>,
+    /// Whether to look up results in the cache before running the command.
+    use_cache: bool,
+    passthrough: bool,
+    /// Whether to pass the commit description in `JJ_DESCRIPTION`. The
+    /// description isn't part of the cache key, so this shouldn't be set if
+    /// the cache is used.
+    pass_description: bool,
+}
+
+impl JobContext {
+    /// Loads the executor, pool, and cache settings to run `spec` in the named
+    /// pool.
+    fn new(
+        ui: &Ui,
+        workspace_command: &WorkspaceCommandHelper,
+        spec: CommandSpec,
+        pool_name: String,
+        jobs: NonZeroUsize,
+        clean: bool,
+    ) -> Result<Self, CommandError> {
+        let settings = workspace_command.settings();
+        let repo_path = workspace_command.repo_path();
+        let pool_path = pool_root(settings, repo_path)?.join(&pool_name);
+        let executor = executor_from_settings(settings)?;
+        let cache_env = settings
+            .get::<Vec<String>>("run.cache.env")?
+            .into_iter()
+            .map(|name| {
+                let value = std::env::var(&name).ok();
+                (name, value)
+            })
+            .collect();
+        let HumanByteSize(mut max_new_file_size) =
+            settings.get_value_with("snapshot.max-new-file-size", TryInto::try_into)?;
+        if max_new_file_size == 0 {
+            max_new_file_size = u64::MAX;
+        }
+        let pool = WorkspacePool::new(
+            pool_path,
+            jobs,
+            tree_state_settings(settings)?,
+            max_new_file_size,
+            workspace_command.auto_tracking_matcher(ui)?,
+            clean,
+        )?;
+        Ok(Self {
+            spec,
+            executor,
+            pool,
+            pool_name,
+            cache: RunCache::new(repo_path),
+            cache_env,
+            use_cache: !clean,
+            passthrough: false,
+            pass_description: false,
+        })
+    }
 }
 
 // TODO: make this more revset/commit stream friendly.
-#[allow(clippy::too_many_arguments)]
 async fn run_inner(
-    tx: &WorkspaceCommandTransaction<'_>,
+    base_ignores: Arc<GitIgnoreFile>,
     sender: Sender<RunJob>,
     handle: &tokio::runtime::Handle,
-    spec: Arc<CommandSpec>,
-    pool: Arc<WorkspacePool>,
+    ctx: Arc<JobContext>,
     commits: &[Commit],
     jobs: usize,
-    passthrough: bool,
 ) -> Result<(), RunError> {
-    let base_ignores = tx.base_workspace_helper().base_ignores().unwrap().clone();
     let semaphore = Arc::new(Semaphore::new(jobs));
     let mut command_futures: JoinSet<Result<RunJob, RunError>> = JoinSet::new();
     for commit in commits {
@@ -399,14 +490,13 @@ async fn run_inner(
             .await
             .expect("semaphore not closed");
         let base_ignores = base_ignores.clone();
-        let pool = pool.clone();
+        let ctx = ctx.clone();
         let commit = commit.clone();
-        let spec = spec.clone();
         command_futures.spawn_on(
             async move {
                 let _permit = permit;
                 // TODO: handle/propagate error here
-                rewrite_commit(base_ignores, pool, commit, spec, passthrough).await
+                rewrite_commit(base_ignores, &ctx, commit).await
             },
             handle,
         );
@@ -429,16 +519,42 @@ async fn run_inner(
     Ok(())
 }
 
-/// Run `spec` against `commit`. The caller is responsible for committing any
-/// returned new tree to the repo.
+/// Run the command against `commit`, or look up the result of a previous run
+/// in the cache. The caller is responsible for committing any returned new tree
+/// to the repo.
 async fn rewrite_commit(
     base_ignores: Arc<GitIgnoreFile>,
-    pool: Arc<WorkspacePool>,
+    ctx: &JobContext,
     commit: Commit,
-    spec: Arc<CommandSpec>,
-    passthrough: bool,
 ) -> Result<RunJob, RunError> {
-    let mut workspace = pool.acquire(&commit, base_ignores.clone()).await?;
+    let spec = &ctx.spec;
+    let cache_env = ctx
+        .cache_env
+        .iter()
+        .map(|(name, value)| {
+            let value = match name.as_str() {
+                "JJ_CHANGE_ID" => Some(commit.change_id().reverse_hex()),
+                "JJ_COMMIT_ID" => Some(commit.id().hex()),
+                _ => value.clone(),
+            };
+            (name.clone(), value)
+        })
+        .collect_vec();
+    let cache_key = CacheKey::new(
+        &commit.tree(),
+        spec,
+        ctx.executor.as_ref(),
+        &ctx.pool_name,
+        &cache_env,
+        ctx.passthrough,
+    );
+    if ctx.use_cache
+        && let Some(job) = cached_job(&ctx.cache, &cache_key, &commit).await
+    {
+        return Ok(job);
+    }
+
+    let mut workspace = ctx.pool.acquire(&commit, base_ignores.clone()).await?;
     let working_copy_dir = workspace.working_copy_dir.clone();
     let old_id = commit.id().clone();
     let old_tree = commit.tree();
@@ -465,6 +581,7 @@ async fn rewrite_commit(
                 stderr: Vec::new(),
                 skipped: true,
                 status: None,
+                cached: false,
             });
         }
         exec_dir
@@ -472,45 +589,30 @@ async fn rewrite_commit(
         working_copy_dir.clone()
     };
 
-    // TODO: Later this should take some trait which allows `run` to integrate with
-    // something like Bazels RE protocol.
-    // e.g
-    // ```
-    // let mut executor /* Arc<dyn CommandExecutor> */ = store.get_executor();
-    // let command = executor.spawn(...)?; // RE or separate processes depending on impl.
-    // ...
-    // ```
-    tracing::debug!("trying to run command '{}' on commit {}", spec, commit.id());
-
-    let mut command = tokio::process::Command::new(&spec.program);
-    command
-        .args(&spec.args)
-        .current_dir(&exec_dir)
-        .env("JJ_WORKSPACE_ROOT", &working_copy_dir)
-        .env("JJ_CHANGE_ID", commit.change_id().reverse_hex())
-        .env("JJ_COMMIT_ID", commit.id().hex())
-        .stdin(Stdio::null())
-        .kill_on_drop(true);
-    let output = if passthrough {
-        // Connect stdout/stderr directly to the terminal so TTY-aware
-        // programs work as expected. Capture is not possible; we wait
-        // for the process to exit and return empty stdout/stderr.
-        command.stdout(Stdio::inherit()).stderr(Stdio::inherit());
-        let status = command.status().await?;
-        Output {
-            status,
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        }
-    } else {
-        // Pipe and buffer the subprocess's stdout/stderr so we can emit them
-        // atomically to the parent's stdout/stderr after the process exits.
-        // Writing concurrently from multiple jobs would interleave output.
-        command.stdout(Stdio::piped()).stderr(Stdio::piped());
-        command.spawn()?.wait_with_output().await?
+    tracing::debug!(
+        executor = ?ctx.executor,
+        "trying to run command '{}' on commit {}",
+        spec,
+        commit.id()
+    );
+    let mut env = vec![
+        ("JJ_WORKSPACE_ROOT", working_copy_dir.clone().into()),
+        ("JJ_CHANGE_ID", commit.change_id().reverse_hex().into()),
+        ("JJ_COMMIT_ID", commit.id().hex().into()),
+    ];
+    if ctx.pass_description {
+        env.push(("JJ_DESCRIPTION", commit.description().into()));
+    }
+    let invocation = Invocation {
+        spec,
+        working_copy_dir: &working_copy_dir,
+        exec_dir: &exec_dir,
+        env,
+        passthrough: ctx.passthrough,
     };
+    let output = ctx.executor.execute(&invocation).await?;
 
-    let options = pool.snapshot_options(base_ignores);
+    let options = ctx.pool.snapshot_options(base_ignores);
     tracing::debug!("trying to snapshot the new tree");
     let (dirty, stats) = workspace.tree_state.snapshot(&options).await.unwrap();
     if !dirty {
@@ -546,21 +648,66 @@ async fn rewrite_commit(
 
         Some(commit.store().get_tree(RepoPathBuf::root(), new_id).await?)
 
-        // TODO: Serialize the new tree into /output/{id-tree} for a cache
-        // lookup TODO: supersede with a custom workspace implementation
+        // TODO: supersede with a custom workspace implementation
     } else {
         None
     };
 
+    let result = CachedResult {
+        output_tree: new_tree.as_ref().map(|tree| tree.id().clone()),
+        dirty,
+        status: output.status,
+        stdout: output.stdout,
+        stderr: output.stderr,
+    };
+    if let Err(err) = ctx.cache.insert(&cache_key, &result) {
+        tracing::warn!(?err, "failed to store the result in the run cache");
+    }
+
     Ok(RunJob {
         old_id,
         old_tree,
         new_tree,
         dirty,
-        stdout: output.stdout,
-        stderr: output.stderr,
+        stdout: result.stdout,
+        stderr: result.stderr,
         skipped: false,
-        status: Some(output.status),
+        status: Some(result.status),
+        cached: false,
+    })
+}
+
+/// Builds the job result from a cached result. Returns `None` if there's no
+/// usable entry, e.g. because the output tree was garbage-collected since.
+async fn cached_job(cache: &RunCache, key: &CacheKey, commit: &Commit) -> Option<RunJob> {
+    let result = match cache.get(key) {
+        Ok(result) => result?,
+        Err(err) => {
+            tracing::warn!(?err, "failed to read the run cache");
+            return None;
+        }
+    };
+    let new_tree = match &result.output_tree {
+        Some(id) => match commit.store().get_tree(RepoPathBuf::root(), id).await {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                tracing::debug!(?err, "cached output tree is no longer available");
+                return None;
+            }
+        },
+        None => None,
+    };
+    tracing::debug!(commit = commit.id().hex(), "using cached result");
+    Some(RunJob {
+        old_id: commit.id().clone(),
+        old_tree: commit.tree(),
+        new_tree,
+        dirty: result.dirty,
+        stdout: result.stdout,
+        stderr: result.stderr,
+        skipped: false,
+        status: Some(result.status),
+        cached: true,
     })
 }
 
@@ -577,6 +724,17 @@ async fn rewrite_commit(
 /// - JJ_COMMIT_ID
 /// - JJ_WORKSPACE_ROOT
 ///
+/// The command runs as a local process by default. Set `run.executor` to run
+/// it in a sandbox or to pass it to an external program instead.
+///
+/// Results are cached, so revisions whose content didn't change since a
+/// previous run of the same command reuse that run's result instead of running
+/// the command again. Use `--no-cache` to always run the command.
+///
+/// The working copies are kept between runs so build artifacts can be reused.
+/// They're grouped in named pools, e.g. one per toolchain, which are selected
+/// with `--pool`. Use `jj run pool` to list and clean them up.
+///
 /// # Example
 ///
 /// ```shell
@@ -585,10 +743,15 @@ async fn rewrite_commit(
 /// ```
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[command(subcommand_value_name = "SUBCOMMAND")]
 pub struct RunArgs {
+    #[command(subcommand)]
+    subcommand: Option<RunCommand>,
+
     /// Command to run across all selected revisions
-    #[arg(value_name = "COMMAND")]
-    command: String,
+    #[arg(value_name = "COMMAND", required = true)]
+    command: Option<String>,
 
     /// Arguments to pass to the command
     ///
@@ -622,6 +785,7 @@ pub struct RunArgs {
     /// By default `jj run` reuses working copies between invocations so build
     /// artifacts are preserved. With `--clean`, every commit starts from a
     /// freshly checked-out tree.
+    /// Implies `--no-cache`.
     #[arg(long)]
     clean: bool,
 
@@ -638,6 +802,105 @@ pub struct RunArgs {
     /// since parallel jobs would interleave their output.
     #[arg(long)]
     passthrough: bool,
+
+    /// Run the command even if a cached result exists
+    ///
+    /// The new results are still stored in the cache.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// The workspace pool to run the command in
+    ///
+    /// Overrides the `run.pool` config setting. Pools don't share working
+    /// copies, so separate pools keep separate build artifacts.
+    #[arg(long, value_name = "NAME")]
+    pool: Option<String>,
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+enum RunCommand {
+    #[command(subcommand)]
+    Pool(RunPoolCommand),
+}
+
+fn new_runtime() -> tokio::runtime::Runtime {
+    let mut builder = Builder::new_multi_thread();
+    builder.enable_io();
+    builder.enable_time();
+    builder.build().unwrap()
+}
+
+/// Runs a command against each of the commits in the named pool like `jj run`,
+/// but doesn't rewrite the commits. The results aren't looked up in the cache,
+/// and the commit description is passed in `JJ_DESCRIPTION`.
+///
+/// The output of the command is printed to stderr. Returns the commits the
+/// command failed on.
+pub(crate) async fn check_commits(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    pool_name: &str,
+    command: &CommandNameAndArgs,
+    commits: &[Commit],
+) -> Result<Vec<(Commit, ExitStatus)>, CommandError> {
+    validate_pool_name(pool_name)?;
+    let (program, args) = command.split_name_and_args();
+    let spec = CommandSpec {
+        program: program.into_owned(),
+        args: args.into_owned(),
+        subdir: None,
+    };
+    let jobs = resolve_jobs(workspace_command, None)?;
+    let mut ctx = JobContext::new(
+        ui,
+        workspace_command,
+        spec,
+        pool_name.to_owned(),
+        jobs,
+        false,
+    )?;
+    ctx.use_cache = false;
+    ctx.pass_description = true;
+    let ctx = Arc::new(ctx);
+    let base_ignores = workspace_command.base_ignores()?;
+
+    let rt = new_runtime();
+    let (sender_tx, mut receiver) = mpsc::channel(jobs.get());
+    let mut failures = vec![];
+    futures::try_join!(
+        async {
+            run_inner(
+                base_ignores,
+                sender_tx,
+                rt.handle(),
+                ctx.clone(),
+                commits,
+                jobs.get(),
+            )
+            .await
+            .map_err(CommandError::from)
+        },
+        async {
+            while let Some(res) = receiver.recv().await {
+                let Some(status) = res.status else {
+                    continue;
+                };
+                let mut err = ui.stderr();
+                err.write_all(&res.stdout)?;
+                err.write_all(&res.stderr)?;
+                drop(err);
+                if !status.success() {
+                    let commit = commits
+                        .iter()
+                        .find(|commit| *commit.id() == res.old_id)
+                        .expect("result should be of one of the commits");
+                    failures.push((commit.clone(), status));
+                }
+            }
+            Ok::<_, CommandError>(())
+        },
+    )?;
+    Ok(failures)
 }
 
 /// Precedence: `--jobs`, `run.jobs` config, 1.
@@ -674,10 +937,15 @@ pub async fn cmd_run(
     command: &CommandHelper,
     args: &RunArgs,
 ) -> Result<(), CommandError> {
-    let repo_path = command.workspace_loader()?.repo_path().to_path_buf();
-    // TODO: should be stored in a backend and not hardcoded.
-    let base_path = repo_path.parent().unwrap().join("run").join("default");
-    fs::create_dir_all(&base_path)?;
+    if let Some(RunCommand::Pool(subcommand)) = &args.subcommand {
+        return pool::cmd_run_pool(ui, command, subcommand);
+    }
+    let program = args.command.clone().expect("required by clap");
+    let pool_name = match &args.pool {
+        Some(name) => name.clone(),
+        None => command.settings().get_string("run.pool")?,
+    };
+    validate_pool_name(&pool_name)?;
 
     let mut workspace_command = command.workspace_helper(ui).await?;
     let mut resolved_commits: Vec<_> = if args.revisions.is_empty() {
@@ -727,32 +995,25 @@ pub async fn cmd_run(
     };
 
     let store = workspace_command.repo().store().clone();
-    let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+    let spec = CommandSpec {
+        program,
+        args: args.args.clone(),
+        subdir,
+    };
+    let mut ctx = JobContext::new(ui, &workspace_command, spec, pool_name, jobs, args.clean)?;
+    ctx.use_cache &= !args.no_cache;
+    ctx.passthrough = args.passthrough;
+    let ctx = Arc::new(ctx);
+    let base_ignores = workspace_command.base_ignores()?;
 
     let mut tx = workspace_command.start_transaction();
 
-    let rt = {
-        let mut builder = Builder::new_multi_thread();
-        builder.enable_io();
-        builder.enable_time();
-        builder.build().unwrap()
-    };
+    let rt = new_runtime();
     let mut done_commits = HashSet::new();
     let (sender_tx, mut receiver) = mpsc::channel(jobs.get());
-
-    let pool = Arc::new(WorkspacePool::new(
-        &repo_path,
-        jobs,
-        auto_tracking_matcher,
-        args.clean,
-    )?);
-
-    let spec = Arc::new(CommandSpec {
-        program: args.command.clone(),
-        args: args.args.clone(),
-        subdir,
-    });
+    let spec = &ctx.spec;
     let mut rewritten_commits = HashMap::new();
+    let mut num_cached: u32 = 0;
 
     // Drive the producer (run_inner) and consumer (receive loop) concurrently
     // so that each subprocess's output is emitted as soon as it finishes rather
@@ -760,20 +1021,21 @@ pub async fn cmd_run(
     futures::try_join!(
         async {
             run_inner(
-                &tx,
+                base_ignores,
                 sender_tx,
                 rt.handle(),
-                spec.clone(),
-                pool.clone(),
+                ctx.clone(),
                 &resolved_commits,
                 jobs.get(),
-                args.passthrough,
             )
             .await
             .map_err(CommandError::from)
         },
         async {
             while let Some(res) = receiver.recv().await {
+                if res.cached {
+                    num_cached += 1;
+                }
                 if res.skipped {
                     writeln!(
                         ui.stderr(),
@@ -803,6 +1065,12 @@ pub async fn cmd_run(
                                 write!(formatter, "Failed revision: ")?;
                                 tx.write_commit_summary(formatter, &commit)
                             });
+                            if res.cached {
+                                error.add_hint(
+                                    "The failure was recorded by a previous run. Use `--no-cache` \
+                                     to run the command again.",
+                                );
+                            }
                             return Err(error);
                         }
                     }
@@ -818,6 +1086,13 @@ pub async fn cmd_run(
         },
     )?;
 
+    if num_cached > 0 {
+        writeln!(
+            ui.stderr(),
+            "Reused cached results for {num_cached} commits."
+        )?;
+    }
+
     // The operation was a no-op, bail.
     if rewritten_commits.is_empty() {
         tx.finish(ui, "run: nothing changed").await?;
@@ -0,0 +1,254 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Location and maintenance of the workspace pools used by `jj run`.
+//!
+//! Pools live in `run.pool-dir`, which defaults to `.jj/run/`. If it's an
+//! absolute path, each repository gets its own subdirectory. Each pool is a
+//! directory named after the pool, holding numbered slots (`N/`) and their
+//! lock files (`N.lock`).
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::content_hash::blake2b_hash;
+use jj_lib::file_util::expand_home_path;
+use jj_lib::hex_util;
+use jj_lib::lock::FileLock;
+use jj_lib::settings::UserSettings;
+
+use super::RunError;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+/// Manage the workspace pools of `jj run`
+///
+/// `jj run` keeps the working copies it runs commands in, so build artifacts
+/// can be reused by the next run. Working copies are grouped in named pools,
+/// which are selected with `jj run --pool` or the `run.pool` setting.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum RunPoolCommand {
+    Clean(RunPoolCleanArgs),
+    List(RunPoolListArgs),
+}
+
+/// List the workspace pools and their working copies
+#[derive(clap::Args, Clone, Debug)]
+pub struct RunPoolListArgs {}
+
+/// Delete the working copies of workspace pools
+///
+/// Working copies which are in use by a running `jj run` are left alone.
+#[derive(clap::Args, Clone, Debug)]
+pub struct RunPoolCleanArgs {
+    /// The pools to clean
+    #[arg(value_name = "POOLS", required_unless_present = "all")]
+    pools: Vec<String>,
+
+    /// Clean all pools
+    #[arg(long, conflicts_with = "pools")]
+    all: bool,
+}
+
+pub fn cmd_run_pool(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &RunPoolCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        RunPoolCommand::Clean(args) => cmd_run_pool_clean(ui, command, args),
+        RunPoolCommand::List(args) => cmd_run_pool_list(ui, command, args),
+    }
+}
+
+fn cmd_run_pool_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &RunPoolListArgs,
+) -> Result<(), CommandError> {
+    let repo_path = command.workspace_loader()?.repo_path().to_path_buf();
+    let root = pool_root(command.settings(), &repo_path)?;
+    let mut formatter = ui.stdout_formatter();
+    for name in list_pools(&root)? {
+        let pool_path = root.join(&name);
+        let slots = list_slots(&pool_path)?;
+        let mut in_use = 0;
+        for &slot in &slots {
+            if FileLock::try_lock(slot_lock_path(&pool_path, slot))
+                .map_err(RunError::from)?
+                .is_none()
+            {
+                in_use += 1;
+            }
+        }
+        write!(formatter, "{name}: {} working copies", slots.len())?;
+        if in_use > 0 {
+            write!(formatter, " ({in_use} in use)")?;
+        }
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
+
+fn cmd_run_pool_clean(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &RunPoolCleanArgs,
+) -> Result<(), CommandError> {
+    let repo_path = command.workspace_loader()?.repo_path().to_path_buf();
+    let root = pool_root(command.settings(), &repo_path)?;
+    let names = if args.all {
+        list_pools(&root)?
+    } else {
+        for name in &args.pools {
+            validate_pool_name(name)?;
+            if !root.join(name).is_dir() {
+                return Err(user_error(format!("No such workspace pool: {name}")));
+            }
+        }
+        args.pools.clone()
+    };
+    for name in &names {
+        let pool_path = root.join(name);
+        let mut removed = 0;
+        let mut in_use = 0;
+        for slot in list_slots(&pool_path)? {
+            let Some(lock) =
+                FileLock::try_lock(slot_lock_path(&pool_path, slot)).map_err(RunError::from)?
+            else {
+                in_use += 1;
+                continue;
+            };
+            let slot_path = pool_path.join(slot.to_string());
+            fs::remove_dir_all(&slot_path)
+                .map_err(|err| RunError::PathDeletionFailure(slot_path, err))?;
+            drop(lock);
+            removed += 1;
+        }
+        if in_use == 0 {
+            // Fails if another `jj run` started using the pool in the meantime,
+            // which is fine.
+            fs::remove_dir(&pool_path).ok();
+        }
+        writeln!(
+            ui.status(),
+            "Removed {removed} working copies from pool {name}."
+        )?;
+        if in_use > 0 {
+            writeln!(
+                ui.warning_default(),
+                "Skipped {in_use} working copies of pool {name} which are in use."
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the directory the pools are stored in.
+///
+/// Relative paths in `run.pool-dir` are resolved against the `.jj` directory.
+/// An absolute directory may be shared by several repositories, so the pools
+/// are stored in a subdirectory named after a hash of the repository path.
+pub fn pool_root(settings: &UserSettings, repo_path: &Path) -> Result<PathBuf, ConfigGetError> {
+    // The parent() call is needed to not write under `.jj/repo/`.
+    let jj_dir = repo_path.parent().unwrap();
+    let dir = settings
+        .get_string("run.pool-dir")
+        .optional()?
+        .map_or_else(|| PathBuf::from("run"), |dir| expand_home_path(&dir));
+    if dir.is_absolute() {
+        Ok(dir.join(repo_key(repo_path)))
+    } else {
+        Ok(jj_dir.join(dir))
+    }
+}
+
+/// Returns a directory name identifying the repository at `repo_path`.
+fn repo_key(repo_path: &Path) -> String {
+    let hash = blake2b_hash(repo_path.as_os_str().as_encoded_bytes());
+    hex_util::encode_hex(&hash[..16])
+}
+
+/// Pool names become directory names, so they must be a single normal path
+/// component. Names starting with `.` are reserved.
+pub fn validate_pool_name(name: &str) -> Result<(), RunError> {
+    let is_valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && !name.ends_with(".lock");
+    if is_valid {
+        Ok(())
+    } else {
+        Err(RunError::InvalidPoolName(name.to_owned()))
+    }
+}
+
+pub fn slot_lock_path(pool_path: &Path, index: usize) -> PathBuf {
+    pool_path.join(format!("{index}.lock"))
+}
+
+/// Returns the names of the pools in `root`, sorted.
+fn list_pools(root: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+    let mut names = vec![];
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Ok(name) = entry.file_name().into_string()
+            && validate_pool_name(&name).is_ok()
+        {
+            names.push(name);
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Returns the indices of the slots in the pool at `pool_path`, sorted.
+fn list_slots(pool_path: &Path) -> io::Result<Vec<usize>> {
+    let entries = match fs::read_dir(pool_path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+    let mut slots = vec![];
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Some(index) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        {
+            slots.push(index);
+        }
+    }
+    slots.sort_unstable();
+    Ok(slots)
+}
@@ -14,10 +14,14 @@
 
 use std::collections::BTreeMap;
 
+use futures::StreamExt as _;
 use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
+use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopyRecords;
+use jj_lib::copies::get_copy_records;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
 use jj_lib::merged_tree::MergedTree;
@@ -31,13 +35,21 @@ use jj_lib::working_copy::UntrackedReason;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::print_snapshot_stats;
 use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::write_conflicted_paths_json;
+use crate::cli_util::write_json_line;
 use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::diff_util::DiffFormat;
-use crate::diff_util::get_copy_records;
+use crate::diff_util::diff_status;
+use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
+use crate::ui::OutputFormat;
 use crate::ui::Ui;
 
 /// Show high-level repo status [default alias: st]
@@ -82,6 +94,30 @@ pub(crate) async fn cmd_status(
         .transpose()?;
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
     let matcher = fileset_expression.to_matcher();
+    if ui.output_format() == OutputFormat::Json {
+        let status = match &maybe_wc_commit {
+            Some(wc_commit) => {
+                let status =
+                    collect_working_copy_status(repo.as_ref(), wc_commit, snapshot_stats).await?;
+                print_unmatched_explicit_paths(
+                    ui,
+                    &workspace_command,
+                    &fileset_expression,
+                    [&status.tree],
+                )?;
+                Some(status)
+            }
+            None => None,
+        };
+        let mut formatter = ui.stdout_formatter();
+        return write_status_json(
+            formatter.as_mut(),
+            repo.as_ref(),
+            status.as_ref(),
+            matcher.as_ref(),
+        )
+        .await;
+    }
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -121,7 +157,7 @@ pub(crate) async fn cmd_status(
                     .await?;
                 if !diff_output.is_empty() {
                     writeln!(formatter, "Working copy changes:")?;
-                    formatter.raw()?.write_all(&diff_output)?;
+                    formatter.raw_formatted()?.write_all(&diff_output)?;
                 }
             }
 
@@ -197,6 +233,13 @@ pub(crate) async fn cmd_status(
                 }
             }
         }
+
+        if workspace_command
+            .settings()
+            .get_bool("working-copy.update-submodules")?
+        {
+            print_outdated_submodules(formatter, &workspace_command, &status.tree, &matcher)?;
+        }
     } else {
         writeln!(formatter, "No working copy.")?;
     }
@@ -249,6 +292,161 @@ pub(crate) async fn cmd_status(
     Ok(())
 }
 
+/// Item of `jj status --output=json`.
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StatusItem<'a> {
+    WorkingCopy {
+        commit: &'a Commit,
+    },
+    Parent {
+        commit: &'a Commit,
+    },
+    Change {
+        status: &'static str,
+        path: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source: Option<&'a str>,
+    },
+    Untracked {
+        path: &'a str,
+        is_dir: bool,
+    },
+    ConflictedBookmark {
+        name: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        remote: Option<&'a str>,
+    },
+}
+
+/// Writes the working-copy status as JSON lines.
+async fn write_status_json(
+    formatter: &mut dyn Formatter,
+    repo: &dyn Repo,
+    status: Option<&WorkingCopyStatus>,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    if let Some(status) = status {
+        write_json_line(
+            formatter,
+            &StatusItem::WorkingCopy {
+                commit: &status.commit,
+            },
+        )?;
+        for parent in &status.parents {
+            write_json_line(formatter, &StatusItem::Parent { commit: parent })?;
+        }
+
+        let mut copy_records = CopyRecords::default();
+        for parent in &status.parents {
+            let records =
+                get_copy_records(repo.store(), parent.id(), status.commit.id(), matcher).await?;
+            copy_records.add_records(records);
+        }
+        let mut tree_diff =
+            status
+                .parent_tree
+                .diff_stream_with_copies(&status.tree, matcher, &copy_records);
+        while let Some(CopiesTreeDiffEntry { path, values }) = tree_diff.next().await {
+            let values = values?;
+            let item = StatusItem::Change {
+                status: diff_status(&path, &values).label(),
+                path: path.target().as_internal_file_string(),
+                source: path
+                    .copy_operation()
+                    .map(|_| path.source().as_internal_file_string()),
+            };
+            write_json_line(formatter, &item)?;
+        }
+
+        visit_collapsed_untracked_files(
+            status.untracked_paths_matching(matcher),
+            status.tree.clone(),
+            |path, is_dir| {
+                let item = StatusItem::Untracked {
+                    path: path.as_internal_file_string(),
+                    is_dir,
+                };
+                write_json_line(formatter, &item)?;
+                Ok(())
+            },
+        )
+        .await?;
+
+        if status.commit.has_conflict() {
+            let conflicts = status.tree.conflicts_matching(matcher).collect_vec();
+            write_conflicted_paths_json(conflicts, formatter)?;
+        }
+    }
+
+    let view = repo.view();
+    for (name, _) in view
+        .local_bookmarks()
+        .filter(|(_, target)| target.has_conflict())
+    {
+        let item = StatusItem::ConflictedBookmark {
+            name: name.as_str(),
+            remote: None,
+        };
+        write_json_line(formatter, &item)?;
+    }
+    for (symbol, _) in view
+        .all_remote_bookmarks()
+        .filter(|(_, remote_ref)| remote_ref.target.has_conflict())
+    {
+        let item = StatusItem::ConflictedBookmark {
+            name: symbol.name.as_str(),
+            remote: Some(symbol.remote.as_str()),
+        };
+        write_json_line(formatter, &item)?;
+    }
+    Ok(())
+}
+
+/// Warns about submodules which aren't checked out at the pinned commits.
+fn print_outdated_submodules(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    let submodule_store = workspace_command.repo().submodule_store();
+    let mut outdated = vec![];
+    for (path, value) in tree.entries_matching(matcher) {
+        let Some(Some(TreeValue::GitSubmodule(pinned_id))) = value?.into_resolved().ok() else {
+            continue;
+        };
+        let dest = path
+            .to_fs_path(workspace_command.workspace_root())
+            .map_err(user_error)?;
+        let checked_out_id = submodule_store
+            .checked_out_commit(&dest)
+            .map_err(|err| user_error_with_message("Failed to read submodule", err))?;
+        if let Some(checked_out_id) = checked_out_id
+            && checked_out_id != pinned_id
+        {
+            outdated.push((path, pinned_id, checked_out_id));
+        }
+    }
+    if outdated.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        formatter.labeled("warning").with_heading("Warning: "),
+        "These submodules are not checked out at the pinned commits:"
+    )?;
+    for (path, pinned_id, checked_out_id) in outdated {
+        writeln!(
+            formatter,
+            "  {}: checked out at {}, pinned at {}",
+            workspace_command.format_file_path(&path),
+            short_commit_hash(&checked_out_id),
+            short_commit_hash(&pinned_id)
+        )?;
+    }
+    Ok(())
+}
+
 struct WorkingCopyStatus {
     commit: Commit,
     parents: Vec<Commit>,
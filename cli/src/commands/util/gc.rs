@@ -21,12 +21,16 @@ use jj_lib::repo::Repo as _;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
+use crate::commands::run::RunCache;
 use crate::ui::Ui;
 
 /// Run backend-dependent garbage collection.
 ///
 /// To garbage-collect old operations and the commits/objects referenced by
 /// them, run `jj op abandon ..<some old operation>` before `jj util gc`.
+///
+/// Cached `jj run` results which haven't been used within the same time
+/// threshold are removed as well.
 #[derive(clap::Args, Clone, Debug)]
 pub struct UtilGcArgs {
     /// Time threshold
@@ -63,5 +67,6 @@ pub async fn cmd_util_gc(
         .gc(slice::from_ref(repo.op_id()), keep_newer)
         .await?;
     repo.store().gc(repo.index(), keep_newer)?;
+    RunCache::new(workspace_command.repo_path()).gc(keep_newer)?;
     Ok(())
 }
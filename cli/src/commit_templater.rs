@@ -15,26 +15,31 @@
 //! Template environment for `jj log`, `jj evolog` and similar.
 
 use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::cmp::max;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
 use std::io;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use bstr::BString;
+use bstr::ByteSlice as _;
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
+use futures::stream;
 use futures::stream::BoxStream;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::Timestamp;
+use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::conflict_labels::ConflictLabels;
@@ -43,6 +48,11 @@ use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyRecords;
+use jj_lib::copies::get_copy_records_from_parents;
+use jj_lib::copies::get_copy_records_onto_tree;
+use jj_lib::diff_presentation::unified::DiffLineType;
+use jj_lib::diff_presentation::unified::UnifiedDiffHunk;
+use jj_lib::diff_presentation::unified::unified_diff_hunks;
 use jj_lib::evolution::CommitEvolutionEntry;
 use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::fileset;
@@ -54,6 +64,7 @@ use jj_lib::id_prefix::IdPrefixIndex;
 use jj_lib::index::IndexResult;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
+use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
@@ -95,6 +106,8 @@ use crate::diff_util;
 use crate::diff_util::DiffStatEntry;
 use crate::diff_util::DiffStats;
 use crate::formatter::Formatter;
+use crate::gerrit_util::GerritReview;
+use crate::gerrit_util::GerritReviews;
 use crate::git_util;
 use crate::operation_templater;
 use crate::operation_templater::OperationTemplateBuildFnTable;
@@ -126,6 +139,7 @@ use crate::templater::Literal;
 use crate::templater::SizeHint;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
+use crate::templater::TemplateMap;
 use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
 
@@ -383,6 +397,26 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::DiffHunk(property) => {
+                let table = &self.build_fn_table.diff_hunk_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
+            CommitTemplatePropertyKind::DiffHunkList(property) => {
+                let table = &self.build_fn_table.diff_hunk_list_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
+            CommitTemplatePropertyKind::DiffHunkLine(property) => {
+                let table = &self.build_fn_table.diff_hunk_line_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
+            CommitTemplatePropertyKind::DiffHunkLineList(property) => {
+                let table = &self.build_fn_table.diff_hunk_line_list_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(self, diagnostics, build_ctx, property, function)
+            }
             CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
                 let type_name = "CryptographicSignature";
                 let table = &self.build_fn_table.cryptographic_signature_methods;
@@ -390,6 +424,13 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let inner_property = property.try_unwrap(type_name).into_dyn();
                 build(self, diagnostics, build_ctx, inner_property, function)
             }
+            CommitTemplatePropertyKind::GerritReviewOpt(property) => {
+                let type_name = "GerritReview";
+                let table = &self.build_fn_table.gerrit_review_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name).into_dyn();
+                build(self, diagnostics, build_ctx, inner_property, function)
+            }
             CommitTemplatePropertyKind::AnnotationLine(property) => {
                 let type_name = "AnnotationLine";
                 let table = &self.build_fn_table.annotation_line_methods;
@@ -469,7 +510,12 @@ pub enum CommitTemplatePropertyKind<'repo> {
     DiffStats(BoxedTemplateProperty<'repo, DiffStatsFormatted<'repo>>),
     DiffStatEntry(BoxedTemplateProperty<'repo, DiffStatEntry>),
     DiffStatEntryList(BoxedTemplateProperty<'repo, Vec<DiffStatEntry>>),
+    DiffHunk(BoxedTemplateProperty<'repo, DiffHunk>),
+    DiffHunkList(BoxedTemplateProperty<'repo, Vec<DiffHunk>>),
+    DiffHunkLine(BoxedTemplateProperty<'repo, DiffHunkLine>),
+    DiffHunkLineList(BoxedTemplateProperty<'repo, Vec<DiffHunkLine>>),
     CryptographicSignatureOpt(BoxedTemplateProperty<'repo, Option<CryptographicSignature>>),
+    GerritReviewOpt(BoxedTemplateProperty<'repo, Option<GerritReview>>),
     AnnotationLine(BoxedTemplateProperty<'repo, AnnotationLine>),
     Trailer(BoxedTemplateProperty<'repo, Trailer>),
     TrailerList(BoxedTemplateProperty<'repo, Vec<Trailer>>),
@@ -503,7 +549,12 @@ template_builder::impl_property_wrappers!(<'repo> CommitTemplatePropertyKind<'re
     DiffStats(DiffStatsFormatted<'repo>),
     DiffStatEntry(DiffStatEntry),
     DiffStatEntryList(Vec<DiffStatEntry>),
+    DiffHunk(DiffHunk),
+    DiffHunkList(Vec<DiffHunk>),
+    DiffHunkLine(DiffHunkLine),
+    DiffHunkLineList(Vec<DiffHunkLine>),
     CryptographicSignatureOpt(Option<CryptographicSignature>),
+    GerritReviewOpt(Option<GerritReview>),
     AnnotationLine(AnnotationLine),
     Trailer(Trailer),
     TrailerList(Vec<Trailer>),
@@ -551,7 +602,12 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::DiffStats(_) => "DiffStats",
             Self::DiffStatEntry(_) => "DiffStatEntry",
             Self::DiffStatEntryList(_) => "List<DiffStatEntry>",
+            Self::DiffHunk(_) => "DiffHunk",
+            Self::DiffHunkList(_) => "List<DiffHunk>",
+            Self::DiffHunkLine(_) => "DiffHunkLine",
+            Self::DiffHunkLineList(_) => "List<DiffHunkLine>",
             Self::CryptographicSignatureOpt(_) => "Option<CryptographicSignature>",
+            Self::GerritReviewOpt(_) => "Option<GerritReview>",
             Self::AnnotationLine(_) => "AnnotationLine",
             Self::Trailer(_) => "Trailer",
             Self::TrailerList(_) => "List<Trailer>",
@@ -609,9 +665,14 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::DiffStats(_) => Err(self),
             Self::DiffStatEntry(_) => Err(self),
             Self::DiffStatEntryList(property) => Ok(property.map(|l| !l.is_empty()).into_dyn()),
+            Self::DiffHunk(_) => Err(self),
+            Self::DiffHunkList(property) => Ok(property.map(|l| !l.is_empty()).into_dyn()),
+            Self::DiffHunkLine(_) => Err(self),
+            Self::DiffHunkLineList(property) => Ok(property.map(|l| !l.is_empty()).into_dyn()),
             Self::CryptographicSignatureOpt(property) => {
                 Ok(property.map(|sig| sig.is_some()).into_dyn())
             }
+            Self::GerritReviewOpt(property) => Ok(property.map(|opt| opt.is_some()).into_dyn()),
             Self::AnnotationLine(_) => Err(self),
             Self::Trailer(_) => Err(self),
             Self::TrailerList(property) => Ok(property.map(|l| !l.is_empty()).into_dyn()),
@@ -663,7 +724,12 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::DiffStats(_) => None,
             Self::DiffStatEntry(_) => None,
             Self::DiffStatEntryList(_) => None,
+            Self::DiffHunk(_) => None,
+            Self::DiffHunkList(_) => None,
+            Self::DiffHunkLine(_) => None,
+            Self::DiffHunkLineList(_) => None,
             Self::CryptographicSignatureOpt(_) => None,
+            Self::GerritReviewOpt(property) => Some(property.into_serialize()),
             Self::AnnotationLine(_) => None,
             Self::Trailer(_) => None,
             Self::TrailerList(_) => None,
@@ -699,7 +765,12 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             Self::DiffStats(property) => Some(property.into_template()),
             Self::DiffStatEntry(_) => None,
             Self::DiffStatEntryList(_) => None,
+            Self::DiffHunk(_) => None,
+            Self::DiffHunkList(_) => None,
+            Self::DiffHunkLine(_) => None,
+            Self::DiffHunkLineList(_) => None,
             Self::CryptographicSignatureOpt(_) => None,
+            Self::GerritReviewOpt(_) => None,
             Self::AnnotationLine(_) => None,
             Self::Trailer(property) => Some(property.into_template()),
             Self::TrailerList(property) => Some(property.into_template()),
@@ -768,7 +839,12 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::DiffStats(_), _) => None,
             (Self::DiffStatEntry(_), _) => None,
             (Self::DiffStatEntryList(_), _) => None,
+            (Self::DiffHunk(_), _) => None,
+            (Self::DiffHunkList(_), _) => None,
+            (Self::DiffHunkLine(_), _) => None,
+            (Self::DiffHunkLineList(_), _) => None,
             (Self::CryptographicSignatureOpt(_), _) => None,
+            (Self::GerritReviewOpt(_), _) => None,
             (Self::AnnotationLine(_), _) => None,
             (Self::Trailer(_), _) => None,
             (Self::TrailerList(_), _) => None,
@@ -810,7 +886,12 @@ impl<'repo> CoreTemplatePropertyVar<'repo> for CommitTemplatePropertyKind<'repo>
             (Self::DiffStats(_), _) => None,
             (Self::DiffStatEntry(_), _) => None,
             (Self::DiffStatEntryList(_), _) => None,
+            (Self::DiffHunk(_), _) => None,
+            (Self::DiffHunkList(_), _) => None,
+            (Self::DiffHunkLine(_), _) => None,
+            (Self::DiffHunkLineList(_), _) => None,
             (Self::CryptographicSignatureOpt(_), _) => None,
+            (Self::GerritReviewOpt(_), _) => None,
             (Self::AnnotationLine(_), _) => None,
             (Self::Trailer(_), _) => None,
             (Self::TrailerList(_), _) => None,
@@ -847,8 +928,13 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub diff_stats_methods: CommitTemplateBuildMethodFnMap<'repo, DiffStats>,
     pub diff_stat_entry_methods: CommitTemplateBuildMethodFnMap<'repo, DiffStatEntry>,
     pub diff_stat_entry_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<DiffStatEntry>>,
+    pub diff_hunk_methods: CommitTemplateBuildMethodFnMap<'repo, DiffHunk>,
+    pub diff_hunk_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<DiffHunk>>,
+    pub diff_hunk_line_methods: CommitTemplateBuildMethodFnMap<'repo, DiffHunkLine>,
+    pub diff_hunk_line_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<DiffHunkLine>>,
     pub cryptographic_signature_methods:
         CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature>,
+    pub gerrit_review_methods: CommitTemplateBuildMethodFnMap<'repo, GerritReview>,
     pub annotation_line_methods: CommitTemplateBuildMethodFnMap<'repo, AnnotationLine>,
     pub trailer_methods: CommitTemplateBuildMethodFnMap<'repo, Trailer>,
     pub trailer_list_methods: CommitTemplateBuildMethodFnMap<'repo, Vec<Trailer>>,
@@ -878,7 +964,12 @@ impl CommitTemplateBuildFnTable<'_> {
             diff_stats_methods: HashMap::new(),
             diff_stat_entry_methods: HashMap::new(),
             diff_stat_entry_list_methods: HashMap::new(),
+            diff_hunk_methods: HashMap::new(),
+            diff_hunk_list_methods: HashMap::new(),
+            diff_hunk_line_methods: HashMap::new(),
+            diff_hunk_line_list_methods: HashMap::new(),
             cryptographic_signature_methods: HashMap::new(),
+            gerrit_review_methods: HashMap::new(),
             annotation_line_methods: HashMap::new(),
             trailer_methods: HashMap::new(),
             trailer_list_methods: HashMap::new(),
@@ -908,7 +999,12 @@ impl CommitTemplateBuildFnTable<'_> {
             diff_stats_methods,
             diff_stat_entry_methods,
             diff_stat_entry_list_methods,
+            diff_hunk_methods,
+            diff_hunk_list_methods,
+            diff_hunk_line_methods,
+            diff_hunk_line_list_methods,
             cryptographic_signature_methods,
+            gerrit_review_methods,
             annotation_line_methods,
             trailer_methods,
             trailer_list_methods,
@@ -950,10 +1046,18 @@ impl CommitTemplateBuildFnTable<'_> {
             &mut self.diff_stat_entry_list_methods,
             diff_stat_entry_list_methods,
         );
+        merge_fn_map(&mut self.diff_hunk_methods, diff_hunk_methods);
+        merge_fn_map(&mut self.diff_hunk_list_methods, diff_hunk_list_methods);
+        merge_fn_map(&mut self.diff_hunk_line_methods, diff_hunk_line_methods);
+        merge_fn_map(
+            &mut self.diff_hunk_line_list_methods,
+            diff_hunk_line_list_methods,
+        );
         merge_fn_map(
             &mut self.cryptographic_signature_methods,
             cryptographic_signature_methods,
         );
+        merge_fn_map(&mut self.gerrit_review_methods, gerrit_review_methods);
         merge_fn_map(&mut self.annotation_line_methods, annotation_line_methods);
         merge_fn_map(&mut self.trailer_methods, trailer_methods);
         merge_fn_map(&mut self.trailer_list_methods, trailer_list_methods);
@@ -985,7 +1089,12 @@ impl CommitTemplateBuildFnTable<'_> {
             diff_stats_methods: builtin_diff_stats_methods(),
             diff_stat_entry_methods: builtin_diff_stat_entry_methods(),
             diff_stat_entry_list_methods: template_builder::builtin_unformattable_list_methods(),
+            diff_hunk_methods: builtin_diff_hunk_methods(),
+            diff_hunk_list_methods: template_builder::builtin_unformattable_list_methods(),
+            diff_hunk_line_methods: builtin_diff_hunk_line_methods(),
+            diff_hunk_line_list_methods: template_builder::builtin_unformattable_list_methods(),
             cryptographic_signature_methods: builtin_cryptographic_signature_methods(),
+            gerrit_review_methods: builtin_gerrit_review_methods(),
             annotation_line_methods: builtin_annotation_line_methods(),
             trailer_methods: builtin_trailer_methods(),
             trailer_list_methods: builtin_trailer_list_methods(),
@@ -1000,6 +1109,8 @@ pub struct CommitKeywordCache<'repo> {
     tags_index: OnceCell<Rc<CommitRefsIndex>>,
     git_refs_index: OnceCell<Rc<CommitRefsIndex>>,
     is_immutable_fn: OnceCell<Rc<RevsetContainingFn<'repo>>>,
+    gerrit_reviews: OnceCell<Rc<GerritReviews>>,
+    tree_diffs: Rc<TreeDiffCache>,
 }
 
 impl<'repo> CommitKeywordCache<'repo> {
@@ -1018,6 +1129,10 @@ impl<'repo> CommitKeywordCache<'repo> {
             .get_or_init(|| Rc::new(build_commit_refs_index(repo.view().git_refs())))
     }
 
+    pub fn tree_diffs(&self) -> &Rc<TreeDiffCache> {
+        &self.tree_diffs
+    }
+
     pub fn is_immutable_fn(
         &self,
         language: &CommitTemplateLanguage<'repo>,
@@ -1032,6 +1147,21 @@ impl<'repo> CommitKeywordCache<'repo> {
             Ok(revset.containing_fn().into())
         })
     }
+
+    pub fn gerrit_reviews(
+        &self,
+        path_converter: &RepoPathUiConverter,
+        span: pest::Span<'_>,
+    ) -> TemplateParseResult<&Rc<GerritReviews>> {
+        self.gerrit_reviews.get_or_try_init(|| {
+            let RepoPathUiConverter::Fs { cwd: _, base } = path_converter;
+            let reviews = GerritReviews::load_for_workspace(base).map_err(|err| {
+                TemplateParseError::expression("Failed to load Gerrit review status", span)
+                    .with_source(err)
+            })?;
+            Ok(Rc::new(reviews))
+        })
+    }
 }
 
 /// Builtin functions for the commit template language.
@@ -1137,6 +1267,19 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "gerrit_review",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let reviews = language
+                .keyword_cache
+                .gerrit_reviews(language.path_converter, function.name_span)?
+                .clone();
+            let out_property =
+                self_property.map(move |commit| reviews.get_for_commit(&commit).cloned());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "working_copies",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1324,9 +1467,16 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
                 FilesetExpression::all()
             };
             let repo = language.repo;
+            let cache = language.keyword_cache.tree_diffs().clone();
             let matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let files = Rc::new(files);
             let out_property = self_property.and_then(move |commit| {
-                Ok(TreeDiff::from_commit(repo, &commit, matcher.clone()).block_on()?)
+                let diff = TreeDiff::from_commit(repo, &commit, matcher.clone()).block_on()?;
+                let copy_records_key = CopyRecordsKey {
+                    source_ids: commit.parent_ids().to_vec(),
+                    target_id: commit.id().clone(),
+                };
+                Ok(diff.memoized_in(&cache, &files, copy_records_key))
             });
             Ok(out_property.into_dyn_wrapped())
         },
@@ -1495,18 +1645,34 @@ fn builtin_commit_evolution_entry_methods<'repo>()
                 FilesetExpression::all()
             };
             let repo = language.repo;
+            let cache = language.keyword_cache.tree_diffs().clone();
             let matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let files = Rc::new(files);
             let out_property = self_property.and_then(move |entry| {
                 let predecessors = entry.predecessors().block_on()?;
                 let from_tree =
                     rebase_to_dest_parent(repo, &predecessors, &entry.commit).block_on()?;
                 let to_tree = entry.commit.tree();
-                Ok(TreeDiff {
+                let copy_records = get_copy_records_onto_tree(
+                    repo.store(),
+                    predecessors.iter().map(|commit| commit.id()),
+                    entry.commit.id(),
+                    &from_tree,
+                    &*matcher,
+                )
+                .block_on()?;
+                let diff = TreeDiff {
                     from_tree,
                     to_tree,
                     matcher: matcher.clone(),
-                    copy_records: CopyRecords::default(), // TODO: copy tracking
-                })
+                    copy_records,
+                    memo: Rc::default(),
+                };
+                let copy_records_key = CopyRecordsKey {
+                    source_ids: predecessors.iter().map(|c| c.id().clone()).collect(),
+                    target_id: entry.commit.id().clone(),
+                };
+                Ok(diff.memoized_in(&cache, &files, copy_records_key))
             });
             Ok(out_property.into_dyn_wrapped())
         },
@@ -2291,6 +2457,7 @@ pub struct TreeDiff {
     to_tree: MergedTree,
     matcher: Rc<dyn Matcher>,
     copy_records: CopyRecords,
+    memo: Rc<TreeDiffMemo>,
 }
 
 impl TreeDiff {
@@ -2299,30 +2466,73 @@ impl TreeDiff {
         commit: &Commit,
         matcher: Rc<dyn Matcher>,
     ) -> BackendResult<Self> {
-        let mut copy_records = CopyRecords::default();
-        for parent in commit.parent_ids() {
-            let records =
-                diff_util::get_copy_records(repo.store(), parent, commit.id(), &*matcher).await?;
-            copy_records.add_records(records);
-        }
+        let copy_records = get_copy_records_from_parents(
+            repo.store(),
+            commit.parent_ids(),
+            commit.id(),
+            &*matcher,
+        )
+        .await?;
         Ok(Self {
             from_tree: commit.parent_tree(repo).await?,
             to_tree: commit.tree(),
             matcher,
             copy_records,
+            memo: Rc::default(),
         })
     }
 
+    /// Shares the evaluated results with the other diffs of the same trees,
+    /// files, and copy records.
+    fn memoized_in(
+        self,
+        cache: &TreeDiffCache,
+        files: &Rc<FilesetExpression>,
+        copy_records_key: CopyRecordsKey,
+    ) -> Self {
+        let key = TreeDiffKey {
+            from_tree_ids: self.from_tree.tree_ids().clone(),
+            to_tree_ids: self.to_tree.tree_ids().clone(),
+            files: files.clone(),
+            copy_records: copy_records_key,
+        };
+        Self {
+            memo: cache.get(key),
+            ..self
+        }
+    }
+
     fn diff_stream(&self) -> BoxStream<'_, CopiesTreeDiffEntry> {
+        if let Some(entries) = self.memo.entries.get() {
+            let entries = entries.iter().map(|entry| CopiesTreeDiffEntry {
+                path: entry.path.clone(),
+                values: Ok(entry.values.clone()),
+            });
+            return stream::iter(entries).boxed();
+        }
         self.from_tree
             .diff_stream_with_copies(&self.to_tree, &*self.matcher, &self.copy_records)
     }
 
-    async fn collect_entries(&self) -> BackendResult<Vec<TreeDiffEntry>> {
-        self.diff_stream()
-            .map(TreeDiffEntry::from_backend_entry_with_copies)
-            .try_collect()
-            .await
+    /// Returns the changed entries, which are computed only once.
+    fn entries(&self) -> BackendResult<&[TreeDiffEntry]> {
+        let entries = self.memo.entries.get_or_try_init(|| {
+            self.diff_stream()
+                .map(TreeDiffEntry::from_backend_entry_with_copies)
+                .try_collect()
+                .block_on()
+        })?;
+        Ok(entries.as_slice())
+    }
+
+    /// Returns the stats of changed lines, which are computed only once.
+    fn stats(&self, marker_style: ConflictMarkerStyle) -> BackendResult<&DiffStats> {
+        self.memo.stats.get_or_try_init(|| {
+            let store = self.from_tree.store();
+            // No user configuration exists for diff stat.
+            let options = diff_util::DiffStatOptions::default();
+            DiffStats::calculate(store, self.diff_stream(), &options, marker_style).block_on()
+        })
     }
 
     fn into_formatted<F, E>(self, show: F) -> TreeDiffFormatted<F>
@@ -2339,6 +2549,50 @@ impl TreeDiff {
     }
 }
 
+/// Results of [`TreeDiff`] evaluated so far.
+#[derive(Debug, Default)]
+struct TreeDiffMemo {
+    entries: OnceCell<Vec<TreeDiffEntry>>,
+    stats: OnceCell<DiffStats>,
+}
+
+#[derive(Debug, Eq, Hash, PartialEq)]
+struct TreeDiffKey {
+    from_tree_ids: Merge<TreeId>,
+    to_tree_ids: Merge<TreeId>,
+    files: Rc<FilesetExpression>,
+    copy_records: CopyRecordsKey,
+}
+
+/// Identifies [`CopyRecords`] by the commits they were looked up between.
+#[derive(Debug, Eq, Hash, PartialEq)]
+struct CopyRecordsKey {
+    source_ids: Vec<CommitId>,
+    target_id: CommitId,
+}
+
+/// Memoized tree diffs, so the same diff isn't computed for each of
+/// `.files()`, `.stat()`, etc. while rendering a commit.
+#[derive(Debug, Default)]
+pub struct TreeDiffCache {
+    memos: RefCell<HashMap<TreeDiffKey, Rc<TreeDiffMemo>>>,
+}
+
+impl TreeDiffCache {
+    // Templates are usually evaluated for one commit after another, so old
+    // diffs are unlikely to be reused. The cache is cleared when it grows in
+    // order to not retain the diffs of all commits.
+    const MAX_ENTRIES: usize = 16;
+
+    fn get(&self, key: TreeDiffKey) -> Rc<TreeDiffMemo> {
+        let mut memos = self.memos.borrow_mut();
+        if memos.len() >= Self::MAX_ENTRIES && !memos.contains_key(&key) {
+            memos.clear();
+        }
+        memos.entry(key).or_default().clone()
+    }
+}
+
 /// Tree diff to be rendered by predefined function `F`.
 struct TreeDiffFormatted<F> {
     diff: TreeDiff,
@@ -2374,9 +2628,7 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
         "files",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            // TODO: cache and reuse diff entries within the current evaluation?
-            let out_property =
-                self_property.and_then(|diff| Ok(diff.collect_entries().block_on()?));
+            let out_property = self_property.and_then(|diff| Ok(diff.entries()?.to_vec()));
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -2481,15 +2733,9 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                 })
                 .transpose()?;
             let path_converter = language.path_converter;
-            // No user configuration exists for diff stat.
-            let options = diff_util::DiffStatOptions::default();
             let conflict_marker_style = language.conflict_marker_style;
-            // TODO: cache and reuse stats within the current evaluation?
             let out_property = (self_property, width_property).and_then(move |(diff, width)| {
-                let store = diff.from_tree.store();
-                let tree_diff = diff.diff_stream();
-                let stats = DiffStats::calculate(store, tree_diff, &options, conflict_marker_style)
-                    .block_on()?;
+                let stats = diff.stats(conflict_marker_style)?.clone();
                 Ok(DiffStatsFormatted {
                     stats,
                     path_converter,
@@ -2619,6 +2865,187 @@ fn builtin_tree_diff_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'r
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "hunks",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let ([], [context_node]) = function.expect_arguments()?;
+            let context_property = context_node
+                .map(|node| {
+                    template_builder::expect_usize_expression(
+                        language,
+                        diagnostics,
+                        build_ctx,
+                        node,
+                    )
+                })
+                .transpose()?;
+            let options = diff_util::UnifiedDiffOptions::from_settings(language.settings())
+                .map_err(|err| {
+                    let message = "Failed to load diff settings";
+                    TemplateParseError::expression(message, function.name_span).with_source(err)
+                })?;
+            let repo = language.repo;
+            let conflict_marker_style = language.conflict_marker_style;
+            let out_property =
+                (self_property, context_property).and_then(move |(entry, context)| {
+                    let contents = diff_util::materialize_diff_contents(
+                        repo.store(),
+                        &entry.path,
+                        entry.values,
+                        conflict_marker_style,
+                    )
+                    .block_on()?;
+                    if contents.before.is_binary || contents.after.is_binary {
+                        return Ok(vec![]);
+                    }
+                    let hunks = unified_diff_hunks(
+                        contents.as_ref().map(|content| content.contents.as_bstr()),
+                        context.unwrap_or(options.context),
+                        options.line_diff.compare_mode,
+                    );
+                    Ok(hunks.into_iter().map(DiffHunk::from_unified).collect_vec())
+                });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
+/// Hunk of changed lines in [`TreeDiffEntry`] with surrounding context lines.
+#[derive(Clone, Debug)]
+pub struct DiffHunk {
+    /// 0-based range of lines in the source (or left) file.
+    pub left_line_range: Range<usize>,
+    /// 0-based range of lines in the target (or right) file.
+    pub right_line_range: Range<usize>,
+    pub lines: Vec<DiffHunkLine>,
+}
+
+impl DiffHunk {
+    fn from_unified(hunk: UnifiedDiffHunk<'_>) -> Self {
+        let lines = hunk
+            .lines
+            .into_iter()
+            .map(|(line_type, tokens)| DiffHunkLine {
+                line_type,
+                content: tokens
+                    .iter()
+                    .map(|(_, content)| *content)
+                    .collect_vec()
+                    .concat()
+                    .into(),
+            })
+            .collect();
+        Self {
+            left_line_range: hunk.left_line_range,
+            right_line_range: hunk.right_line_range,
+            lines,
+        }
+    }
+}
+
+/// Line in [`DiffHunk`].
+#[derive(Clone, Debug)]
+pub struct DiffHunkLine {
+    pub line_type: DiffLineType,
+    pub content: BString,
+}
+
+/// Returns the 1-based line number of the `range` as in the unified diff hunk
+/// header.
+fn hunk_start_line_number(range: &Range<usize>) -> usize {
+    // If the range is empty, the line number preceding the range is used.
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
+    }
+}
+
+fn builtin_diff_hunk_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, DiffHunk> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<DiffHunk>::new();
+    map.insert(
+        "left_start",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|hunk| {
+                Ok(i64::try_from(hunk_start_line_number(
+                    &hunk.left_line_range,
+                ))?)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "left_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|hunk| Ok(i64::try_from(hunk.left_line_range.len())?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "right_start",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|hunk| {
+                Ok(i64::try_from(hunk_start_line_number(
+                    &hunk.right_line_range,
+                ))?)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "right_count",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|hunk| Ok(i64::try_from(hunk.right_line_range.len())?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "lines",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|hunk| hunk.lines);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
+fn builtin_diff_hunk_line_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, DiffHunkLine> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<DiffHunkLine>::new();
+    map.insert(
+        "kind",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|line| {
+                match line.line_type {
+                    DiffLineType::Context => "context",
+                    DiffLineType::Removed => "removed",
+                    DiffLineType::Added => "added",
+                }
+                .to_owned()
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "content",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|line| line.content);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -2892,6 +3319,69 @@ fn builtin_cryptographic_signature_methods<'repo>()
     map
 }
 
+fn builtin_gerrit_review_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, GerritReview> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<GerritReview>::new();
+    map.insert(
+        "number",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|review| Ok(i64::try_from(review.number)?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "patchset",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|review| Ok(i64::try_from(review.patchset)?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "status",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|review| review.status);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "url",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|review| review.url);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "votes",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|review| review.votes());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "approved",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|review| review.is_approved());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "submittable",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|review| review.submittable);
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
 #[derive(Debug, Clone)]
 pub struct AnnotationLine {
     pub commit: Commit,
@@ -2995,6 +3485,20 @@ fn builtin_trailer_list_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "to_map",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|trailers| {
+                let mut map = TemplateMap::<Vec<String>>::new();
+                for trailer in trailers {
+                    map.entry(trailer.key).or_default().push(trailer.value);
+                }
+                map
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -3311,4 +3815,38 @@ mod tests {
         insta::assert_snapshot!(
             env.render_ok("json(self)", &id), @r#"{"prefix":"012","rest":"3abcdef"}"#);
     }
+
+    #[test]
+    fn test_tree_diff_memo() {
+        let env = CommitTemplateTestEnv::init();
+        let repo = env.test_workspace.repo.as_ref();
+        let workspace_name = env.test_workspace.workspace.workspace_name();
+        let commit_id = repo.view().get_wc_commit_id(workspace_name).unwrap();
+        let commit = repo.store().get_commit(commit_id).unwrap();
+        let cache = TreeDiffCache::default();
+        let new_diff = |files: FilesetExpression| {
+            let matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let copy_records_key = CopyRecordsKey {
+                source_ids: commit.parent_ids().to_vec(),
+                target_id: commit.id().clone(),
+            };
+            TreeDiff::from_commit(repo, &commit, matcher)
+                .block_on()
+                .unwrap()
+                .memoized_in(&cache, &Rc::new(files), copy_records_key)
+        };
+
+        let diff1 = new_diff(FilesetExpression::all());
+        diff1.entries().unwrap();
+
+        // Repeated diff of the same files is served from the memo
+        let diff2 = new_diff(FilesetExpression::all());
+        assert!(Rc::ptr_eq(&diff1.memo, &diff2.memo));
+        assert!(diff2.memo.entries.get().is_some());
+
+        // Diff of different files isn't
+        let diff3 = new_diff(FilesetExpression::none());
+        assert!(!Rc::ptr_eq(&diff1.memo, &diff3.memo));
+        assert!(diff3.memo.entries.get().is_none());
+    }
 }
@@ -103,12 +103,31 @@ impl TextEditor {
         content: impl AsRef<[u8]>,
         suffix: Option<&str>,
     ) -> Result<String, TempTextEditError> {
+        self.edit_temp_file(content.as_ref(), suffix, |path| fs::read_to_string(path))
+    }
+
+    /// Writes the given `content` to temporary file and opens it in editor.
+    /// Unlike `edit_str()`, the edited content doesn't have to be valid UTF-8.
+    pub fn edit_bytes(
+        &self,
+        content: impl AsRef<[u8]>,
+        suffix: Option<&str>,
+    ) -> Result<Vec<u8>, TempTextEditError> {
+        self.edit_temp_file(content.as_ref(), suffix, |path| fs::read(path))
+    }
+
+    fn edit_temp_file<T>(
+        &self,
+        content: &[u8],
+        suffix: Option<&str>,
+        read: impl FnOnce(&Path) -> io::Result<T>,
+    ) -> Result<T, TempTextEditError> {
         let path = self
-            .write_temp_file(content.as_ref(), suffix)
+            .write_temp_file(content, suffix)
             .map_err(|err| TempTextEditError::new(err.into(), None))?;
         self.edit_file(&path)
             .map_err(|err| TempTextEditError::new(err.into(), Some(path.clone())))?;
-        let edited = fs::read_to_string(&path)
+        let edited = read(&path)
             .context(&path)
             .map_err(|err| TempTextEditError::new(err.into(), Some(path.clone())))?;
         // Delete the file only if everything went well.
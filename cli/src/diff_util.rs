@@ -14,7 +14,6 @@
 
 use std::borrow::Cow;
 use std::cmp::max;
-use std::future;
 use std::io;
 use std::iter;
 use std::ops::Range;
@@ -30,8 +29,6 @@ use futures::stream::BoxStream;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendError;
 use jj_lib::backend::BackendResult;
-use jj_lib::backend::CommitId;
-use jj_lib::backend::CopyRecord;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
@@ -41,11 +38,14 @@ use jj_lib::conflicts::ConflictMaterializeOptions;
 use jj_lib::conflicts::MaterializedTreeDiffEntry;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::conflicts::materialized_diff_stream;
 use jj_lib::copies::CopiesTreeDiffEntry;
 use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
+use jj_lib::copies::get_copy_records_from_parents;
+use jj_lib::copies::get_copy_records_onto_tree;
 use jj_lib::diff::ContentDiff;
 use jj_lib::diff::DiffHunk;
 use jj_lib::diff::DiffHunkKind;
@@ -639,7 +639,14 @@ impl<'a> DiffRenderer<'a> {
         let to_description = Merge::resolved(to_commit.description());
         let from_tree = rebase_to_dest_parent(self.repo, from_commits, to_commit).await?;
         let to_tree = to_commit.tree();
-        let copy_records = CopyRecords::default(); // TODO
+        let copy_records = get_copy_records_onto_tree(
+            self.repo.store(),
+            from_commits.iter().map(|commit| commit.id()),
+            to_commit.id(),
+            &from_tree,
+            matcher,
+        )
+        .await?;
         self.show_diff_commit_descriptions(
             *formatter,
             Diff::new(&from_description, &to_description),
@@ -666,12 +673,13 @@ impl<'a> DiffRenderer<'a> {
     ) -> Result<(), DiffRenderError> {
         let from_tree = commit.parent_tree(self.repo).await?;
         let to_tree = commit.tree();
-        let mut copy_records = CopyRecords::default();
-        for parent_id in commit.parent_ids() {
-            let records =
-                get_copy_records(self.repo.store(), parent_id, commit.id(), matcher).await?;
-            copy_records.add_records(records);
-        }
+        let copy_records = get_copy_records_from_parents(
+            self.repo.store(),
+            commit.parent_ids(),
+            commit.id(),
+            matcher,
+        )
+        .await?;
         self.show_diff(
             ui,
             formatter,
@@ -684,21 +692,6 @@ impl<'a> DiffRenderer<'a> {
     }
 }
 
-pub async fn get_copy_records(
-    store: &Store,
-    root: &CommitId,
-    head: &CommitId,
-    matcher: &dyn Matcher,
-) -> BackendResult<Vec<CopyRecord>> {
-    // TODO: teach backend about matching path prefixes?
-    let stream = store.get_copy_records(None, root, head)?;
-    // TODO: test record.source as well? should be AND-ed or OR-ed?
-    stream
-        .try_filter(|record| future::ready(matcher.matches(&record.target)))
-        .try_collect()
-        .await
-}
-
 /// How conflicts are processed and rendered in diffs.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -1966,6 +1959,31 @@ impl DiffStats {
     }
 }
 
+/// Materializes the file contents of the diff entry to be compared.
+///
+/// Conflicts are materialized with the given marker style and no labels, which
+/// is consistent with [`DiffStats::calculate()`].
+pub async fn materialize_diff_contents(
+    store: &Store,
+    path: &CopiesTreeDiffEntryPath,
+    values: Diff<MergedTreeValue>,
+    marker_style: ConflictMarkerStyle,
+) -> BackendResult<Diff<FileContent<BString>>> {
+    let materialize_options = ConflictMaterializeOptions {
+        marker_style,
+        marker_len: None,
+        merge: store.merge_options().clone(),
+    };
+    let conflict_labels = ConflictLabels::unlabeled();
+    let before =
+        materialize_tree_value(store, path.source(), values.before, &conflict_labels).await?;
+    let after =
+        materialize_tree_value(store, path.target(), values.after, &conflict_labels).await?;
+    let left_content = diff_content(path.source(), before, &materialize_options).await?;
+    let right_content = diff_content(path.target(), after, &materialize_options).await?;
+    Ok(Diff::new(left_content, right_content))
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DiffEntryStatus {
     Added,
@@ -42,6 +42,13 @@ pub trait Formatter: Write {
     /// already formatted, such as in the graphical log.
     fn raw(&mut self) -> io::Result<Box<dyn Write + '_>>;
 
+    /// Returns the backing `Write` for content which was already rendered by
+    /// a formatter of the same kind, such as the nodes in the graphical log.
+    /// Unlike [`Self::raw()`], the content is never escaped.
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        self.raw()
+    }
+
     fn push_label(&mut self, label: &str);
 
     fn pop_label(&mut self);
@@ -54,6 +61,10 @@ impl<T: Formatter + ?Sized> Formatter for &mut T {
         <T as Formatter>::raw(self)
     }
 
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        <T as Formatter>::raw_formatted(self)
+    }
+
     fn push_label(&mut self, label: &str) {
         <T as Formatter>::push_label(self, label);
     }
@@ -72,6 +83,10 @@ impl<T: Formatter + ?Sized> Formatter for Box<T> {
         <T as Formatter>::raw(self)
     }
 
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        <T as Formatter>::raw_formatted(self)
+    }
+
     fn push_label(&mut self, label: &str) {
         <T as Formatter>::push_label(self, label);
     }
@@ -183,6 +198,7 @@ enum FormatterFactoryKind {
     PlainText,
     Sanitized,
     Color { rules: Arc<Rules>, debug: bool },
+    Html { rules: Arc<Rules> },
 }
 
 impl FormatterFactory {
@@ -202,6 +218,12 @@ impl FormatterFactory {
         Ok(Self { kind })
     }
 
+    pub fn html(config: &StackedConfig) -> Result<Self, ConfigGetError> {
+        let rules = Arc::new(rules_from_config(config)?);
+        let kind = FormatterFactoryKind::Html { rules };
+        Ok(Self { kind })
+    }
+
     pub fn new_formatter<'output, W: Write + 'output>(
         &self,
         output: W,
@@ -212,10 +234,21 @@ impl FormatterFactory {
             FormatterFactoryKind::Color { rules, debug } => {
                 Box::new(ColorFormatter::new(output, rules.clone(), *debug))
             }
+            FormatterFactoryKind::Html { rules } => {
+                Box::new(HtmlFormatter::new(output, rules.clone()))
+            }
         }
     }
 
     pub fn maybe_color(&self) -> bool {
+        matches!(
+            self.kind,
+            FormatterFactoryKind::Color { .. } | FormatterFactoryKind::Html { .. }
+        )
+    }
+
+    /// Whether the output is colorized by ANSI escape sequences.
+    pub fn is_ansi_color(&self) -> bool {
         matches!(self.kind, FormatterFactoryKind::Color { .. })
     }
 }
@@ -357,35 +390,7 @@ impl<W: Write> ColorFormatter<W> {
         if let Some(cached) = self.cached_styles.get(&self.labels) {
             cached.clone()
         } else {
-            // We use the reverse list of matched indices as a measure of how well the rule
-            // matches the actual labels. For example, for rule "a d" and the actual labels
-            // "a b c d", we'll get [3,0]. We compare them by Rust's default Vec comparison.
-            // That means "a d" will trump both rule "d" (priority [3]) and rule
-            // "a b c" (priority [2,1,0]).
-            let mut matched_styles = vec![];
-            for (labels, style) in self.rules.as_ref() {
-                let mut labels_iter = self.labels.iter().enumerate();
-                // The indexes in the current label stack that match the required label.
-                let mut matched_indices = vec![];
-                for required_label in labels {
-                    for (label_index, label) in &mut labels_iter {
-                        if label == required_label {
-                            matched_indices.push(label_index);
-                            break;
-                        }
-                    }
-                }
-                if matched_indices.len() == labels.len() {
-                    matched_indices.reverse();
-                    matched_styles.push((style, matched_indices));
-                }
-            }
-            matched_styles.sort_by_key(|(_, indices)| indices.clone());
-
-            let mut style = Style::default();
-            for (matched_style, _) in matched_styles {
-                style.merge(matched_style);
-            }
+            let style = resolve_style(&self.rules, &self.labels);
             self.cached_styles
                 .insert(self.labels.clone(), style.clone());
             style
@@ -483,6 +488,39 @@ impl<W: Write> ColorFormatter<W> {
     }
 }
 
+fn resolve_style(rules: &Rules, labels: &[String]) -> Style {
+    // We use the reverse list of matched indices as a measure of how well the rule
+    // matches the actual labels. For example, for rule "a d" and the actual labels
+    // "a b c d", we'll get [3,0]. We compare them by Rust's default Vec comparison.
+    // That means "a d" will trump both rule "d" (priority [3]) and rule
+    // "a b c" (priority [2,1,0]).
+    let mut matched_styles = vec![];
+    for (rule_labels, style) in rules {
+        let mut labels_iter = labels.iter().enumerate();
+        // The indexes in the current label stack that match the required label.
+        let mut matched_indices = vec![];
+        for required_label in rule_labels {
+            for (label_index, label) in &mut labels_iter {
+                if label == required_label {
+                    matched_indices.push(label_index);
+                    break;
+                }
+            }
+        }
+        if matched_indices.len() == rule_labels.len() {
+            matched_indices.reverse();
+            matched_styles.push((style, matched_indices));
+        }
+    }
+    matched_styles.sort_by_key(|(_, indices)| indices.clone());
+
+    let mut style = Style::default();
+    for (matched_style, _) in matched_styles {
+        style.merge(matched_style);
+    }
+    style
+}
+
 fn rules_from_config(config: &StackedConfig) -> Result<Rules, ConfigGetError> {
     config
         .table_keys("colors")
@@ -659,6 +697,290 @@ impl<W: Write> Drop for ColorFormatter<W> {
     }
 }
 
+/// Formatter that emits HTML fragment.
+///
+/// Labeled text is wrapped in `<span>` elements with the labels as CSS classes
+/// and the style resolved from the `colors` config inlined. The output is
+/// supposed to be embedded in `<pre>` element.
+#[derive(Clone, Debug)]
+pub struct HtmlFormatter<W: Write> {
+    output: W,
+    rules: Arc<Rules>,
+    /// The stack of currently applied labels.
+    labels: Vec<String>,
+    cached_styles: HashMap<Vec<String>, String>,
+    /// The labels of the currently open `<span>`. Empty if no `<span>` is
+    /// open.
+    current_labels: Vec<String>,
+}
+
+impl<W: Write> HtmlFormatter<W> {
+    pub fn new(output: W, rules: Arc<Rules>) -> Self {
+        Self {
+            output,
+            rules,
+            labels: vec![],
+            cached_styles: HashMap::new(),
+            current_labels: vec![],
+        }
+    }
+
+    pub fn for_config(output: W, config: &StackedConfig) -> Result<Self, ConfigGetError> {
+        let rules = rules_from_config(config)?;
+        Ok(Self::new(output, Arc::new(rules)))
+    }
+
+    fn requested_css(&mut self) -> &str {
+        self.cached_styles
+            .entry(self.labels.clone())
+            .or_insert_with(|| style_to_css(&resolve_style(&self.rules, &self.labels)))
+    }
+
+    fn write_new_span(&mut self) -> io::Result<()> {
+        if self.labels == self.current_labels {
+            return Ok(());
+        }
+        if !self.current_labels.is_empty() {
+            self.output.write_all(b"</span>")?;
+        }
+        if !self.labels.is_empty() {
+            let classes = self.labels.join(" ");
+            let css = self.requested_css().to_owned();
+            write!(self.output, "<span class=\"")?;
+            write_html_escaped(&mut self.output, classes.as_bytes())?;
+            if css.is_empty() {
+                write!(self.output, "\">")?;
+            } else {
+                write!(self.output, "\" style=\"{css}\">")?;
+            }
+        }
+        self.current_labels.clone_from(&self.labels);
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for HtmlFormatter<W> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        // Like ColorFormatter, each line is styled independently. Empty
+        // content isn't wrapped in <span>.
+        for line in data.split_inclusive(|b| *b == b'\n') {
+            let (content, newline) = match line.strip_suffix(b"\n") {
+                Some(content) => (content, true),
+                None => (line, false),
+            };
+            if !content.is_empty() {
+                self.write_new_span()?;
+                write_html_escaped(&mut self.output, content)?;
+            }
+            if newline {
+                let labels = mem::take(&mut self.labels);
+                self.write_new_span()?;
+                self.output.write_all(b"\n")?;
+                self.labels = labels;
+            }
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.output.flush()
+    }
+}
+
+impl<W: Write> Formatter for HtmlFormatter<W> {
+    fn raw(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        self.write_new_span()?;
+        Ok(Box::new(HtmlRawWriter::new(self.output.by_ref())))
+    }
+
+    fn raw_formatted(&mut self) -> io::Result<Box<dyn Write + '_>> {
+        self.write_new_span()?;
+        Ok(Box::new(self.output.by_ref()))
+    }
+
+    fn push_label(&mut self, label: &str) {
+        self.labels.push(label.to_owned());
+    }
+
+    fn pop_label(&mut self) {
+        self.labels.pop();
+    }
+
+    fn maybe_color(&self) -> bool {
+        true
+    }
+}
+
+impl<W: Write> Drop for HtmlFormatter<W> {
+    fn drop(&mut self) {
+        // Close the currently open <span> if any.
+        self.labels.clear();
+        self.write_new_span().ok();
+    }
+}
+
+/// Writer that escapes raw output as HTML text, dropping ANSI escape sequences
+/// which can't be rendered.
+struct HtmlRawWriter<W> {
+    output: W,
+    state: AnsiEscapeState,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum AnsiEscapeState {
+    Text,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+impl<W: Write> HtmlRawWriter<W> {
+    fn new(output: W) -> Self {
+        Self {
+            output,
+            state: AnsiEscapeState::Text,
+        }
+    }
+}
+
+impl<W: Write> Write for HtmlRawWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let mut text = Vec::with_capacity(data.len());
+        for &b in data {
+            self.state = match (self.state, b) {
+                (AnsiEscapeState::Text, b'\x1b') => AnsiEscapeState::Escape,
+                (AnsiEscapeState::Text, _) => {
+                    text.push(b);
+                    AnsiEscapeState::Text
+                }
+                (AnsiEscapeState::Escape, b'[') => AnsiEscapeState::Csi,
+                (AnsiEscapeState::Escape, b']') => AnsiEscapeState::Osc,
+                // Two-byte sequence
+                (AnsiEscapeState::Escape, _) => AnsiEscapeState::Text,
+                (AnsiEscapeState::Csi, 0x40..=0x7e) => AnsiEscapeState::Text,
+                (AnsiEscapeState::Csi, _) => AnsiEscapeState::Csi,
+                (AnsiEscapeState::Osc | AnsiEscapeState::OscEscape, b'\x07') => {
+                    AnsiEscapeState::Text
+                }
+                (AnsiEscapeState::Osc | AnsiEscapeState::OscEscape, b'\x1b') => {
+                    AnsiEscapeState::OscEscape
+                }
+                (AnsiEscapeState::OscEscape, b'\\') => AnsiEscapeState::Text,
+                (AnsiEscapeState::Osc | AnsiEscapeState::OscEscape, _) => AnsiEscapeState::Osc,
+            };
+        }
+        write_html_escaped(&mut self.output, &text)?;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+fn style_to_css(style: &Style) -> String {
+    let (fg, bg) = if style.reverse.unwrap_or_default() {
+        let fg = style.bg.map_or(Some("Canvas".to_owned()), color_to_css);
+        let bg = style.fg.map_or(Some("CanvasText".to_owned()), color_to_css);
+        (fg, bg)
+    } else {
+        (
+            style.fg.and_then(color_to_css),
+            style.bg.and_then(color_to_css),
+        )
+    };
+    let mut declarations = vec![];
+    if let Some(color) = fg {
+        declarations.push(format!("color: {color}"));
+    }
+    if let Some(color) = bg {
+        declarations.push(format!("background-color: {color}"));
+    }
+    if style.bold.unwrap_or_default() {
+        declarations.push("font-weight: bold".to_owned());
+    }
+    if style.dim.unwrap_or_default() {
+        declarations.push("opacity: 0.6".to_owned());
+    }
+    if style.italic.unwrap_or_default() {
+        declarations.push("font-style: italic".to_owned());
+    }
+    let decorations = [
+        (style.underline, "underline"),
+        (style.crossed_out, "line-through"),
+    ]
+    .iter()
+    .filter(|(enabled, _)| enabled.unwrap_or_default())
+    .map(|(_, decoration)| *decoration)
+    .join(" ");
+    if !decorations.is_empty() {
+        declarations.push(format!("text-decoration: {decorations}"));
+    }
+    declarations.join("; ")
+}
+
+/// Translates the color to CSS color. Returns `None` for the default color.
+fn color_to_css(color: Color) -> Option<String> {
+    // Colors of the standard xterm palette
+    const BASIC_COLORS: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0x80, 0x00, 0x00),
+        (0x00, 0x80, 0x00),
+        (0x80, 0x80, 0x00),
+        (0x00, 0x00, 0x80),
+        (0x80, 0x00, 0x80),
+        (0x00, 0x80, 0x80),
+        (0xc0, 0xc0, 0xc0),
+        (0x80, 0x80, 0x80),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x00, 0x00, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+    let index = match color {
+        Color::Reset => return None,
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+        Color::AnsiValue(index) => index,
+        Color::Rgb { r, g, b } => return Some(format!("#{r:02x}{g:02x}{b:02x}")),
+    };
+    let (r, g, b) = match index {
+        0..=15 => BASIC_COLORS[usize::from(index)],
+        16..=231 => {
+            let i = usize::from(index - 16);
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[i / 6 % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    };
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
 /// Like buffered formatter, but records `push`/`pop_label()` calls.
 ///
 /// This allows you to manipulate the recorded data without losing labels.
@@ -797,6 +1119,24 @@ fn write_sanitized(output: &mut impl Write, buf: &[u8]) -> Result<(), Error> {
     }
 }
 
+fn write_html_escaped(output: &mut impl Write, buf: &[u8]) -> Result<(), Error> {
+    let mut start = 0;
+    for (i, b) in buf.iter().enumerate() {
+        let escaped: &[u8] = match b {
+            b'&' => b"&amp;",
+            b'<' => b"&lt;",
+            b'>' => b"&gt;",
+            b'"' => b"&quot;",
+            b'\x1b' => "␛".as_bytes(),
+            _ => continue,
+        };
+        output.write_all(&buf[start..i])?;
+        output.write_all(escaped)?;
+        start = i + 1;
+    }
+    output.write_all(&buf[start..])
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error as _;
@@ -1571,6 +1911,90 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_html_formatter_nested() -> TestResult {
+        let config = config_from_string(
+            r#"
+        colors.outer = "blue"
+        colors.inner = "red"
+        colors."outer inner" = "green"
+        "#,
+        );
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        write!(formatter, " before outer ")?;
+        formatter.push_label("outer");
+        write!(formatter, " before inner ")?;
+        formatter.push_label("inner");
+        write!(formatter, " inside inner ")?;
+        formatter.pop_label();
+        write!(formatter, " after inner ")?;
+        formatter.pop_label();
+        write!(formatter, " after outer ")?;
+        formatter.push_label("unstyled");
+        write!(formatter, " unstyled ")?;
+        formatter.pop_label();
+        drop(formatter);
+        insta::assert_snapshot!(
+            to_snapshot_string(output),
+            @r#" before outer <span class="outer" style="color: #000080"> before inner </span><span class="outer inner" style="color: #008000"> inside inner </span><span class="outer" style="color: #000080"> after inner </span> after outer <span class="unstyled"> unstyled </span>[EOF]"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_formatter_attributes() -> TestResult {
+        let config = config_from_string(indoc! {r##"
+            colors.styled = { fg = "#ff8000", bg = "ansi-color-21", bold = true, underline = true, crossed-out = true }
+            colors.reversed = { fg = "bright red", reverse = true }
+            colors.dimmed = { dim = true, italic = true, fg = "ansi-color-244" }
+        "##});
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        for label in ["styled", "reversed", "dimmed"] {
+            formatter.push_label(label);
+            write!(formatter, " {label} ")?;
+            formatter.pop_label();
+            writeln!(formatter)?;
+        }
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r#"
+        <span class="styled" style="color: #ff8000; background-color: #0000ff; font-weight: bold; text-decoration: underline line-through"> styled </span>
+        <span class="reversed" style="color: Canvas; background-color: #ff0000"> reversed </span>
+        <span class="dimmed" style="color: #808080; opacity: 0.6; font-style: italic"> dimmed </span>
+        [EOF]
+        "#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_formatter_escape() -> TestResult {
+        // Each line is wrapped independently, and special characters are
+        // escaped.
+        let config = config_from_string(
+            r#"
+        colors.outer = "green"
+        "#,
+        );
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = HtmlFormatter::for_config(&mut output, &config)?;
+        formatter.push_label("outer");
+        write!(formatter, "<a & \"b\">\n\n\x1b[1mline\x1b[0m")?;
+        // Escape sequences in raw output are dropped, and the text is escaped.
+        write!(
+            formatter.raw()?,
+            " \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\ \x1b[1m<b>bold</b>\x1b[0m"
+        )?;
+        // Formatted output is passed through.
+        write!(formatter.raw_formatted()?, " <b>formatted</b>")?;
+        drop(formatter);
+        insta::assert_snapshot!(to_snapshot_string(output), @r#"
+        <span class="outer" style="color: #008000">&lt;a &amp; &quot;b&quot;&gt;</span>
+
+        <span class="outer" style="color: #008000">␛[1mline␛[0m link &lt;b&gt;bold&lt;/b&gt; <b>formatted</b></span>[EOF]
+        "#);
+        Ok(())
+    }
+
     #[test]
     fn test_labeled_scope() -> TestResult {
         let config = config_from_string(indoc! {"
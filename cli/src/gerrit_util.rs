@@ -0,0 +1,574 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gerrit utilities shared by commands, templates, and revsets.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::collections::btree_map;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::revset::FunctionCallNode;
+use jj_lib::revset::LoweringContext;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetExtensions;
+use jj_lib::revset::RevsetFilterExtension;
+use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::revset::RevsetParseError;
+use jj_lib::revset::UserRevsetExpression;
+use jj_lib::trailer::parse_description_trailers;
+use jj_lib::workspace::DefaultWorkspaceLoaderFactory;
+use jj_lib::workspace::WorkspaceLoadError;
+use jj_lib::workspace::WorkspaceLoaderFactory as _;
+use percent_encoding::NON_ALPHANUMERIC;
+use percent_encoding::utf8_percent_encode;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::IgnoredAny;
+use thiserror::Error;
+
+/// Maximum number of changes to look up in a single Gerrit query, which keeps
+/// the request URL reasonably short.
+const QUERY_CHUNK_SIZE: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum GerritError {
+    #[error("Failed to run curl")]
+    Spawn(#[source] io::Error),
+    #[error("Gerrit request to {url} failed: {message}")]
+    Request { url: String, message: String },
+    #[error("Failed to parse Gerrit response from {url}")]
+    Response {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Failed to read Gerrit review status from {}", path.display())]
+    ReadState {
+        path: PathBuf,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Failed to write Gerrit review status to {}", path.display())]
+    WriteState {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error(transparent)]
+    WorkspaceLoad(#[from] WorkspaceLoadError),
+}
+
+/// Returns the Gerrit `Change-Id` of the commit.
+///
+/// This is the value of the `Change-Id` trailer, or the ID at the end of the
+/// `Link` trailer. Commits without either trailer use the ID that `jj gerrit
+/// upload` derives from the jj change ID.
+pub fn gerrit_change_id(commit: &Commit) -> String {
+    gerrit_change_id_from_trailers(commit.description())
+        .unwrap_or_else(|| derived_gerrit_change_id(commit.change_id()))
+}
+
+/// Returns the Gerrit `Change-Id` from the `Change-Id` or `Link` trailer of the
+/// description.
+pub fn gerrit_change_id_from_trailers(description: &str) -> Option<String> {
+    parse_description_trailers(description)
+        .into_iter()
+        .find_map(|trailer| match trailer.key.as_str() {
+            "Change-Id" => Some(trailer.value),
+            "Link" => trailer
+                .value
+                .rsplit_once("/id/")
+                .map(|(_url, id)| id.to_owned()),
+            _ => None,
+        })
+}
+
+/// Returns the Gerrit `Change-Id` derived from the jj change ID.
+pub fn derived_gerrit_change_id(change_id: &ChangeId) -> String {
+    // Gerrit change id is 40 chars, jj change id is 32, so we need padding.
+    // To be consistent with `format_gerrit_change_id_trailer`, we pad with
+    // 6a6a6964 (hex of "jjid").
+    format!("I{}6a6a6964", change_id.hex())
+}
+
+/// Returns the jj change ID if the Gerrit `Change-Id` was derived from it.
+pub fn change_id_from_gerrit_change_id(gerrit_change_id: &str) -> Option<ChangeId> {
+    let hex = gerrit_change_id
+        .strip_prefix('I')?
+        .strip_suffix("6a6a6964")?;
+    if hex.len() == 32 {
+        ChangeId::try_from_hex(hex)
+    } else {
+        None
+    }
+}
+
+/// Review status of a Gerrit change as of the last `jj gerrit fetch`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct GerritReview {
+    /// Change number.
+    pub number: u64,
+    /// Number of the current patchset.
+    pub patchset: u64,
+    /// One of `NEW`, `MERGED`, or `ABANDONED`.
+    pub status: String,
+    /// Whether the change can be submitted.
+    pub submittable: bool,
+    /// URL of the change in the Gerrit web UI.
+    pub url: String,
+    /// Labels of the change, sorted by name.
+    pub labels: Vec<GerritLabel>,
+}
+
+impl GerritReview {
+    /// Returns true if `Code-Review` is approved and no label is rejected.
+    pub fn is_approved(&self) -> bool {
+        self.labels
+            .iter()
+            .any(|label| label.name == "Code-Review" && label.approved)
+            && !self.labels.iter().any(|label| label.rejected)
+    }
+
+    /// Returns the non-zero votes formatted as `<label><value>`, e.g.
+    /// `Code-Review+2`.
+    pub fn votes(&self) -> Vec<String> {
+        self.labels
+            .iter()
+            .filter(|label| label.value != 0)
+            .map(|label| label.to_string())
+            .collect()
+    }
+}
+
+/// Votes on a Gerrit label.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct GerritLabel {
+    pub name: String,
+    /// The lowest vote if any vote is negative, otherwise the highest vote.
+    pub value: i64,
+    /// Whether the label has the maximum positive vote.
+    pub approved: bool,
+    /// Whether the label has the maximum negative vote.
+    pub rejected: bool,
+}
+
+impl fmt::Display for GerritLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:+}", self.name, self.value)
+    }
+}
+
+/// Review status of Gerrit changes, keyed by `Change-Id`.
+///
+/// This is stored in the repo directory by `jj gerrit fetch`. It isn't
+/// recorded in the operation log.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct GerritReviews {
+    changes: BTreeMap<String, GerritReview>,
+}
+
+impl GerritReviews {
+    /// Loads the stored review status. Returns an empty set if nothing has
+    /// been fetched yet.
+    pub fn load(repo_path: &Path) -> Result<Self, GerritError> {
+        let path = reviews_file_path(repo_path);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(GerritError::ReadState {
+                    path,
+                    source: err.into(),
+                });
+            }
+        };
+        serde_json::from_slice(&data).map_err(|err| GerritError::ReadState {
+            path,
+            source: err.into(),
+        })
+    }
+
+    /// Loads the review status stored in the repo of the workspace.
+    pub fn load_for_workspace(workspace_root: &Path) -> Result<Self, GerritError> {
+        let workspace_loader = DefaultWorkspaceLoaderFactory.create(workspace_root)?;
+        Self::load(workspace_loader.repo_path())
+    }
+
+    pub fn save(&self, repo_path: &Path) -> Result<(), GerritError> {
+        let path = reviews_file_path(repo_path);
+        let write = || {
+            fs::create_dir_all(path.parent().unwrap())?;
+            let data = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+            fs::write(&path, data)
+        };
+        write().map_err(|source| GerritError::WriteState {
+            path: path.clone(),
+            source,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Looks up the review status by Gerrit `Change-Id`.
+    pub fn get(&self, change_id: &str) -> Option<&GerritReview> {
+        self.changes.get(change_id)
+    }
+
+    /// Looks up the review status of the commit.
+    pub fn get_for_commit(&self, commit: &Commit) -> Option<&GerritReview> {
+        if self.changes.is_empty() {
+            return None;
+        }
+        self.get(&gerrit_change_id(commit))
+    }
+
+    pub fn insert(&mut self, change_id: String, review: GerritReview) {
+        self.changes.insert(change_id, review);
+    }
+
+    pub fn remove(&mut self, change_id: &str) -> Option<GerritReview> {
+        self.changes.remove(change_id)
+    }
+
+    /// Iterates over `Change-Id`s and review status.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &GerritReview)> {
+        self.changes
+            .iter()
+            .map(|(change_id, review)| (change_id.as_str(), review))
+    }
+}
+
+fn reviews_file_path(repo_path: &Path) -> PathBuf {
+    repo_path.join("gerrit").join("reviews.json")
+}
+
+/// Client for the Gerrit REST API.
+///
+/// Requests are sent by `curl`, which takes care of TLS, proxies, and
+/// credentials stored in `~/.netrc`.
+#[derive(Clone, Debug)]
+pub struct GerritClient {
+    base_url: String,
+}
+
+impl GerritClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    /// Queries the review status of the changes with the given `Change-Id`s.
+    ///
+    /// Changes unknown to Gerrit are omitted from the result. If a `Change-Id`
+    /// is used by changes on multiple branches, an open change is preferred.
+    pub fn query_changes(
+        &self,
+        change_ids: &[String],
+    ) -> Result<BTreeMap<String, GerritReview>, GerritError> {
+        let mut reviews = BTreeMap::new();
+        for chunk in change_ids.chunks(QUERY_CHUNK_SIZE) {
+            let query = chunk.iter().map(|id| format!("change:{id}")).join(" OR ");
+            for change in self.query(&query)? {
+                let review = change.to_review(&self.base_url);
+                match reviews.entry(change.change_id) {
+                    btree_map::Entry::Vacant(entry) => {
+                        entry.insert(review);
+                    }
+                    btree_map::Entry::Occupied(mut entry) => {
+                        if entry.get().status != "NEW" && review.status == "NEW" {
+                            entry.insert(review);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(reviews)
+    }
+
+    /// Queries the review status of the change with the given number.
+    pub fn query_change_number(&self, number: u64) -> Result<Option<GerritReview>, GerritError> {
+        let changes = self.query(&format!("change:{number}"))?;
+        Ok(changes
+            .iter()
+            .find(|change| change.number == number)
+            .map(|change| change.to_review(&self.base_url)))
+    }
+
+    /// Sends the query, following the pages if Gerrit limits the number of
+    /// results per request.
+    fn query(&self, query: &str) -> Result<Vec<ChangeInfo>, GerritError> {
+        let mut changes = vec![];
+        loop {
+            let url = format!(
+                "{}/changes/?q={}&o=CURRENT_REVISION&o=DETAILED_LABELS&o=SUBMITTABLE&S={}",
+                self.base_url,
+                utf8_percent_encode(query, NON_ALPHANUMERIC),
+                changes.len(),
+            );
+            let body = self.get(&url)?;
+            let page = parse_change_infos(&body)
+                .map_err(|source| GerritError::Response { url, source })?;
+            let has_more = page.last().is_some_and(|change| change.more_changes);
+            changes.extend(page);
+            if !has_more {
+                return Ok(changes);
+            }
+        }
+    }
+
+    fn get(&self, url: &str) -> Result<Vec<u8>, GerritError> {
+        let output = Command::new("curl")
+            .args([
+                "--silent",
+                "--show-error",
+                "--fail",
+                "--location",
+                "--netrc-optional",
+                "--",
+            ])
+            .arg(url)
+            .output()
+            .map_err(GerritError::Spawn)?;
+        if !output.status.success() {
+            return Err(GerritError::Request {
+                url: url.to_owned(),
+                message: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            });
+        }
+        Ok(output.stdout)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChangeInfo {
+    change_id: String,
+    project: String,
+    #[serde(rename = "_number")]
+    number: u64,
+    status: String,
+    #[serde(default)]
+    submittable: bool,
+    current_revision: Option<String>,
+    #[serde(default)]
+    revisions: BTreeMap<String, RevisionInfo>,
+    #[serde(default)]
+    labels: BTreeMap<String, LabelInfo>,
+    /// Set on the last change if the results are truncated.
+    #[serde(rename = "_more_changes", default)]
+    more_changes: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RevisionInfo {
+    #[serde(rename = "_number")]
+    number: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LabelInfo {
+    approved: Option<IgnoredAny>,
+    rejected: Option<IgnoredAny>,
+    #[serde(default)]
+    all: Vec<ApprovalInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApprovalInfo {
+    value: Option<i64>,
+}
+
+impl ChangeInfo {
+    fn to_review(&self, base_url: &str) -> GerritReview {
+        let patchset = self
+            .current_revision
+            .as_ref()
+            .and_then(|revision| self.revisions.get(revision))
+            .map_or(0, |revision| revision.number);
+        let labels = self
+            .labels
+            .iter()
+            .map(|(name, label)| {
+                let values = label.all.iter().filter_map(|approval| approval.value);
+                let value = values
+                    .clone()
+                    .min()
+                    .filter(|&value| value < 0)
+                    .or_else(|| values.max())
+                    .unwrap_or(0);
+                GerritLabel {
+                    name: name.clone(),
+                    value,
+                    approved: label.approved.is_some(),
+                    rejected: label.rejected.is_some(),
+                }
+            })
+            .collect();
+        GerritReview {
+            number: self.number,
+            patchset,
+            status: self.status.clone(),
+            submittable: self.submittable,
+            url: format!("{base_url}/c/{}/+/{}", self.project, self.number),
+            labels,
+        }
+    }
+}
+
+/// Parses a list of `ChangeInfo` entities, skipping the prefix Gerrit adds to
+/// JSON responses to prevent cross-site script inclusion.
+fn parse_change_infos(body: &[u8]) -> Result<Vec<ChangeInfo>, serde_json::Error> {
+    let body = body.strip_prefix(b")]}'").unwrap_or(body);
+    serde_json::from_slice(body)
+}
+
+/// Registers the `gerrit_*()` revset functions.
+pub fn add_revset_functions(extensions: &mut RevsetExtensions) {
+    extensions.add_custom_function("gerrit_changes", |_diagnostics, function, context| {
+        build_review_filter(function, context, |_| true)
+    });
+    extensions.add_custom_function("gerrit_approved", |_diagnostics, function, context| {
+        build_review_filter(function, context, GerritReview::is_approved)
+    });
+    extensions.add_custom_function("gerrit_submittable", |_diagnostics, function, context| {
+        build_review_filter(function, context, |review| review.submittable)
+    });
+}
+
+fn build_review_filter(
+    function: &FunctionCallNode,
+    context: &LoweringContext,
+    predicate: fn(&GerritReview) -> bool,
+) -> Result<Arc<UserRevsetExpression>, RevsetParseError> {
+    function.expect_no_arguments()?;
+    let Some(workspace) = context.workspace() else {
+        return Err(RevsetParseError::expression(
+            "Gerrit review status is only available in a workspace",
+            function.name_span,
+        ));
+    };
+    let RepoPathUiConverter::Fs { cwd: _, base } = workspace.path_converter;
+    Ok(RevsetExpression::filter(RevsetFilterPredicate::Extension(
+        Arc::new(GerritReviewFilter {
+            workspace_root: base.to_owned(),
+            predicate,
+            change_ids: OnceLock::new(),
+        }),
+    )))
+}
+
+/// Matches commits by the stored Gerrit review status.
+///
+/// The review status is loaded when the revset is evaluated, not when it is
+/// parsed.
+#[derive(Debug)]
+struct GerritReviewFilter {
+    workspace_root: PathBuf,
+    predicate: fn(&GerritReview) -> bool,
+    /// `Change-Id`s of the matching reviews.
+    change_ids: OnceLock<HashSet<String>>,
+}
+
+impl GerritReviewFilter {
+    fn change_ids(&self) -> Result<&HashSet<String>, GerritError> {
+        if let Some(change_ids) = self.change_ids.get() {
+            return Ok(change_ids);
+        }
+        let reviews = GerritReviews::load_for_workspace(&self.workspace_root)?;
+        let change_ids = reviews
+            .iter()
+            .filter(|(_, review)| (self.predicate)(review))
+            .map(|(change_id, _)| change_id.to_owned())
+            .collect();
+        Ok(self.change_ids.get_or_init(|| change_ids))
+    }
+}
+
+impl RevsetFilterExtension for GerritReviewFilter {
+    fn matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError> {
+        let change_ids = self
+            .change_ids()
+            .map_err(|err| RevsetEvaluationError::Other(err.into()))?;
+        Ok(!change_ids.is_empty() && change_ids.contains(&gerrit_change_id(commit)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derived_gerrit_change_id() {
+        let change_id = ChangeId::from_hex("0123456789abcdef0123456789abcdef");
+        assert_eq!(
+            derived_gerrit_change_id(&change_id),
+            "I0123456789abcdef0123456789abcdef6a6a6964"
+        );
+        assert_eq!(
+            change_id_from_gerrit_change_id("I0123456789abcdef0123456789abcdef6a6a6964"),
+            Some(change_id)
+        );
+        assert_eq!(
+            change_id_from_gerrit_change_id("I1111111111111111111111111111111111111111"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_change_infos() {
+        let body = br#")]}'
+[
+  {
+    "change_id": "I1111111111111111111111111111111111111111",
+    "project": "jj",
+    "_number": 42,
+    "status": "NEW",
+    "submittable": true,
+    "current_revision": "bbb",
+    "revisions": {"bbb": {"_number": 3}},
+    "labels": {
+      "Code-Review": {"approved": {"_account_id": 1}, "all": [{"value": 2}, {"value": 1}]},
+      "Verified": {"rejected": {"_account_id": 2}, "all": [{"value": 1}, {"value": -1}]},
+      "Other": {"all": [{"_account_id": 3}]}
+    }
+  }
+]
+"#;
+        let changes = parse_change_infos(body).unwrap();
+        assert_eq!(changes.len(), 1);
+        let review = changes[0].to_review("https://review.example.com");
+        assert_eq!(review.number, 42);
+        assert_eq!(review.patchset, 3);
+        assert_eq!(review.url, "https://review.example.com/c/jj/+/42");
+        assert!(review.submittable);
+        assert_eq!(review.votes(), ["Code-Review+2", "Verified-1"]);
+        assert!(!review.is_approved());
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hooks which run commands against the commits affected by an operation.
+//!
+//! Hooks are configured in the `hooks.<event>.<name>` tables. Each hook runs
+//! its command in a working copy of each affected commit, using the same
+//! machinery as `jj run`.
+
+use std::io::Write as _;
+use std::sync::Arc;
+
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::revset::UserRevsetExpression;
+
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::config_error;
+use crate::command_error::user_error;
+use crate::commands::run::check_commits;
+use crate::config::CommandNameAndArgs;
+use crate::revset_util::RevsetExpressionEvaluator;
+use crate::ui::Ui;
+
+/// Name of the `jj run` workspace pool hooks are run in.
+const HOOKS_POOL_NAME: &str = "hooks";
+
+/// Event which triggers hooks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HookEvent {
+    /// Before `jj describe` updates descriptions. Runs against the commits
+    /// with the new descriptions.
+    PreDescribe,
+    /// Before `jj git push` pushes. Runs against the commits to be pushed.
+    PrePush,
+    /// After an operation is committed. Runs against the commits added by the
+    /// operation.
+    PostOperation,
+}
+
+impl HookEvent {
+    /// Name of the event in the `hooks` config table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PreDescribe => "pre-describe",
+            Self::PrePush => "pre-push",
+            Self::PostOperation => "post-operation",
+        }
+    }
+
+    /// Whether a failing hook aborts the operation. Hooks which run after the
+    /// operation is committed can only warn.
+    fn can_abort(self) -> bool {
+        match self {
+            Self::PreDescribe | Self::PrePush => true,
+            Self::PostOperation => false,
+        }
+    }
+}
+
+/// A hook configured in the `hooks.<event>.<name>` table.
+struct HookConfig {
+    name: String,
+    command: CommandNameAndArgs,
+    /// Only the affected commits which are also in this revset are checked.
+    revisions: Arc<UserRevsetExpression>,
+}
+
+/// Simplifies deserialization of the config values while building a
+/// HookConfig.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RawHookConfig {
+    command: CommandNameAndArgs,
+    #[serde(default = "default_hook_revisions")]
+    revisions: String,
+    #[serde(default = "default_hook_enabled")]
+    enabled: bool,
+}
+
+fn default_hook_revisions() -> String {
+    "all()".to_owned()
+}
+
+fn default_hook_enabled() -> bool {
+    true
+}
+
+/// Parses the enabled hooks in the `hooks.<event>` config table.
+fn get_hooks_config(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    event: HookEvent,
+) -> Result<Vec<HookConfig>, CommandError> {
+    let settings = workspace_command.settings();
+    let mut hooks = vec![];
+    // Sort keys early so hooks run in a deterministic order.
+    for name in settings.table_keys(["hooks", event.name()]).sorted() {
+        let hook: RawHookConfig = settings.get(["hooks", event.name(), name])?;
+        if !hook.enabled {
+            continue;
+        }
+        if let CommandNameAndArgs::Structured { .. } = &hook.command {
+            return Err(config_error(format!(
+                "`hooks.{event}.{name}.command` can't set environment variables",
+                event = event.name()
+            )));
+        }
+        let revisions = workspace_command
+            .parse_revset(ui, &RevisionArg::from(hook.revisions))?
+            .expression()
+            .clone();
+        hooks.push(HookConfig {
+            name: format!("{}.{name}", event.name()),
+            command: hook.command,
+            revisions,
+        });
+    }
+    Ok(hooks)
+}
+
+/// Runs the hooks configured for the `event` against the `affected` commits
+/// which match the hooks' `revisions`.
+///
+/// `attach_evaluator` should evaluate expressions in the repo the affected
+/// commits are in, which may be a transaction that isn't committed yet.
+pub(crate) async fn run_hooks<'repo>(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    event: HookEvent,
+    affected: &Arc<UserRevsetExpression>,
+    attach_evaluator: &dyn Fn(Arc<UserRevsetExpression>) -> RevsetExpressionEvaluator<'repo>,
+    format_commit_summary: &dyn Fn(&Commit) -> String,
+) -> Result<(), CommandError> {
+    let hooks = get_hooks_config(ui, workspace_command, event)?;
+    for hook in hooks {
+        let mut commits: Vec<Commit> = attach_evaluator(affected.intersection(&hook.revisions))
+            .evaluate_to_commits()?
+            .try_collect()
+            .await?;
+        if commits.is_empty() {
+            continue;
+        }
+        commits.reverse();
+        writeln!(
+            ui.status(),
+            "Running hook `{}` on {} commits",
+            hook.name,
+            commits.len()
+        )?;
+        let failures = check_commits(
+            ui,
+            workspace_command,
+            HOOKS_POOL_NAME,
+            &hook.command,
+            &commits,
+        )
+        .await?;
+        let Some((commit, status)) = failures.first() else {
+            continue;
+        };
+        if event.can_abort() {
+            let mut err = user_error(format!("Hook `{}` failed with {status}", hook.name));
+            err.add_hint(format!(
+                "Failed revision: {}",
+                format_commit_summary(commit)
+            ));
+            return Err(err);
+        }
+        writeln!(
+            ui.warning_default(),
+            "Hook `{}` failed with {status} on revision {}",
+            hook.name,
+            format_commit_summary(commit)
+        )?;
+    }
+    Ok(())
+}
@@ -26,6 +26,7 @@ pub mod description_util;
 pub mod diff_util;
 pub mod formatter;
 pub mod generic_templater;
+pub mod gerrit_util;
 #[cfg(feature = "git")]
 pub mod git_util;
 #[cfg(not(feature = "git"))]
@@ -44,6 +45,7 @@ pub mod git_util {
     }
 }
 pub mod graphlog;
+pub mod hooks;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
@@ -15,6 +15,7 @@
 mod builtin;
 mod diff_working_copies;
 mod external;
+mod text_editor;
 
 use std::sync::Arc;
 
@@ -54,7 +55,9 @@ use self::external::ExternalToolError;
 use self::external::edit_diff_external;
 pub use self::external::generate_diff;
 pub use self::external::invoke_external_diff;
+pub use self::text_editor::edit_conflicts_in_text_editor;
 use crate::config::CommandNameAndArgs;
+use crate::description_util::TempTextEditError;
 use crate::ui::Ui;
 
 const BUILTIN_EDITOR_NAME: &str = ":builtin";
@@ -111,6 +114,8 @@ pub enum ConflictResolveError {
     )]
     EmptyOrUnchanged,
     #[error(transparent)]
+    TextEditor(#[from] TempTextEditError),
+    #[error(transparent)]
     Backend(#[from] jj_lib::backend::BackendError),
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -330,9 +335,27 @@ struct MergeToolFile {
 }
 
 impl MergeToolFile {
+    /// Loads a conflicted file which can be resolved by 3-way merge.
     async fn from_tree_and_path(
         tree: &MergedTree,
         repo_path: &RepoPath,
+    ) -> Result<Self, ConflictResolveError> {
+        let merge_tool_file = Self::from_tree_and_path_any_sides(tree, repo_path).await?;
+        // We only support conflicts with 2 sides (3-way conflicts)
+        let sides = merge_tool_file.file.ids.num_sides();
+        if sides > 2 {
+            return Err(ConflictResolveError::ConflictTooComplicated {
+                path: repo_path.to_owned(),
+                sides,
+            });
+        }
+        Ok(merge_tool_file)
+    }
+
+    /// Loads a conflicted file with any number of sides.
+    async fn from_tree_and_path_any_sides(
+        tree: &MergedTree,
+        repo_path: &RepoPath,
     ) -> Result<Self, ConflictResolveError> {
         let conflict = match tree.path_value(repo_path).await?.into_resolved() {
             Err(conflict) => conflict,
@@ -346,13 +369,6 @@ impl MergeToolFile {
                     path: repo_path.to_owned(),
                     summary: conflict.describe(tree.labels()),
                 })?;
-        // We only support conflicts with 2 sides (3-way conflicts)
-        if file.ids.num_sides() > 2 {
-            return Err(ConflictResolveError::ConflictTooComplicated {
-                path: repo_path.to_owned(),
-                sides: file.ids.num_sides(),
-            });
-        }
         if file.executable.is_none() {
             return Err(ConflictResolveError::ExecutableConflict {
                 path: repo_path.to_owned(),
@@ -0,0 +1,138 @@
+use std::io::Write as _;
+
+use futures::future::try_join_all;
+use jj_lib::backend::CopyId;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflicts;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMaterializeOptions;
+use jj_lib::conflicts::choose_materialized_conflict_marker_len;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree_builder::MergedTreeBuilder;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::store::Store;
+
+use super::ConflictResolveError;
+use super::MergeToolFile;
+use super::MergeToolPartialResolutionError;
+use crate::description_util::TextEditor;
+use crate::ui::Ui;
+
+async fn edit_conflict_in_text_editor_single_file(
+    editor: &TextEditor,
+    store: &Store,
+    merge_tool_file: &MergeToolFile,
+    conflict_marker_style: ConflictMarkerStyle,
+    tree_builder: &mut MergedTreeBuilder,
+) -> Result<(), ConflictResolveError> {
+    let MergeToolFile {
+        repo_path,
+        conflict,
+        file,
+    } = merge_tool_file;
+
+    let conflict_marker_len = choose_materialized_conflict_marker_len(&file.contents);
+    let options = ConflictMaterializeOptions {
+        marker_style: conflict_marker_style,
+        marker_len: Some(conflict_marker_len),
+        merge: store.merge_options().clone(),
+    };
+    let initial_content = materialize_merge_result_to_bytes(&file.contents, &file.labels, &options);
+    // Keep the file name so the editor can pick up the file type.
+    let suffix = if let Some(filename) = repo_path.components().next_back() {
+        let name = filename
+            .to_fs_name()
+            .map_err(|err| err.with_path(repo_path))?;
+        Some(format!("_{name}"))
+    } else {
+        None
+    };
+    let content = editor.edit_bytes(&initial_content, suffix.as_deref())?;
+    if initial_content == content {
+        // Leave the conflict as is.
+        return Ok(());
+    }
+
+    // Hunks that still have conflict markers remain conflicted. If there are
+    // no valid markers, the whole file is resolved.
+    let new_file_ids = conflicts::update_from_content(
+        &file.unsimplified_ids,
+        store,
+        repo_path,
+        &content,
+        conflict_marker_len,
+    )
+    .await?;
+    let new_tree_value = match new_file_ids.into_resolved() {
+        Ok(file_id) => {
+            let executable = file.executable.expect("should have been resolved");
+            Merge::resolved(file_id.map(|id| TreeValue::File {
+                id,
+                executable,
+                copy_id: CopyId::placeholder(),
+            }))
+        }
+        // Update the file ids only, leaving the executable flags unchanged
+        Err(file_ids) => conflict.with_new_file_ids(&file_ids),
+    };
+    tree_builder.set_or_remove(repo_path.to_owned(), new_tree_value);
+    Ok(())
+}
+
+/// Opens the conflicted files one by one in the text editor, and updates the
+/// conflicts from the edited conflict markers.
+///
+/// Unlike merge tools, conflicts with any number of sides are supported.
+pub async fn edit_conflicts_in_text_editor(
+    ui: &Ui,
+    path_converter: &RepoPathUiConverter,
+    editor: &TextEditor,
+    tree: &MergedTree,
+    repo_paths: &[&RepoPath],
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(MergedTree, Option<MergeToolPartialResolutionError>), ConflictResolveError> {
+    let merge_tool_files: Vec<MergeToolFile> = try_join_all(
+        repo_paths
+            .iter()
+            .map(|&repo_path| MergeToolFile::from_tree_and_path_any_sides(tree, repo_path)),
+    )
+    .await?;
+
+    let mut tree_builder = MergedTreeBuilder::new(tree.clone());
+    let mut partial_resolution_error = None;
+    for (i, merge_tool_file) in merge_tool_files.iter().enumerate() {
+        writeln!(
+            ui.status(),
+            "Resolving conflicts in: {}",
+            path_converter.format_file_path(&merge_tool_file.repo_path)
+        )?;
+        match edit_conflict_in_text_editor_single_file(
+            editor,
+            tree.store(),
+            merge_tool_file,
+            conflict_marker_style,
+            &mut tree_builder,
+        )
+        .await
+        {
+            Ok(()) => {}
+            Err(err) if i == 0 => {
+                // If the first resolution fails, just return the error normally
+                return Err(err);
+            }
+            Err(err) => {
+                // Keep the files which were already edited
+                partial_resolution_error = Some(MergeToolPartialResolutionError {
+                    source: err,
+                    resolved_count: i,
+                });
+                break;
+            }
+        }
+    }
+    let new_tree = tree_builder.write_tree().await?;
+    Ok((new_tree, partial_resolution_error))
+}
@@ -50,6 +50,7 @@ use crate::templater::BoxedSerializeProperty;
 use crate::templater::BoxedTemplateProperty;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
+use crate::templater::TemplateMap;
 use crate::templater::TemplatePropertyExt as _;
 use crate::templater::WrapTemplateProperty;
 
@@ -544,12 +545,11 @@ where
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
             let out_property = self_property.map(|op| {
-                // TODO: introduce map type
                 op.metadata()
                     .attributes
                     .iter()
-                    .map(|(key, value)| format!("{key}: {value}"))
-                    .join("\n")
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<TemplateMap<_>>()
             });
             Ok(out_property.into_dyn_wrapped())
         },
@@ -564,7 +564,6 @@ where
             ));
             function.expect_no_arguments()?;
             let out_property = self_property.map(|op| {
-                // TODO: introduce map type
                 op.metadata()
                     .attributes
                     .iter()
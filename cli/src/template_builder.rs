@@ -21,6 +21,7 @@ use std::path::PathBuf;
 
 use bstr::BString;
 use bstr::ByteSlice as _;
+use itertools::Either;
 use itertools::Itertools as _;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
@@ -59,6 +60,7 @@ use crate::templater::BoxedTemplateProperty;
 use crate::templater::CoalesceTemplate;
 use crate::templater::ConcatTemplate;
 use crate::templater::ConditionalProperty;
+use crate::templater::Duration;
 use crate::templater::Email;
 use crate::templater::HyperlinkTemplate;
 use crate::templater::JoinTemplate;
@@ -75,6 +77,7 @@ use crate::templater::SeparateTemplate;
 use crate::templater::SizeHint;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
+use crate::templater::TemplateMap;
 use crate::templater::TemplateProperty;
 use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
@@ -192,6 +195,10 @@ where
     Self: WrapTemplateProperty<'a, RegexCaptures>,
     Self: WrapTemplateProperty<'a, Timestamp>,
     Self: WrapTemplateProperty<'a, TimestampRange>,
+    Self: WrapTemplateProperty<'a, Duration>,
+    Self: WrapTemplateProperty<'a, TemplateMap<String>>,
+    Self: WrapTemplateProperty<'a, TemplateMap<Vec<String>>>,
+    Self: WrapTemplateProperty<'a, TemplateMap<ConfigValue>>,
 {
     fn wrap_template(template: Box<dyn Template + 'a>) -> Self;
     fn wrap_any(property: BoxedAnyProperty<'a>) -> Self;
@@ -237,6 +244,10 @@ pub enum CoreTemplatePropertyKind<'a> {
     RegexCaptures(BoxedTemplateProperty<'a, RegexCaptures>),
     Timestamp(BoxedTemplateProperty<'a, Timestamp>),
     TimestampRange(BoxedTemplateProperty<'a, TimestampRange>),
+    Duration(BoxedTemplateProperty<'a, Duration>),
+    StringMap(BoxedTemplateProperty<'a, TemplateMap<String>>),
+    StringListMap(BoxedTemplateProperty<'a, TemplateMap<Vec<String>>>),
+    ConfigValueMap(BoxedTemplateProperty<'a, TemplateMap<ConfigValue>>),
 
     // Both TemplateProperty and Template can represent a value to be evaluated
     // dynamically, which suggests that `Box<dyn Template + 'a>` could be
@@ -277,6 +288,10 @@ macro_rules! impl_core_property_wrappers {
             RegexCaptures($crate::templater::RegexCaptures),
             Timestamp(jj_lib::backend::Timestamp),
             TimestampRange(jj_lib::op_store::TimestampRange),
+            Duration($crate::templater::Duration),
+            StringMap($crate::templater::TemplateMap<String>),
+            StringListMap($crate::templater::TemplateMap<Vec<String>>),
+            ConfigValueMap($crate::templater::TemplateMap<jj_lib::config::ConfigValue>),
         });
     };
 }
@@ -317,6 +332,10 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::RegexCaptures(_) => "RegexCaptures",
             Self::Timestamp(_) => "Timestamp",
             Self::TimestampRange(_) => "TimestampRange",
+            Self::Duration(_) => "Duration",
+            Self::StringMap(_) => "Map<String, String>",
+            Self::StringListMap(_) => "Map<String, List<String>>",
+            Self::ConfigValueMap(_) => "Map<String, ConfigValue>",
             Self::Template(_) => "Template",
             Self::Any(_) => "Any",
             Self::AnyList(_) => "AnyList",
@@ -356,6 +375,10 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::RegexCaptures(_) => Err(self),
             Self::Timestamp(_) => Err(self),
             Self::TimestampRange(_) => Err(self),
+            Self::Duration(_) => Err(self),
+            Self::StringMap(property) => Ok(property.map(|m| !m.is_empty()).into_dyn()),
+            Self::StringListMap(property) => Ok(property.map(|m| !m.is_empty()).into_dyn()),
+            Self::ConfigValueMap(property) => Ok(property.map(|m| !m.is_empty()).into_dyn()),
             // Template and AnyList types could also be evaluated to boolean,
             // but it's less likely to apply label() or .map() and use the
             // result as conditional.
@@ -405,6 +428,18 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::RegexCaptures(_) => None,
             Self::Timestamp(property) => Some(property.into_serialize()),
             Self::TimestampRange(property) => Some(property.into_serialize()),
+            Self::Duration(property) => Some(property.into_serialize()),
+            Self::StringMap(property) => Some(property.into_serialize()),
+            Self::StringListMap(property) => Some(property.into_serialize()),
+            Self::ConfigValueMap(property) => Some(
+                property
+                    .map(|map| {
+                        map.into_iter()
+                            .map(|(key, value)| (key, config::to_serializable_value(value)))
+                            .collect::<TemplateMap<_>>()
+                    })
+                    .into_serialize(),
+            ),
             Self::Template(_) => None,
             Self::Any(property) => property.try_into_serialize(),
             Self::AnyList(property) => property.try_into_serialize(),
@@ -430,6 +465,10 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             Self::RegexCaptures(_) => None,
             Self::Timestamp(property) => Some(property.into_template()),
             Self::TimestampRange(property) => Some(property.into_template()),
+            Self::Duration(property) => Some(property.into_template()),
+            Self::StringMap(property) => Some(property.into_template()),
+            Self::StringListMap(property) => Some(property.into_template()),
+            Self::ConfigValueMap(property) => Some(property.into_template()),
             Self::Template(template) => Some(template),
             Self::Any(property) => property.try_into_template(),
             Self::AnyList(property) => property.try_into_template(),
@@ -474,6 +513,9 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::Email(lhs), Self::String(rhs)) => {
                 Some((lhs, rhs).map(|(l, r)| l.0 == r).into_dyn())
             }
+            (Self::Duration(lhs), Self::Duration(rhs)) => {
+                Some((lhs, rhs).map(|(l, r)| l.delta() == r.delta()).into_dyn())
+            }
             (Self::ByteString(_), _) => None,
             (Self::ByteStringList(_), _) => None,
             (Self::String(_), _) => None,
@@ -491,6 +533,10 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::RegexCaptures(_), _) => None,
             (Self::Timestamp(_), _) => None,
             (Self::TimestampRange(_), _) => None,
+            (Self::Duration(_), _) => None,
+            (Self::StringMap(_), _) => None,
+            (Self::StringListMap(_), _) => None,
+            (Self::ConfigValueMap(_), _) => None,
             (Self::Template(_), _) => None,
             (Self::Any(_), _) => None,
             (Self::AnyList(_), _) => None,
@@ -511,6 +557,11 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::IntegerOpt(lhs), Self::IntegerOpt(rhs)) => {
                 Some((lhs, rhs).map(|(l, r)| l.cmp(&r)).into_dyn())
             }
+            (Self::Duration(lhs), Self::Duration(rhs)) => Some(
+                (lhs, rhs)
+                    .map(|(l, r)| l.delta().cmp(&r.delta()))
+                    .into_dyn(),
+            ),
             (Self::ByteString(_), _) => None,
             (Self::ByteStringList(_), _) => None,
             (Self::String(_), _) => None,
@@ -528,6 +579,10 @@ impl<'a> CoreTemplatePropertyVar<'a> for CoreTemplatePropertyKind<'a> {
             (Self::RegexCaptures(_), _) => None,
             (Self::Timestamp(_), _) => None,
             (Self::TimestampRange(_), _) => None,
+            (Self::Duration(_), _) => None,
+            (Self::StringMap(_), _) => None,
+            (Self::StringListMap(_), _) => None,
+            (Self::ConfigValueMap(_), _) => None,
             (Self::Template(_), _) => None,
             (Self::Any(_), _) => None,
             (Self::AnyList(_), _) => None,
@@ -594,6 +649,10 @@ pub struct CoreTemplateBuildFnTable<'a, L: ?Sized, P = <L as TemplateLanguage<'a
     pub regex_captures_methods: TemplateBuildMethodFnMap<'a, L, RegexCaptures, P>,
     pub timestamp_methods: TemplateBuildMethodFnMap<'a, L, Timestamp, P>,
     pub timestamp_range_methods: TemplateBuildMethodFnMap<'a, L, TimestampRange, P>,
+    pub duration_methods: TemplateBuildMethodFnMap<'a, L, Duration, P>,
+    pub string_map_methods: TemplateBuildMethodFnMap<'a, L, TemplateMap<String>, P>,
+    pub string_list_map_methods: TemplateBuildMethodFnMap<'a, L, TemplateMap<Vec<String>>, P>,
+    pub config_value_map_methods: TemplateBuildMethodFnMap<'a, L, TemplateMap<ConfigValue>, P>,
     pub template_methods: BuildTemplateMethodFnMap<'a, L, P>,
     pub any_methods: BuildAnyMethodFnMap<'a, L, P>,
     pub any_list_methods: BuildAnyMethodFnMap<'a, L, P>,
@@ -625,6 +684,10 @@ impl<L: ?Sized, P> CoreTemplateBuildFnTable<'_, L, P> {
             regex_captures_methods: HashMap::new(),
             timestamp_methods: HashMap::new(),
             timestamp_range_methods: HashMap::new(),
+            duration_methods: HashMap::new(),
+            string_map_methods: HashMap::new(),
+            string_list_map_methods: HashMap::new(),
+            config_value_map_methods: HashMap::new(),
             template_methods: HashMap::new(),
             any_methods: HashMap::new(),
             any_list_methods: HashMap::new(),
@@ -648,6 +711,10 @@ impl<L: ?Sized, P> CoreTemplateBuildFnTable<'_, L, P> {
             regex_captures_methods,
             timestamp_methods,
             timestamp_range_methods,
+            duration_methods,
+            string_map_methods,
+            string_list_map_methods,
+            config_value_map_methods,
             template_methods,
             any_methods,
             any_list_methods,
@@ -668,6 +735,10 @@ impl<L: ?Sized, P> CoreTemplateBuildFnTable<'_, L, P> {
         merge_fn_map(&mut self.regex_captures_methods, regex_captures_methods);
         merge_fn_map(&mut self.timestamp_methods, timestamp_methods);
         merge_fn_map(&mut self.timestamp_range_methods, timestamp_range_methods);
+        merge_fn_map(&mut self.duration_methods, duration_methods);
+        merge_fn_map(&mut self.string_map_methods, string_map_methods);
+        merge_fn_map(&mut self.string_list_map_methods, string_list_map_methods);
+        merge_fn_map(&mut self.config_value_map_methods, config_value_map_methods);
         merge_fn_map(&mut self.template_methods, template_methods);
         merge_fn_map(&mut self.any_methods, any_methods);
         merge_fn_map(&mut self.any_list_methods, any_list_methods);
@@ -696,6 +767,10 @@ where
             regex_captures_methods: builtin_regex_captures_methods(),
             timestamp_methods: builtin_timestamp_methods(),
             timestamp_range_methods: builtin_timestamp_range_methods(),
+            duration_methods: builtin_duration_methods(),
+            string_map_methods: builtin_map_methods(),
+            string_list_map_methods: builtin_map_methods(),
+            config_value_map_methods: builtin_map_methods(),
             template_methods: HashMap::new(),
             any_methods: HashMap::new(),
             any_list_methods: builtin_any_list_methods(),
@@ -818,6 +893,26 @@ where
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::Duration(property) => {
+                let table = &self.duration_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
+            CoreTemplatePropertyKind::StringMap(property) => {
+                let table = &self.string_map_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
+            CoreTemplatePropertyKind::StringListMap(property) => {
+                let table = &self.string_list_map_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
+            CoreTemplatePropertyKind::ConfigValueMap(property) => {
+                let table = &self.config_value_map_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
             CoreTemplatePropertyKind::Template(template) => {
                 let table = &self.template_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -1075,39 +1170,75 @@ fn build_binary_operation<'a, L: TemplateLanguage<'a> + ?Sized>(
             };
             Ok(L::Property::wrap_property(out))
         }
-        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+        BinaryOp::Sub => {
+            let lhs =
+                template_parser::catch_aliases(diagnostics, lhs_node, |diagnostics, node| {
+                    let expression = build_expression(language, diagnostics, build_ctx, node)?;
+                    let actual_type = expression.type_name();
+                    match expression.property.try_into_timestamp() {
+                        Ok(property) => Ok(Either::Right(property)),
+                        Err(property) => {
+                            property.try_into_integer().map(Either::Left).map_err(|_| {
+                                TemplateParseError::expected_type("Integer", actual_type, node.span)
+                            })
+                        }
+                    }
+                })?;
+            match lhs {
+                Either::Left(lhs) => {
+                    let rhs =
+                        expect_integer_expression(language, diagnostics, build_ctx, rhs_node)?;
+                    Ok(build_integer_arithmetic(op, lhs, rhs).into_dyn_wrapped())
+                }
+                Either::Right(lhs) => {
+                    // Timestamp - Timestamp is the duration between the two.
+                    let rhs =
+                        expect_timestamp_expression(language, diagnostics, build_ctx, rhs_node)?;
+                    let out = (lhs, rhs).and_then(|(l, r)| Ok(Duration::between(&r, &l)?));
+                    Ok(out.into_dyn_wrapped())
+                }
+            }
+        }
+        BinaryOp::Add | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
             let lhs = expect_integer_expression(language, diagnostics, build_ctx, lhs_node)?;
             let rhs = expect_integer_expression(language, diagnostics, build_ctx, rhs_node)?;
-            let build = |op: fn(i64, i64) -> Option<i64>, msg: fn(i64) -> &'static str| {
-                (lhs, rhs).and_then(move |(l, r)| {
-                    op(l, r).ok_or_else(|| TemplatePropertyError(msg(r).into()))
-                })
-            };
-            let out = match op {
-                BinaryOp::Add => build(i64::checked_add, |_| "Attempt to add with overflow"),
-                BinaryOp::Sub => build(i64::checked_sub, |_| "Attempt to subtract with overflow"),
-                BinaryOp::Mul => build(i64::checked_mul, |_| "Attempt to multiply with overflow"),
-                BinaryOp::Div => build(i64::checked_div, |r| {
-                    if r == 0 {
-                        "Attempt to divide by zero"
-                    } else {
-                        "Attempt to divide with overflow"
-                    }
-                }),
-                BinaryOp::Rem => build(i64::checked_rem, |r| {
-                    if r == 0 {
-                        "Attempt to divide by zero"
-                    } else {
-                        "Attempt to divide with overflow"
-                    }
-                }),
-                _ => unreachable!(),
-            };
-            Ok(out.into_dyn_wrapped())
+            Ok(build_integer_arithmetic(op, lhs, rhs).into_dyn_wrapped())
         }
     }
 }
 
+fn build_integer_arithmetic<'a>(
+    op: BinaryOp,
+    lhs: BoxedTemplateProperty<'a, i64>,
+    rhs: BoxedTemplateProperty<'a, i64>,
+) -> BoxedTemplateProperty<'a, i64> {
+    let build = |op: fn(i64, i64) -> Option<i64>, msg: fn(i64) -> &'static str| {
+        (lhs, rhs)
+            .and_then(move |(l, r)| op(l, r).ok_or_else(|| TemplatePropertyError(msg(r).into())))
+    };
+    let out = match op {
+        BinaryOp::Add => build(i64::checked_add, |_| "Attempt to add with overflow"),
+        BinaryOp::Sub => build(i64::checked_sub, |_| "Attempt to subtract with overflow"),
+        BinaryOp::Mul => build(i64::checked_mul, |_| "Attempt to multiply with overflow"),
+        BinaryOp::Div => build(i64::checked_div, |r| {
+            if r == 0 {
+                "Attempt to divide by zero"
+            } else {
+                "Attempt to divide with overflow"
+            }
+        }),
+        BinaryOp::Rem => build(i64::checked_rem, |r| {
+            if r == 0 {
+                "Attempt to divide by zero"
+            } else {
+                "Attempt to divide with overflow"
+            }
+        }),
+        _ => unreachable!(),
+    };
+    out.into_dyn()
+}
+
 fn builtin_byte_string_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
 -> TemplateBuildMethodFnMap<'a, L, BString> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1684,8 +1815,23 @@ fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "as_map",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|value| match value {
+                ConfigValue::InlineTable(table) => Ok(table
+                    .into_iter()
+                    .map(|(key, value)| (key.as_str().to_owned(), value.decorated("", "")))
+                    .collect::<TemplateMap<_>>()),
+                _ => Err(TemplatePropertyError(
+                    format!("Expected a table, but is {}", value.type_name()).into(),
+                )),
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     // TODO: add is_<type>() -> Boolean?
-    // TODO: add .get(key) -> ConfigValue or Option<ConfigValue>?
     map
 }
 
@@ -1878,10 +2024,8 @@ fn builtin_timestamp_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
             let now = Timestamp::now();
-            let format = timeago::Formatter::new();
-            let out_property = self_property.and_then(move |timestamp| {
-                Ok(time_util::format_duration(&timestamp, &now, &format)?)
-            });
+            let out_property =
+                self_property.and_then(move |timestamp| Ok(Duration::ago(&timestamp, &now)?));
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -2015,17 +2159,40 @@ fn builtin_timestamp_range_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
         "duration",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            // TODO: Introduce duration type, and move formatting to it.
-            let out_property = self_property.and_then(|time_range| {
-                let mut f = timeago::Formatter::new();
-                f.min_unit(timeago::TimeUnit::Microseconds).ago("");
-                let duration = time_util::format_duration(&time_range.start, &time_range.end, &f)?;
-                if duration == "now" {
-                    Ok("less than a microsecond".to_owned())
-                } else {
-                    Ok(duration)
-                }
-            });
+            let out_property = self_property
+                .and_then(|time_range| Ok(Duration::between(&time_range.start, &time_range.end)?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
+
+fn builtin_duration_methods<'a, L: TemplateLanguage<'a> + ?Sized>()
+-> TemplateBuildMethodFnMap<'a, L, Duration> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, Duration>::new();
+    map.insert(
+        "seconds",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|duration| duration.delta().num_seconds());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "minutes",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|duration| duration.delta().num_minutes());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "humanize",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|duration| duration.humanize());
             Ok(out_property.into_dyn_wrapped())
         },
     );
@@ -2207,6 +2374,108 @@ where
     map
 }
 
+/// Creates new symbol table for map property.
+pub fn builtin_map_methods<'a, L, V>() -> TemplateBuildMethodFnMap<'a, L, TemplateMap<V>>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    L::Property: WrapTemplateProperty<'a, V> + WrapTemplateProperty<'a, TemplateMap<V>>,
+    V: Clone + 'a,
+{
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, TemplateMap<V>>::new();
+    map.insert(
+        "len",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|items| Ok(i64::try_from(items.len())?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "contains_key",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [key_node] = function.expect_exact_arguments()?;
+            let key_property =
+                expect_stringify_expression(language, diagnostics, build_ctx, key_node)?;
+            let out_property =
+                (self_property, key_property).map(|(items, key)| items.contains_key(&key));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "get",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [key_node] = function.expect_exact_arguments()?;
+            let key_property =
+                expect_stringify_expression(language, diagnostics, build_ctx, key_node)?;
+            // TODO: Return `Option<T>` instead of erroring out.
+            let out_property = (self_property, key_property).and_then(|(mut items, key)| {
+                items
+                    .swap_remove(&key)
+                    .ok_or_else(|| TemplatePropertyError(format!("Key {key:?} not found").into()))
+            });
+            Ok(L::Property::wrap_property(out_property.into_dyn()))
+        },
+    );
+    map.insert(
+        "keys",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|items| items.into_keys().collect_vec());
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "values",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let values_property = self_property.map(|items| items.into_values().collect_vec());
+            let item_placeholder = PropertyPlaceholder::<V>::new();
+            let item = L::Property::wrap_property(item_placeholder.clone().into_dyn());
+            let values = ListMapProperty::new(
+                values_property,
+                item_placeholder,
+                Box::new(Expression::unlabeled(item)),
+            );
+            Ok(L::Property::wrap_any_list(Box::new(values)))
+        },
+    );
+    map.insert(
+        "map",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [lambda_node] = function.expect_exact_arguments()?;
+            let entry_placeholder = PropertyPlaceholder::<(String, V)>::new();
+            let mapped_entry =
+                template_parser::catch_aliases(diagnostics, lambda_node, |diagnostics, node| {
+                    let lambda = template_parser::expect_lambda(node)?;
+                    build_lambda_expression(
+                        build_ctx,
+                        lambda,
+                        &[
+                            &|| {
+                                entry_placeholder
+                                    .clone()
+                                    .map(|(key, _)| key)
+                                    .into_dyn_wrapped()
+                            },
+                            &|| {
+                                let value = entry_placeholder.clone().map(|(_, value)| value);
+                                L::Property::wrap_property(value.into_dyn())
+                            },
+                        ],
+                        |build_ctx, body| {
+                            expect_any_expression(language, diagnostics, build_ctx, body)
+                        },
+                    )
+                })?;
+            let mapped = ListMapProperty::new(self_property, entry_placeholder, mapped_entry);
+            Ok(L::Property::wrap_any_list(Box::new(mapped)))
+        },
+    );
+    map
+}
+
 /// Builds expression that extracts iterable property and filters its items.
 fn build_filter_operation<'a, L, O, P, B>(
     language: &L,
@@ -4654,6 +4923,81 @@ mod tests {
             @"<Error: invalid type: sequence, expected a boolean>");
     }
 
+    #[test]
+    fn test_map_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("empty", || literal(TemplateMap::<String>::new()));
+        env.add_keyword("map", || {
+            literal(TemplateMap::from_iter([
+                ("b".to_owned(), "1".to_owned()),
+                ("a".to_owned(), "2".to_owned()),
+            ]))
+        });
+        env.add_keyword("list_map", || {
+            literal(TemplateMap::from_iter([
+                ("k".to_owned(), vec!["x".to_owned(), "y".to_owned()]),
+                ("l".to_owned(), vec!["z".to_owned()]),
+            ]))
+        });
+        env.add_keyword("table", || {
+            literal(r#"{ a = 1, b = "x" }"#.parse::<ConfigValue>().unwrap())
+        });
+
+        insta::assert_snapshot!(env.render_ok("empty"), @"");
+        insta::assert_snapshot!(env.render_ok("map"), @"
+        b: 1
+        a: 2
+        ");
+        insta::assert_snapshot!(env.render_ok("list_map"), @"
+        k: x y
+        l: z
+        ");
+        insta::assert_snapshot!(env.render_ok("if(empty, 'a', 'b')"), @"b");
+        insta::assert_snapshot!(env.render_ok("if(map, 'a', 'b')"), @"a");
+
+        insta::assert_snapshot!(env.render_ok("map.len()"), @"2");
+        insta::assert_snapshot!(env.render_ok("map.keys()"), @"b a");
+        insta::assert_snapshot!(env.render_ok("map.values()"), @"1 2");
+        insta::assert_snapshot!(env.render_ok("map.values().join(',')"), @"1,2");
+        insta::assert_snapshot!(env.render_ok("map.contains_key('a')"), @"true");
+        insta::assert_snapshot!(env.render_ok("map.contains_key('c')"), @"false");
+        insta::assert_snapshot!(env.render_ok("map.get('a')"), @"2");
+        insta::assert_snapshot!(env.render_ok("map.get('a').len()"), @"1");
+        insta::assert_snapshot!(env.render_ok("map.get('c')"), @r#"<Error: Key "c" not found>"#);
+        insta::assert_snapshot!(
+            env.render_ok("map.map(|k, v| k ++ '=' ++ v).join(',')"), @"b=1,a=2");
+        insta::assert_snapshot!(env.render_ok("list_map.get('k').join(',')"), @"x,y");
+        insta::assert_snapshot!(
+            env.render_ok("list_map.map(|k, v| k ++ ':' ++ v.len())"), @"k:2 l:1");
+
+        insta::assert_snapshot!(env.render_ok("json(empty)"), @"{}");
+        insta::assert_snapshot!(env.render_ok("json(map)"), @r#"{"b":"1","a":"2"}"#);
+        insta::assert_snapshot!(env.render_ok("json(list_map)"), @r#"{"k":["x","y"],"l":["z"]}"#);
+        insta::assert_snapshot!(
+            env.render_ok("json(map.map(|k, v| k))"), @r#"["b","a"]"#);
+
+        insta::assert_snapshot!(env.render_ok("table.as_map().keys()"), @"a b");
+        insta::assert_snapshot!(env.render_ok("table.as_map().get('b').as_string()"), @"x");
+        insta::assert_snapshot!(env.render_ok("table.as_map()"), @r#"
+        a: 1
+        b: "x"
+        "#);
+        insta::assert_snapshot!(env.render_ok("json(table.as_map())"), @r#"{"a":1,"b":"x"}"#);
+        insta::assert_snapshot!(
+            env.render_ok("table.as_map().get('a').as_map()"),
+            @"<Error: Expected a table, but is integer>");
+
+        // Lambda should take key and value
+        insta::assert_snapshot!(env.parse_err("map.map(|v| v)"), @"
+         --> 1:10
+          |
+        1 | map.map(|v| v)
+          |          ^
+          |
+          = Expected 2 lambda parameters
+        ");
+    }
+
     #[test]
     fn test_signature_and_email_methods() {
         let mut env = TestTemplateEnv::new();
@@ -4891,6 +5235,73 @@ mod tests {
             env.render_ok("one_msec.duration()"), @"1 millisecond");
     }
 
+    #[test]
+    fn test_duration_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("t0", || literal(new_timestamp(0, 0)));
+        env.add_keyword("t1", || literal(new_timestamp(90_000, 60)));
+        env.add_keyword("t2", || literal(new_timestamp(7_200_000, -60)));
+        env.add_keyword("range", || {
+            literal(TimestampRange {
+                start: new_timestamp(0, 0),
+                end: new_timestamp(90_000, 0),
+            })
+        });
+
+        insta::assert_snapshot!(env.render_ok("t1 - t0"), @"1 minute");
+        insta::assert_snapshot!(env.render_ok("t0 - t1"), @"-1 minute");
+        insta::assert_snapshot!(env.render_ok("t0 - t0"), @"less than a microsecond");
+        insta::assert_snapshot!(env.render_ok("(t1 - t0).seconds()"), @"90");
+        insta::assert_snapshot!(env.render_ok("(t1 - t0).minutes()"), @"1");
+        insta::assert_snapshot!(env.render_ok("(t0 - t1).seconds()"), @"-90");
+        insta::assert_snapshot!(env.render_ok("(t2 - t0).humanize()"), @"2 hours");
+        insta::assert_snapshot!(env.render_ok("range.duration().seconds()"), @"90");
+
+        insta::assert_snapshot!(env.render_ok("range.duration() == t1 - t0"), @"true");
+        insta::assert_snapshot!(env.render_ok("range.duration() != t2 - t0"), @"true");
+        insta::assert_snapshot!(env.render_ok("range.duration() < t2 - t0"), @"true");
+        insta::assert_snapshot!(env.render_ok("range.duration() >= t2 - t0"), @"false");
+        insta::assert_snapshot!(env.render_ok("range.duration().seconds() > 60"), @"true");
+
+        // ago() is printed relative to the current time, but the length can
+        // be formatted without the suffix.
+        insta::assert_snapshot!(env.render_ok("t0.ago().humanize().ends_with('ago')"), @"false");
+        insta::assert_snapshot!(env.render_ok("t0.ago().seconds() > 0"), @"true");
+
+        insta::assert_snapshot!(env.parse_err("t0 - 1"), @"
+         --> 1:6
+          |
+        1 | t0 - 1
+          |      ^
+          |
+          = Expected expression of type `Timestamp`, but actual type is `Integer`
+        ");
+        insta::assert_snapshot!(env.parse_err("1 - t0"), @"
+         --> 1:5
+          |
+        1 | 1 - t0
+          |     ^^
+          |
+          = Expected expression of type `Integer`, but actual type is `Timestamp`
+        ");
+        insta::assert_snapshot!(env.parse_err("t0 + t1"), @"
+         --> 1:1
+          |
+        1 | t0 + t1
+          | ^^
+          |
+          = Expected expression of type `Integer`, but actual type is `Timestamp`
+        ");
+        insta::assert_snapshot!(env.parse_err("(t1 - t0) == 1"), @"
+         --> 1:1
+          |
+        1 | (t1 - t0) == 1
+          | ^------------^
+          |
+          = Cannot compare expressions of type `Duration` and `Integer`
+        ");
+    }
+
     #[test]
     fn test_fill_function() {
         let mut env = TestTemplateEnv::new();
@@ -5430,6 +5841,7 @@ mod tests {
         insta::assert_snapshot!(
             env.render_ok("json(timestamp_range)"),
             @r#"{"start":"1970-01-01T00:00:00Z","end":"1970-01-01T23:00:00-01:00"}"#);
+        insta::assert_snapshot!(env.render_ok("json(timestamp_range.duration())"), @r#""1 day""#);
 
         // AnyList is serializable if the inner type is.
         insta::assert_snapshot!(env.render_ok(r#"json(string_list.map(|s| s))"#), @r#"["foo","bar"]"#);
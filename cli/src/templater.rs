@@ -28,8 +28,11 @@ use std::rc::Rc;
 
 use bstr::BStr;
 use bstr::BString;
+use chrono::TimeDelta;
+use indexmap::IndexMap;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
+use jj_lib::backend::TimestampOutOfRange;
 use jj_lib::config::ConfigValue;
 use jj_lib::file_util;
 use jj_lib::op_store::TimestampRange;
@@ -176,6 +179,72 @@ impl RegexCaptures {
     }
 }
 
+/// Signed span of time, such as the time elapsed between two timestamps.
+#[derive(Clone, Copy, Debug)]
+pub struct Duration {
+    delta: TimeDelta,
+    /// Whether this is the time elapsed until now, which is printed as "2 hours
+    /// ago".
+    relative: bool,
+}
+
+impl Duration {
+    /// Creates duration from `start` to `end`.
+    pub fn between(start: &Timestamp, end: &Timestamp) -> Result<Self, TimestampOutOfRange> {
+        let delta = time_util::duration_between(start, end)?;
+        Ok(Self {
+            delta,
+            relative: false,
+        })
+    }
+
+    /// Creates duration from `timestamp` to `now`, which will be printed
+    /// relative to the current time.
+    pub fn ago(timestamp: &Timestamp, now: &Timestamp) -> Result<Self, TimestampOutOfRange> {
+        let delta = time_util::duration_between(timestamp, now)?;
+        Ok(Self {
+            delta,
+            relative: true,
+        })
+    }
+
+    pub fn delta(&self) -> TimeDelta {
+        self.delta
+    }
+
+    /// Formats the length of this duration in human-readable form.
+    pub fn humanize(&self) -> String {
+        time_util::format_duration(self.delta)
+    }
+}
+
+impl Duration {
+    fn to_display_string(self) -> String {
+        if self.relative {
+            time_util::format_duration_ago(self.delta)
+        } else {
+            self.humanize()
+        }
+    }
+}
+
+impl Template for Duration {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        write!(formatter, "{}", self.to_display_string())
+    }
+}
+
+// Serialized as printed, which is how `Timestamp::ago()` and
+// `TimestampRange::duration()` were serialized when they returned strings.
+impl serde::Serialize for Duration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_display_string())
+    }
+}
+
 impl Template for String {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{self}")
@@ -218,6 +287,15 @@ impl Template for Vec<String> {
     }
 }
 
+impl<V: Template> Template for TemplateMap<V> {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        format_joined_with(formatter, self, "\n", |formatter, (key, value)| {
+            write!(formatter, "{key}: ")?;
+            value.format(formatter)
+        })
+    }
+}
+
 impl Template for bool {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         let repr = if *self { "true" } else { "false" };
@@ -601,6 +679,9 @@ pub trait AnyTemplateProperty<'a> {
 }
 pub type BoxedAnyProperty<'a> = Box<dyn AnyTemplateProperty<'a> + 'a>;
 
+/// Map of string keys to values, preserving insertion order.
+pub type TemplateMap<V> = IndexMap<String, V>;
+
 /// Adapter that wraps literal value in `TemplateProperty`.
 pub struct Literal<O>(pub O);
 
@@ -1,5 +1,6 @@
 use std::sync::LazyLock;
 
+use chrono::TimeDelta;
 use chrono::format::StrftimeItems;
 use jj_lib::backend::Timestamp;
 use jj_lib::backend::TimestampOutOfRange;
@@ -56,15 +57,49 @@ pub fn format_absolute_timestamp_with(
     Ok(datetime.format_with_items(format.items.iter()).to_string())
 }
 
-pub fn format_duration(
+/// Returns the signed duration from `from` to `to`.
+pub fn duration_between(
     from: &Timestamp,
     to: &Timestamp,
-    format: &timeago::Formatter,
-) -> Result<String, TimestampOutOfRange> {
-    let duration = to
-        .to_datetime()?
-        .signed_duration_since(from.to_datetime()?)
+) -> Result<TimeDelta, TimestampOutOfRange> {
+    Ok(to.to_datetime()?.signed_duration_since(from.to_datetime()?))
+}
+
+/// Formats the length of the `duration` in human-readable form such as
+/// "2 hours". Negative duration is prefixed with "-".
+pub fn format_duration(duration: TimeDelta) -> String {
+    let mut f = timeago::Formatter::new();
+    f.min_unit(timeago::TimeUnit::Microseconds).ago("");
+    let formatted = f.convert(abs_std_duration(duration));
+    if formatted == "now" {
+        "less than a microsecond".to_owned()
+    } else if duration < TimeDelta::zero() {
+        format!("-{formatted}")
+    } else {
+        formatted
+    }
+}
+
+/// Formats the `duration` elapsed since an event in relative form such as "2
+/// hours ago". Negative duration is formatted as "in 2 hours".
+pub fn format_duration_ago(duration: TimeDelta) -> String {
+    let mut f = timeago::Formatter::new();
+    if duration < TimeDelta::zero() {
+        f.ago("");
+        let formatted = f.convert(abs_std_duration(duration));
+        if formatted == "now" {
+            formatted
+        } else {
+            format!("in {formatted}")
+        }
+    } else {
+        f.convert(abs_std_duration(duration))
+    }
+}
+
+fn abs_std_duration(duration: TimeDelta) -> std::time::Duration {
+    duration
+        .abs()
         .to_std()
-        .map_err(|_: chrono::OutOfRangeError| TimestampOutOfRange)?;
-    Ok(format.convert(duration))
+        .expect("absolute duration should be non-negative")
 }
@@ -214,6 +214,7 @@ impl Write for UiStderr<'_> {
 
 pub struct Ui {
     quiet: bool,
+    output_format: OutputFormat,
     pager: PagerConfig,
     progress_indicator: bool,
     formatter_factory: FormatterFactory,
@@ -227,6 +228,7 @@ pub enum ColorChoice {
     Never,
     Debug,
     Auto,
+    Html,
 }
 
 impl fmt::Display for ColorChoice {
@@ -236,6 +238,27 @@ impl fmt::Display for ColorChoice {
             Self::Never => "never",
             Self::Debug => "debug",
             Self::Auto => "auto",
+            Self::Html => "html",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Format of the primary command output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human-readable text
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Text => "text",
+            Self::Json => "json",
         };
         write!(f, "{s}")
     }
@@ -245,12 +268,17 @@ fn prepare_formatter_factory(
     config: &StackedConfig,
     stdout: &Stdout,
 ) -> Result<FormatterFactory, ConfigGetError> {
+    if config.get::<OutputFormat>("ui.output-format")? == OutputFormat::Json {
+        // JSON lines must not be decorated by labels.
+        return Ok(FormatterFactory::plain_text());
+    }
     let terminal = stdout.is_terminal();
     let (color, debug) = match config.get("ui.color")? {
         ColorChoice::Always => (true, false),
         ColorChoice::Never => (false, false),
         ColorChoice::Debug => (true, true),
         ColorChoice::Auto => (terminal, false),
+        ColorChoice::Html => return FormatterFactory::html(config),
     };
     if color {
         FormatterFactory::color(config, debug)
@@ -344,6 +372,7 @@ impl Ui {
     pub fn null() -> Self {
         Self {
             quiet: true,
+            output_format: OutputFormat::Text,
             pager: PagerConfig::Disabled,
             progress_indicator: false,
             formatter_factory: FormatterFactory::plain_text(),
@@ -355,6 +384,7 @@ impl Ui {
         let formatter_factory = prepare_formatter_factory(config, &io::stdout())?;
         Ok(Self {
             quiet: config.get("ui.quiet")?,
+            output_format: config.get("ui.output-format")?,
             formatter_factory,
             pager: PagerConfig::from_config(config)?,
             progress_indicator: config.get("ui.progress-indicator")?,
@@ -364,6 +394,7 @@ impl Ui {
 
     pub fn reset(&mut self, config: &StackedConfig) -> Result<(), CommandError> {
         self.quiet = config.get("ui.quiet")?;
+        self.output_format = config.get("ui.output-format")?;
         self.pager = PagerConfig::from_config(config)?;
         self.progress_indicator = config.get("ui.progress-indicator")?;
         self.formatter_factory = prepare_formatter_factory(config, &io::stdout())?;
@@ -414,8 +445,14 @@ impl Ui {
         }
     }
 
+    /// Whether the output may contain ANSI color escape sequences.
     pub fn color(&self) -> bool {
-        self.formatter_factory.maybe_color()
+        self.formatter_factory.is_ansi_color()
+    }
+
+    /// Format of the primary command output.
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
     }
 
     pub fn new_formatter<'output, W: Write + 'output>(
@@ -23,6 +23,7 @@ mod test_completion;
 mod test_concurrent_operations;
 mod test_config_command;
 mod test_config_schema;
+mod test_converge_command;
 mod test_copy_detection;
 mod test_debug_command;
 mod test_debug_init_simple_command;
@@ -41,6 +42,8 @@ mod test_file_track_untrack_commands;
 mod test_fileset_output;
 mod test_fix_command;
 mod test_generate_md_cli_help;
+mod test_gerrit_download;
+mod test_gerrit_fetch;
 mod test_gerrit_upload;
 mod test_git_clone;
 mod test_git_colocated;
@@ -55,6 +58,7 @@ mod test_git_root;
 mod test_gitignores;
 mod test_global_opts;
 mod test_help_command;
+mod test_hooks;
 mod test_identical_commits;
 mod test_immutable_commits;
 mod test_interdiff_command;
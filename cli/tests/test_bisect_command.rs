@@ -588,6 +588,171 @@ fn test_bisect_run_jj_command() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_bisect_manual() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"templates.commit_summary = 'coalesce(description.first_line(), "(no description)")'"#,
+    );
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+    create_commit(&work_dir, "d", &["c"]);
+    create_commit(&work_dir, "e", &["d"]);
+    create_commit(&work_dir, "f", &["e"]);
+
+    let output = work_dir.run_jj(["bisect", "good"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let start_op_id = work_dir.current_operation_id();
+    let output = work_dir.run_jj(["bisect", "start", "--range=.."]);
+    insta::assert_snapshot!(output, @"
+    Bisecting: 5 revisions left to test after this (roughly 3 steps)
+    Now evaluating: c
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: (no description)
+    Parent commit (@-)      : c
+    Added 0 files, modified 0 files, removed 3 files
+    Hint: Test the revision and mark it with `jj bisect good`, `jj bisect bad`, or `jj bisect skip`.
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "start", "--range=.."]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: A bisection is already in progress
+    Hint: Use `jj bisect reset` to end it first.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "bad"]);
+    insta::assert_snapshot!(output, @"
+    Bisecting: 2 revisions left to test after this (roughly 2 steps)
+    Now evaluating: a
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: (no description)
+    Parent commit (@-)      : a
+    Added 0 files, modified 0 files, removed 2 files
+    Hint: Test the revision and mark it with `jj bisect good`, `jj bisect bad`, or `jj bisect skip`.
+    [EOF]
+    ");
+
+    // The heads of the range are assumed to be bad
+    let output = work_dir.run_jj(["bisect", "good", "f"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Commit 8b67af288466 is already marked as bad
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "log"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(|s| s.replace(&start_op_id, "$START_OP")), @"
+    # start operation: $START_OP
+    jj bisect start --range=..
+    jj bisect bad dffaa0d4daccf6cee70bac3498fae3b3fd5d6b5b
+    [EOF]
+    ");
+    let log = work_dir
+        .run_jj(["bisect", "log"])
+        .success()
+        .stdout
+        .into_raw();
+
+    let output = work_dir.run_jj(["bisect", "bad"]);
+    insta::assert_snapshot!(output, @"
+    The first bad revision is: a
+    [EOF]
+    ------- stderr -------
+    Hint: Use `jj bisect reset` to end the bisection session.
+    [EOF]
+    ");
+
+    // The original working-copy commit is restored
+    let output = work_dir.run_jj(["bisect", "reset"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Working copy  (@) now at: f
+    Parent commit (@-)      : e
+    Added 5 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bisect", "log"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The session can be restored from the log. The range is evaluated at the
+    // operation the session was started at, so new commits aren't included.
+    work_dir.run_jj(["new"]).success();
+    let log_path = test_env.env_root().join("bisect.log");
+    let (_, log_without_op) = log.split_once('\n').unwrap();
+    std::fs::write(&log_path, log_without_op)?;
+    let output = work_dir.run_jj(["bisect", "replay", log_path.to_str().unwrap()]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: The bisection log has no `# start operation:` line
+    [EOF]
+    [exit status: 1]
+    ");
+    std::fs::write(&log_path, log)?;
+    let output = work_dir.run_jj(["bisect", "replay", log_path.to_str().unwrap()]);
+    insta::assert_snapshot!(output, @"
+    Bisecting: 2 revisions left to test after this (roughly 2 steps)
+    Now evaluating: a
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: (no description)
+    Parent commit (@-)      : a
+    Added 0 files, modified 0 files, removed 5 files
+    Hint: Test the revision and mark it with `jj bisect good`, `jj bisect bad`, or `jj bisect skip`.
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_bisect_manual_skip() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"templates.commit_summary = 'coalesce(description.first_line(), "(no description)")'"#,
+    );
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+
+    work_dir
+        .run_jj(["bisect", "start", "--range=b::"])
+        .success();
+    let output = work_dir.run_jj(["bisect", "skip"]);
+    insta::assert_snapshot!(output, @"
+    The first bad revision is: c
+    [EOF]
+    ------- stderr -------
+    Hint: Use `jj bisect reset` to end the bisection session.
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"separate(" ",
@@ -1399,6 +1399,32 @@ fn test_log_diff_predefined_formats() -> TestResult {
     * total_added=0 total_removed=0
     [EOF]
     ");
+
+    // custom diff hunks template
+    let template = indoc! {r#"
+        diff.files().map(|e| e.hunks(0).map(|h| concat(
+          e.path() ++ " @@ -" ++ h.left_start() ++ "," ++ h.left_count()
+            ++ " +" ++ h.right_start() ++ "," ++ h.right_count() ++ " @@\n",
+          h.lines().map(|l| l.kind() ++ ": " ++ l.content()).join(""),
+        )).join("")).join("")
+    "#};
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    file1 @@ -2,0 +3,1 @@
+    added: c
+    file2 @@ -1,1 +1,2 @@
+    removed: a
+    added: b
+    added: c
+    [EOF]
+    ");
+
+    // hunks() with default context
+    let template = r#"
+    diff.files().map(|e| e.path() ++ "=" ++ e.hunks().map(|h| h.lines().len()).join(",")).join(" ")
+    "#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(output, @"file1=3 file2=3 rename-target=[EOF]");
     Ok(())
 }
 
@@ -235,6 +235,7 @@ fn test_bookmark_names() {
     --color	When to colorize output
     --quiet	Silence non-primary command output
     --no-pager	Disable the pager
+    --output	Format of the primary command output
     --config	Additional configuration options (can be repeated)
     --config-file	Additional configuration files (can be repeated)
     [EOF]
@@ -728,6 +729,7 @@ fn test_command_completion_short_name() {
     --color	When to colorize output
     --quiet	Silence non-primary command output
     --no-pager	Disable the pager
+    --output	Format of the primary command output
     --config	Additional configuration options (can be repeated)
     --config-file	Additional configuration files (can be repeated)
     --help	Print help (see more with '--help')
@@ -0,0 +1,215 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+const LOG_TEMPLATE: &str =
+    r#"separate(" ", description.first_line(), author.name(), if(divergent, "!divergent!"))"#;
+
+fn set_up(test_env: &TestEnvironment) {
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"templates.commit_summary = 'coalesce(description.first_line(), "(no description)")'"#,
+    );
+}
+
+#[test]
+fn test_converge_no_divergence() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["converge"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No divergent changes found.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_converge_trivial_merge() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "original"]).success();
+    work_dir
+        .run_jj(["describe", "-m", "new description"])
+        .success();
+    work_dir
+        .run_jj([
+            "metaedit",
+            "--author",
+            "Other <other@example.com>",
+            "--at-operation",
+            "@-",
+        ])
+        .success();
+    let output = work_dir.run_jj(["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(output, @"
+    @  new description Test User !divergent!
+    │ ○  original Other !divergent!
+    ├─╯
+    ◆
+    [EOF]
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    [EOF]
+    ");
+
+    // One side changed the description, the other side changed the author, so
+    // both can be merged without asking.
+    let output = work_dir.run_jj(["converge", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Divergent change qpvuntsmwlqt (2 commits):
+      original
+      new description
+    Proposed solution:
+      Author: Other <other@example.com>
+      Description: new description
+      Parent: (no description)
+      Content: merged
+    [EOF]
+    ");
+
+    work_dir.run_jj(["converge"]).success();
+    let output = work_dir.run_jj(["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(output, @"
+    @  new description Other
+    ◆
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_converge_conflicting_authors() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "original"]).success();
+    work_dir
+        .run_jj(["metaedit", "--author", "A <a@example.com>"])
+        .success();
+    work_dir
+        .run_jj([
+            "metaedit",
+            "--author",
+            "B <b@example.com>",
+            "--at-operation",
+            "@-",
+        ])
+        .success();
+
+    let output = work_dir.run_jj(["converge", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    Divergent change qpvuntsmwlqt (2 commits):
+      original
+      original
+    Proposed solution:
+      Author: (unresolved, would prompt)
+      Description: original
+      Parent: (no description)
+      Content: merged
+    [EOF]
+    ");
+
+    // The author can't be chosen without a terminal
+    let output = work_dir.run_jj(["converge"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: The divergent commits of change qpvuntsmwlqt have different authors.
+    Hint: Run this command in an interactive terminal, or rewrite the divergent commits so that they agree.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_converge_conflicting_descriptions() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "original"]).success();
+    work_dir
+        .run_jj(["describe", "-m", "description A"])
+        .success();
+    work_dir
+        .run_jj(["describe", "-m", "description B", "--at-operation", "@-"])
+        .success();
+
+    let output = work_dir.run_jj(["converge", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    Divergent change qpvuntsmwlqt (2 commits):
+      description B
+      description A
+    Proposed solution:
+      Author: Test User <test.user@example.com>
+      Description: (unresolved, would open editor)
+      Parent: (no description)
+      Content: merged
+    [EOF]
+    ");
+
+    // The editor gets one side per divergent commit, separated by the base
+    std::fs::write(&edit_script, "dump editor0\0write\nresolved description").unwrap();
+    let output = work_dir.run_jj(["converge"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Converged 2 divergent commits into resolved description
+    Working copy  (@) now at: resolved description
+    Parent commit (@-)      : (no description)
+    [EOF]
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r#"
+    JJ: The divergent commits of change qpvuntsmwlqt have conflicting descriptions.
+    JJ: Resolve the conflict below.
+    <<<<<<< conflict 1 of 1
+    %%%%%%% diff from: base: qpvuntsm 97604bbe "original"
+    \\\\\\\        to: divergent commit: qpvuntsm 33d8d337 "description B"
+    -original
+    +description B
+    +++++++ divergent commit: qpvuntsm 4fbd456d "description A"
+    description A
+    >>>>>>> conflict 1 of 1 ends
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    let output = work_dir.run_jj(["log", "-T", LOG_TEMPLATE]);
+    insta::assert_snapshot!(output, @"
+    @  resolved description Test User
+    ◆
+    [EOF]
+    ");
+
+    // Conflict markers left in the description are rejected
+    work_dir.run_jj(["undo"]).success();
+    std::fs::write(&edit_script, "").unwrap();
+    let output = work_dir.run_jj(["converge"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: The description still contains conflict markers
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -34,6 +34,116 @@ fn test_debug_fileset() {
     [EOF]
     ");
 
+    let output = work_dir.run_jj(["debug", "fileset", "--optimized", "a | a/b | ~(~c)"]);
+    assert_snapshot!(output, @r#"
+    -- Parsed:
+    UnionAll(
+        [
+            Pattern(
+                PrefixPath(
+                    "a",
+                ),
+            ),
+            Pattern(
+                PrefixPath(
+                    "a/b",
+                ),
+            ),
+            Difference(
+                All,
+                Difference(
+                    All,
+                    Pattern(
+                        PrefixPath(
+                            "c",
+                        ),
+                    ),
+                ),
+            ),
+        ],
+    )
+
+    -- Optimized:
+    UnionAll(
+        [
+            Pattern(
+                PrefixPath(
+                    "a",
+                ),
+            ),
+            Pattern(
+                PrefixPath(
+                    "c",
+                ),
+            ),
+        ],
+    )
+
+    -- Matcher:
+    PrefixMatcher {
+        tree: Dir {
+            "a": Prefix {},
+            "c": Prefix {},
+        },
+    }
+    [EOF]
+    "#);
+
+    // Predicates are resolved against trees, so there's no matcher
+    let output = work_dir.run_jj(["debug", "fileset", "executable()"]);
+    assert_snapshot!(output, @"
+    -- Parsed:
+    Predicate(
+        Executable,
+    )
+
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["debug", "fileset", "--optimized", "executable() | a | a/b"]);
+    assert_snapshot!(output, @r#"
+    -- Parsed:
+    UnionAll(
+        [
+            Predicate(
+                Executable,
+            ),
+            Resolved(
+                Pattern(
+                    PrefixPath(
+                        "a",
+                    ),
+                ),
+            ),
+            Resolved(
+                Pattern(
+                    PrefixPath(
+                        "a/b",
+                    ),
+                ),
+            ),
+        ],
+    )
+
+    -- Optimized:
+    UnionAll(
+        [
+            Predicate(
+                Executable,
+            ),
+            Resolved(
+                Pattern(
+                    PrefixPath(
+                        "a",
+                    ),
+                ),
+            ),
+        ],
+    )
+
+    [EOF]
+    "#);
+
     let output = work_dir.run_jj(["debug", "fileset", "cwd:.."]);
     assert_snapshot!(output.normalize_backslash(), @r#"
     ------- stderr -------
@@ -253,6 +363,54 @@ fn test_debug_reindex() {
     ");
 }
 
+#[test]
+fn test_debug_migrate_op_store() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new"]).success();
+    work_dir.run_jj(["new"]).success();
+    let template = r#"if(!root, description ++ "\n")"#;
+
+    let output = work_dir.run_jj(["debug", "migrate-op-store", "packed_op_store"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Migrated 3 operations to packed_op_store.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["debug", "migrate-op-store", "packed_op_store"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    new empty commit
+    new empty commit
+    new empty commit
+    add workspace 'default'
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["debug", "migrate-op-store", "simple_op_store"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Migrated 4 operations to simple_op_store.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    new empty commit
+    new empty commit
+    new empty commit
+    add workspace 'default'
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_debug_stacked_table() {
     let test_env = TestEnvironment::default();
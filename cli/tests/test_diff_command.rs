@@ -510,6 +510,14 @@ fn test_diff_file_mode() {
     [EOF]
     ");
 
+    // Files are matched if they're executable on either side
+    let output = work_dir.run_jj(["diff", "-r@", "--summary", "executable()"]);
+    insta::assert_snapshot!(output, @"
+    D file3
+    D file4
+    [EOF]
+    ");
+
     let output = work_dir.run_jj(["diff", "-r@--", "--git"]);
     insta::assert_snapshot!(output, @"
     diff --git a/file1 b/file1
@@ -91,8 +91,7 @@ fn test_evolog_with_or_without_diff() {
     │  Modified regular file file1:
     │     1    1: foo
     │          2: bar
-    │  Added regular file file2:
-    │          1: foo
+    │  Modified regular file file2 (file1 => file2):
     ○  rlvkpnrz/3 test.user@example.com 2001-02-03 08:05:08 b955b72e (hidden)
        (empty) my description
        -- operation c5d06fd4ff51 new empty commit
@@ -188,13 +187,9 @@ fn test_evolog_with_or_without_diff() {
     @@ -1,1 +1,2 @@
      foo
     +bar
-    diff --git a/file2 b/file2
-    new file mode 100644
-    index 0000000000..257cc5642c
-    --- /dev/null
-    +++ b/file2
-    @@ -0,0 +1,1 @@
-    +foo
+    diff --git a/file1 b/file2
+    copy from file1
+    copy to file2
     rlvkpnrz/3 test.user@example.com 2001-02-03 08:05:08 b955b72e (hidden)
     (empty) my description
     -- operation c5d06fd4ff51 new empty commit
@@ -828,3 +823,26 @@ fn test_evolog_template_predecessors_and_inter_diff() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_evolog_template_inter_diff_with_rename() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\nb\nc\n");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "a\nb\nc\n");
+    work_dir.run_jj(["debug", "snapshot"]).success();
+
+    // The rename between the predecessor and the commit is detected.
+    let output = work_dir
+        .run_jj(["evolog", "--no-graph", "-T=inter_diff.summary()"])
+        .success();
+    insta::assert_snapshot!(output, @"
+    R {file1 => file2}
+    A file1
+    [EOF]
+    ");
+}
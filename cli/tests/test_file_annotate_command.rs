@@ -305,3 +305,96 @@ fn test_annotate_with_template() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_annotate_renamed_and_ignored_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "a\nb\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    work_dir.remove_file("file.txt");
+    work_dir.write_file("renamed.txt", "a\nb\n");
+    work_dir.run_jj(["commit", "-m=rename"]).success();
+    work_dir.write_file("renamed.txt", "A\nb\nc\n");
+    work_dir.run_jj(["describe", "-m=reformat"]).success();
+
+    let template = r#"commit.description().first_line() ++ ": " ++ content"#;
+    let output = work_dir.run_jj(["file", "annotate", "renamed.txt", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    reformat: A
+    initial: b
+    reformat: c
+    [EOF]
+    ");
+
+    // Modified lines are attributed to the parent of the ignored revision
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "renamed.txt",
+        "-T",
+        template,
+        "--config=annotate.ignore-revisions='subject(reformat)'",
+    ]);
+    insta::assert_snapshot!(output, @"
+    initial: A
+    initial: b
+    reformat: c
+    [EOF]
+    ");
+
+    // Lines originating outside of the domain are attributed to the root commit
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "renamed.txt",
+        "-T",
+        template,
+        "--domain=~subject(initial)",
+    ]);
+    insta::assert_snapshot!(output, @"
+    reformat: A
+    : b
+    reformat: c
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_annotate_line_range() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "1\n2\n3\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    work_dir.write_file("file.txt", "1\n2b\n3\n4\n");
+    work_dir.run_jj(["describe", "-m=next"]).success();
+
+    let template = r#"commit.description().first_line() ++ " " ++ line_number ++ ": " ++ content"#;
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-T", template, "-L2,3"]);
+    insta::assert_snapshot!(output, @"
+    next 2: 2b
+    initial 3: 3
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-T", template, "-L3,100"]);
+    insta::assert_snapshot!(output, @"
+    initial 3: 3
+    next 4: 4
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-L3,2"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    error: invalid value '3,2' for '--lines <START,END>': Invalid line range "3,2": lines are 1-based, and END must not be less than START
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    "#);
+}
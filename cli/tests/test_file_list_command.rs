@@ -92,4 +92,154 @@ fn test_file_list() {
     Warning: No matching entries for paths: file3
     [EOF]
     ");
+
+    // Can filter by file metadata
+    let output = work_dir.run_jj(["file", "list", "executable()"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    conflict-exec-file
+    exec-file
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "conflicted() & ~executable()"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    conflict-file
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "-r=first_parent(@)", "conflicted()"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"");
+}
+
+#[test]
+fn test_file_list_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("small", "small\n");
+    work_dir.write_file("large", "x".repeat(2048));
+    work_dir.write_file("binary", b"\0\x01\x02");
+
+    let output = work_dir.run_jj(["file", "list", "size(>1K)"]);
+    insta::assert_snapshot!(output, @"
+    large
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "size(<=6)"]);
+    insta::assert_snapshot!(output, @"
+    binary
+    small
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "binary()"]);
+    insta::assert_snapshot!(output, @"
+    binary
+    [EOF]
+    ");
+
+    // Invalid size comparison
+    let output = work_dir.run_jj(["file", "list", "size(1K)"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to parse fileset: Invalid size comparison: Expected a comparison such as `>1M`
+    Caused by:  --> 1:6
+      |
+    1 | size(1K)
+      |      ^^
+      |
+      = Invalid size comparison: Expected a comparison such as `>1M`
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Predicates aren't available where there's no tree to look them up in
+    let output = work_dir.run_jj(["log", "-r", "files(executable())"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to parse revset: In fileset expression
+    Caused by:
+    1:  --> 1:7
+      |
+    1 | files(executable())
+      |       ^----------^
+      |
+      = In fileset expression
+    2:  --> 1:1
+      |
+    1 | executable()
+      | ^--------^
+      |
+      = Function `executable` can't be used in this context
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
+#[test]
+fn test_file_list_patterns() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("BUILD.bazel", "");
+    work_dir.write_file("README.md", "");
+    work_dir.write_file("lib/BUILD.bazel", "");
+    work_dir.write_file("lib/lib.rs", "");
+    work_dir.write_file("lib/src/BUILD.bazel", "");
+    work_dir.write_file("lib/src/main.rs", "");
+    let sub_dir = work_dir.dir("lib");
+
+    let output = work_dir.run_jj(["file", "list", "name:BUILD.bazel"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    BUILD.bazel
+    lib/BUILD.bazel
+    lib/src/BUILD.bazel
+    [EOF]
+    ");
+    let output = sub_dir.run_jj(["file", "list", "name:BUILD.bazel"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    BUILD.bazel
+    src/BUILD.bazel
+    [EOF]
+    ");
+    let output = sub_dir.run_jj(["file", "list", "root-name:BUILD.bazel"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    ../BUILD.bazel
+    BUILD.bazel
+    src/BUILD.bazel
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "list", "files-in:lib"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    lib/BUILD.bazel
+    lib/lib.rs
+    [EOF]
+    ");
+    let output = sub_dir.run_jj(["file", "list", "files-in:."]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    BUILD.bazel
+    lib.rs
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "list", r"regex:'\.(rs|md)$'"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    README.md
+    lib/lib.rs
+    lib/src/main.rs
+    [EOF]
+    ");
+    // The pattern is matched against cwd-relative paths
+    let output = sub_dir.run_jj(["file", "list", "regex:'^src/'"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    src/BUILD.bazel
+    src/main.rs
+    [EOF]
+    ");
+    let output = sub_dir.run_jj(["file", "list", "root-regex:'^[A-Z]'"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    ../BUILD.bazel
+    ../README.md
+    [EOF]
+    ");
 }
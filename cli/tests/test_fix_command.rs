@@ -996,6 +996,27 @@ fn test_fix_some_paths() {
     insta::assert_snapshot!(output, @"bar[EOF]");
 }
 
+#[test]
+fn test_fix_paths_matching_predicates() {
+    let mut test_env = TestEnvironment::default();
+    set_up_fake_formatter(&mut test_env, &["--uppercase"]);
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("small", "foo");
+    work_dir.write_file("large", "bar".repeat(1000));
+    work_dir.write_file("binary", "baz\0");
+
+    work_dir
+        .run_jj(["fix", "-s", "@", "size(<1K) & ~binary()"])
+        .success();
+    let output = work_dir.run_jj(["file", "show", "small"]);
+    insta::assert_snapshot!(output, @"FOO[EOF]");
+    let output = work_dir.run_jj(["file", "show", "large"]);
+    assert_eq!(output.stdout.raw(), "bar".repeat(1000));
+    let output = work_dir.run_jj(["file", "show", "binary"]);
+    assert_eq!(output.stdout.raw(), "baz\0");
+}
+
 #[test]
 fn test_fix_cyclic() {
     let mut test_env = TestEnvironment::default();
@@ -0,0 +1,211 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::git;
+use testutils::is_external_tool_installed;
+
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::create_commit;
+use crate::common::start_fake_gerrit_server;
+
+fn get_change_id_hex(work_dir: &TestWorkDir, rev: &str) -> String {
+    work_dir
+        .run_jj(["log", "--no-graph", "-r", rev, "-T", "change_id.hex()"])
+        .success()
+        .stdout
+        .into_raw()
+}
+
+#[test]
+fn test_gerrit_download() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    // Set up a Gerrit remote where a colleague has uploaded a new patchset of
+    // our change "b", and a new change "d" on top of it.
+    let remote_repo = git::init_bare(test_env.env_root().join("gerrit"));
+    let base = git::add_commit(
+        &remote_repo,
+        "refs/heads/main",
+        "base",
+        b"base\n",
+        "base",
+        &[],
+    );
+    let b_v2 = git::add_commit(
+        &remote_repo,
+        "refs/changes/45/12345/1",
+        "b",
+        b"b v2\n",
+        "b v2\n\nChange-Id: I1111111111111111111111111111111111111111\n",
+        &[base.commit_id],
+    );
+    git::add_commit(
+        &remote_repo,
+        "refs/changes/46/12346/1",
+        "d",
+        b"d\n",
+        "d\n\nChange-Id: I4444444444444444444444444444444444444444\n",
+        &[b_v2.commit_id],
+    );
+    work_dir
+        .run_jj(["git", "remote", "add", "gerrit", "../gerrit"])
+        .success();
+    work_dir.run_jj(["git", "fetch"]).success();
+
+    create_commit(&work_dir, "b", &["main@gerrit"]);
+    create_commit(&work_dir, "c", &["b"]);
+    work_dir
+        .run_jj([
+            "describe",
+            "-r=b",
+            "-m=b\n\nChange-Id: I1111111111111111111111111111111111111111",
+        ])
+        .success();
+    let change_id_b = get_change_id_hex(&work_dir, "b");
+
+    // The current patchset can't be looked up without the REST API
+    let output = work_dir.run_jj(["gerrit", "download", "12345"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No Gerrit URL configured
+    Hint: Set `gerrit.url` to the URL of your Gerrit server
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["gerrit", "download", "12345/9"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Patchset 12345/9 not found on remote 'gerrit'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The downloaded patchset becomes a new version of the local change
+    let output = work_dir.run_jj(["gerrit", "download", "12345/1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Downloaded change 12345 patchset 1: b v2
+    Rebased 1 descendant commits.
+    Working copy  (@) now at: c
+    Parent commit (@-)      : b v2
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    assert_eq!(get_change_id_hex(&work_dir, "b"), change_id_b);
+    insta::assert_snapshot!(work_dir.read_file("b"), @"b v2");
+
+    // Commits which were already downloaded are reused
+    let output = work_dir.run_jj(["gerrit", "download", "12346/1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Downloaded change 12346 patchset 1: d
+    [EOF]
+    ");
+    let template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=mutable()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    d
+    c c
+    b v2 b
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_gerrit_download_current_patchset() {
+    if !is_external_tool_installed("curl") {
+        eprintln!("Skipping because curl is not installed");
+        return;
+    }
+
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    let remote_repo = git::init_bare(test_env.env_root().join("gerrit"));
+    let base = git::add_commit(
+        &remote_repo,
+        "refs/heads/main",
+        "base",
+        b"base\n",
+        "base",
+        &[],
+    );
+    for patchset in [1, 2] {
+        git::add_commit(
+            &remote_repo,
+            &format!("refs/changes/45/12345/{patchset}"),
+            "b",
+            format!("b v{patchset}\n").as_bytes(),
+            &format!("b v{patchset}\n\nChange-Id: I1111111111111111111111111111111111111111\n"),
+            &[base.commit_id],
+        );
+    }
+    work_dir
+        .run_jj(["git", "remote", "add", "gerrit", "../gerrit"])
+        .success();
+
+    // Only change 12345 is known to the REST API
+    let url = start_fake_gerrit_server(|path| {
+        if path.contains("q=change%3A12345&") {
+            r#")]}'
+[
+  {
+    "change_id": "I1111111111111111111111111111111111111111",
+    "project": "repo",
+    "_number": 12345,
+    "status": "NEW",
+    "current_revision": "bbb",
+    "revisions": {"bbb": {"_number": 2}}
+  }
+]
+"#
+            .to_owned()
+        } else {
+            ")]}'\n[]\n".to_owned()
+        }
+    });
+    test_env.add_config(format!("gerrit.url = '{url}'"));
+
+    let output = work_dir.run_jj(["gerrit", "download", "99999"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Change 99999 not found on Gerrit
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The current patchset is downloaded if no patchset is specified
+    let output = work_dir.run_jj(["gerrit", "download", "12345"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Downloaded change 12345 patchset 2: b v2
+    [EOF]
+    ");
+
+    // The ref used to fetch the patchset is removed after import
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    assert!(
+        git_repo
+            .try_find_reference("refs/jj/fetched-commit")
+            .unwrap()
+            .is_none()
+    );
+}
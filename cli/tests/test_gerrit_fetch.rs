@@ -0,0 +1,225 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::is_external_tool_installed;
+
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::create_commit;
+use crate::common::start_fake_gerrit_server;
+
+fn get_change_id_hex(work_dir: &TestWorkDir, rev: &str) -> String {
+    work_dir
+        .run_jj([
+            "log",
+            "--no-graph",
+            "-r",
+            rev,
+            "-T",
+            "change_id.normal_hex()",
+        ])
+        .success()
+        .stdout
+        .into_raw()
+}
+
+#[test]
+fn test_gerrit_fetch_no_url() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["gerrit", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No Gerrit URL configured
+    Hint: Set `gerrit.url` to the URL of your Gerrit server
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_gerrit_fetch_and_status() {
+    if !is_external_tool_installed("curl") {
+        eprintln!("Skipping because curl is not installed");
+        return;
+    }
+
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+    work_dir
+        .run_jj([
+            "describe",
+            "-r=b",
+            "-m=b\n\nChange-Id: I2222222222222222222222222222222222222222",
+        ])
+        .success();
+
+    let change_id_a = format!("I{}6a6a6964", get_change_id_hex(&work_dir, "a"));
+    // Gerrit truncates the results, so the changes are returned in two pages.
+    let page_1 = format!(
+        r#")]}}'
+[
+  {{
+    "change_id": "{change_id_a}",
+    "project": "repo",
+    "_number": 101,
+    "status": "NEW",
+    "submittable": true,
+    "current_revision": "aaa",
+    "revisions": {{"aaa": {{"_number": 3}}}},
+    "labels": {{
+      "Code-Review": {{"approved": {{}}, "all": [{{"value": 2}}, {{"value": 0}}]}},
+      "Verified": {{"approved": {{}}, "all": [{{"value": 1}}]}}
+    }},
+    "_more_changes": true
+  }}
+]
+"#
+    );
+    let page_2 = r#")]}'
+[
+  {
+    "change_id": "I2222222222222222222222222222222222222222",
+    "project": "repo",
+    "_number": 102,
+    "status": "NEW",
+    "current_revision": "bbb",
+    "revisions": {"bbb": {"_number": 1}},
+    "labels": {
+      "Code-Review": {"all": [{"value": 1}]},
+      "Verified": {"rejected": {}, "all": [{"value": -1}]}
+    }
+  }
+]
+"#;
+    let url = start_fake_gerrit_server(move |path| {
+        if path.ends_with("&S=0") {
+            page_1.clone()
+        } else {
+            page_2.to_owned()
+        }
+    });
+    test_env.add_config(format!("gerrit.url = '{url}'"));
+
+    // Nothing has been fetched yet
+    let output = work_dir.run_jj(["gerrit", "status"]);
+    insta::assert_snapshot!(output, @"
+    c
+      Not found on Gerrit
+    b
+      Not found on Gerrit
+    a
+      Not found on Gerrit
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["gerrit", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Fetched review status of 2 changes from Gerrit
+    [EOF]
+    ");
+
+    let output = work_dir
+        .run_jj(["gerrit", "status"])
+        .normalize_stdout_with(|s| s.replace(&url, "$URL"));
+    insta::assert_snapshot!(output, @"
+    c
+      Not found on Gerrit
+    b
+      Change 102 (patchset 1): NEW
+      Votes: Code-Review+1 Verified-1
+      $URL/c/repo/+/102
+    a
+      Change 101 (patchset 3): NEW, approved, submittable
+      Votes: Code-Review+2 Verified+1
+      $URL/c/repo/+/101
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["gerrit", "status", "-r=a", "--fetch"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(|s| s.replace(&url, "$URL")), @"
+    a
+      Change 101 (patchset 3): NEW, approved, submittable
+      Votes: Code-Review+2 Verified+1
+      $URL/c/repo/+/101
+    [EOF]
+    ------- stderr -------
+    Fetched review status of 1 changes from Gerrit
+    [EOF]
+    ");
+
+    // Review status in templates
+    let template = r#"
+    description.first_line() ++ " " ++ if(gerrit_review,
+      gerrit_review.number() ++ "/" ++ gerrit_review.patchset() ++ " "
+      ++ gerrit_review.votes().join(","),
+      "-") ++ "\n"
+    "#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=mutable()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    c -
+    b 102/1 Code-Review+1,Verified-1
+    a 101/3 Code-Review+2,Verified+1
+    [EOF]
+    ");
+
+    // Review status in revsets
+    let template = r#"description.first_line() ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=gerrit_changes()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    b
+    a
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=gerrit_approved()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    a
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r=gerrit_submittable()",
+        "-T",
+        template,
+    ]);
+    insta::assert_snapshot!(output, @"
+    a
+    [EOF]
+    ");
+
+    // Corrupt review status is reported
+    std::fs::write(
+        work_dir.root().join(".jj/repo/gerrit/reviews.json"),
+        "not json",
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=gerrit_changes()", "-T", template]);
+    insta::assert_snapshot!(output.normalize_backslash(), @"
+    ------- stderr -------
+    Error: Failed to read Gerrit review status from $TEST_ENV/repo/.jj/repo/gerrit/reviews.json
+    Caused by: expected ident at line 1 column 2
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use indoc::indoc;
+use regex::Regex;
 use testutils::TestResult;
 use testutils::git;
 
@@ -185,6 +186,18 @@ fn test_git_push_current_bookmark() {
     Dry-run requested, not pushing.
     [EOF]
     ");
+    let output = work_dir.run_jj(["--output=json", "git", "push", "--dry-run"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(shorten_commit_ids), @r#"
+    {"type":"bookmark","name":"bookmark2","remote":"origin","action":"move_forward","old_target":"38a204733702","new_target":"88ca14a7d46f"}
+    {"type":"bookmark","name":"my-bookmark","remote":"origin","action":"add","old_target":null,"new_target":"88ca14a7d46f"}
+    [EOF]
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: bookmark2 [move forward from 38a204733702 to 88ca14a7d46f]
+      bookmark: my-bookmark [add to 88ca14a7d46f]
+    Dry-run requested, not pushing.
+    [EOF]
+    "#);
     let output = work_dir.run_jj(["git", "push"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
@@ -2946,3 +2959,8 @@ fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
 fn get_tag_output(work_dir: &TestWorkDir) -> CommandOutput {
     work_dir.run_jj(["tag", "list", "--all-remotes"])
 }
+
+fn shorten_commit_ids(stdout: String) -> String {
+    let commit_id_re = Regex::new(r"\b([0-9a-f]{12})[0-9a-f]{28}\b").unwrap();
+    commit_id_re.replace_all(&stdout, "$1").into_owned()
+}
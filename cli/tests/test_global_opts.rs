@@ -704,7 +704,7 @@ fn test_color_config() {
     insta::assert_snapshot!(output, @"
     ------- stderr -------
     error: invalid value 'foo' for '--color <WHEN>'
-      [possible values: always, never, debug, auto]
+      [possible values: always, never, debug, auto, html]
 
     For more information, try '--help'.
     [EOF]
@@ -754,6 +754,57 @@ fn test_pager_env_config() {
     "#);
 }
 
+#[test]
+fn test_color_html() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["--color=html", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(output, @r#"
+    <span class="log commit node working_copy mutable" style="color: #008000; font-weight: bold">@</span>  <span class="log commit commit_id" style="color: #000080">e8849ae12c709f2321908879bc724fdb2ab8a781</span>
+    <span class="log commit node immutable" style="color: #00ffff; font-weight: bold">◆</span>  <span class="log commit commit_id" style="color: #000080">0000000000000000000000000000000000000000</span>
+    [EOF]
+    "#);
+
+    // Raw output is escaped, and text in the graph isn't escaped twice
+    work_dir
+        .run_jj(["describe", "-m", "<script>alert(1)</script>"])
+        .success();
+    let output = work_dir.run_jj([
+        "--color=html",
+        "log",
+        "-r@",
+        "-T",
+        r#"description.first_line() ++ raw_escape_sequence("<b>x</b>\x1b[1m")"#,
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    <span class="log commit node working_copy mutable" style="color: #008000; font-weight: bold">@</span>  <span class="log commit description first_line">&lt;script&gt;alert(1)&lt;/script&gt;</span><span class="log commit">&lt;b&gt;x&lt;/b&gt;</span>
+    │
+    ~
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_output_json_error() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["--output=json", "log", "-r", "x - y"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    {"type":"error","kind":"user","message":"Failed to parse revset: `-` is not an infix operator","sources":[" --> 1:3\n  |\n1 | x - y\n  |   ^\n  |\n  = `-` is not an infix operator"],"hints":["Did you mean `~` for difference?"]}
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Help is still printed as text
+    let output = work_dir.run_jj(["--output=json", "log", "-h"]);
+    assert!(output.stdout.raw().starts_with("Show revision history"));
+}
+
 #[test]
 fn test_color_ui_messages() {
     let test_env = TestEnvironment::default();
@@ -1325,9 +1376,11 @@ fn test_help() {
           --at-operation <AT_OPERATION>  Operation to load the repo at [aliases: --at-op]
           --debug                        Enable debug logging
           --color <WHEN>                 When to colorize output [possible values: always, never, debug,
-                                         auto]
+                                         auto, html]
           --quiet                        Silence non-primary command output
           --no-pager                     Disable the pager
+          --output <FORMAT>              Format of the primary command output [possible values: text,
+                                         json]
           --config <NAME=VALUE>          Additional configuration options (can be repeated)
           --config-file <PATH>           Additional configuration files (can be repeated)
     [EOF]
@@ -0,0 +1,180 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::formatdoc;
+use testutils::git;
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::to_toml_value;
+
+fn init_test_env() -> (TestEnvironment, String) {
+    let mut test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let fake_formatter = assert_cmd::cargo::cargo_bin("fake-formatter");
+    assert!(fake_formatter.is_file());
+    test_env.add_paths_to_normalize(fake_formatter.clone(), "$FAKE_FORMATTER_PATH");
+    let fake_formatter_path = to_toml_value(fake_formatter.to_str().unwrap()).to_string();
+    (test_env, fake_formatter_path)
+}
+
+fn get_descriptions(work_dir: &TestWorkDir) -> CommandOutput {
+    let template = r#""<" ++ description.first_line() ++ ">\n""#;
+    work_dir.run_jj(["log", "--no-graph", "-r=mutable()", "-T", template])
+}
+
+#[test]
+fn test_pre_describe_hook() {
+    let (test_env, fake_formatter_path) = init_test_env();
+    test_env.add_config(formatdoc! {r#"
+        [hooks.pre-describe.no-wip]
+        command = [{fake_formatter_path}, "--stdout", "checked\n", "--fail"]
+        revisions = "description(glob:'wip*')"
+    "#});
+    let work_dir = test_env.work_dir("repo");
+
+    // A failing hook aborts the command
+    let output = work_dir.run_jj(["describe", "-m", "wip: a"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Running hook `pre-describe.no-wip` on 1 commits
+    checked
+    Error: Hook `pre-describe.no-wip` failed with exit status: 1
+    Hint: Failed revision: wip: a
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_descriptions(&work_dir), @"
+    <>
+    [EOF]
+    ");
+
+    // The hook isn't run on commits which don't match its revisions
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    insta::assert_snapshot!(get_descriptions(&work_dir), @"
+    <a>
+    [EOF]
+    ");
+
+    // Disabled hooks aren't run
+    test_env.add_config("hooks.pre-describe.no-wip.enabled = false");
+    work_dir.run_jj(["describe", "-m", "wip: a"]).success();
+    insta::assert_snapshot!(get_descriptions(&work_dir), @"
+    <wip: a>
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_post_operation_hook() {
+    let (test_env, fake_formatter_path) = init_test_env();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    test_env.add_config(formatdoc! {r#"
+        [hooks.post-operation.check]
+        command = [{fake_formatter_path}, "--stdout", "checked\n", "--fail"]
+    "#});
+
+    // A failing hook only warns since the operation is already committed
+    let output = work_dir.run_jj(["new", "-m", "b"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Working copy  (@) now at: b
+    Parent commit (@-)      : a
+    Running hook `post-operation.check` on 1 commits
+    checked
+    Warning: Hook `post-operation.check` failed with exit status: 1 on revision b
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_descriptions(&work_dir), @"
+    <b>
+    <a>
+    [EOF]
+    ");
+
+    // Errors are also reported as warnings
+    test_env.add_config(formatdoc! {r#"
+        [hooks.post-operation.check]
+        command = {{ env = {{ FOO = "1" }}, command = [{fake_formatter_path}] }}
+    "#});
+    let output = work_dir.run_jj(["new", "-m", "c"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Working copy  (@) now at: c
+    Parent commit (@-)      : b
+    Warning: Failed to run post-operation hooks: `hooks.post-operation.check.command` can't set environment variables
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_descriptions(&work_dir), @"
+    <c>
+    <b>
+    <a>
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_pre_push_hook() {
+    let (test_env, fake_formatter_path) = init_test_env();
+    git::init_bare(test_env.env_root().join("remote"));
+    test_env.add_config(formatdoc! {r#"
+        [hooks.pre-push.check]
+        command = [{fake_formatter_path}, "--stdout", "checked\n", "--fail"]
+    "#});
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a"])
+        .success();
+
+    // A failing hook blocks the push
+    let output = work_dir.run_jj(["git", "push", "--bookmark", "a"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: a [add to 99025a24f7f3]
+    Running hook `pre-push.check` on 1 commits
+    checked
+    Error: Hook `pre-push.check` failed with exit status: 1
+    Hint: Failed revision: a
+    [EOF]
+    [exit status: 1]
+    ");
+    let remote_repo = git::open(test_env.env_root().join("remote"));
+    assert_eq!(remote_repo.references().unwrap().all().unwrap().count(), 0);
+}
+
+#[test]
+fn test_hook_with_env() {
+    let (test_env, fake_formatter_path) = init_test_env();
+    test_env.add_config(formatdoc! {r#"
+        [hooks.pre-describe.check]
+        command = {{ env = {{ FOO = "1" }}, command = [{fake_formatter_path}] }}
+    "#});
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["describe", "-m", "a"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Config error: `hooks.pre-describe.check.command` can't set environment variables
+    For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
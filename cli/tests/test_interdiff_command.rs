@@ -168,6 +168,61 @@ fn test_interdiff_paths() {
     ");
 }
 
+#[test]
+fn test_interdiff_renamed_files() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "1\n2\n3\n");
+    work_dir.write_file("file2", "a\nb\nc\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "base"])
+        .success();
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file3", "foo\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "left"])
+        .success();
+
+    // The new parent renames file1, and the commit itself renames file2 and
+    // modifies the renamed file1
+    work_dir.run_jj(["new", "base"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file1-renamed", "1\n2\n3\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1-renamed", "1\n2\n3\n4\n");
+    work_dir.write_file("file3", "foo\nbar\n");
+    work_dir.remove_file("file2");
+    work_dir.write_file("file2-renamed", "a\nb\nc\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "right"])
+        .success();
+
+    // Only the rename made by the commit is shown
+    let output = work_dir.run_jj(["interdiff", "--from", "left", "--to", "right"]);
+    insta::assert_snapshot!(output, @"
+    Modified regular file file1-renamed:
+       1    1: 1
+       2    2: 2
+       3    3: 3
+            4: 4
+    Modified regular file file2-renamed (file2 => file2-renamed):
+    Modified regular file file3:
+       1    1: foo
+            2: bar
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["interdiff", "--from", "left", "--to", "right", "--summary"]);
+    insta::assert_snapshot!(output, @"
+    M file1-renamed
+    R {file2 => file2-renamed}
+    M file3
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_interdiff_conflicting() {
     let test_env = TestEnvironment::default();
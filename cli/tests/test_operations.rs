@@ -237,6 +237,67 @@ fn test_op_log_limit() {
     ");
 }
 
+#[test]
+fn test_op_log_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file", "a");
+    work_dir.run_jj(["new"]).success();
+    let template = r#"description ++ "\n""#;
+
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        "snapshot()",
+    ]);
+    insta::assert_snapshot!(output, @"
+    snapshot working copy
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template, "-r", "@-.."]);
+    insta::assert_snapshot!(output, @"
+    new empty commit
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        "--no-graph",
+        "-T",
+        template,
+        "-r",
+        "~snapshot()",
+    ]);
+    insta::assert_snapshot!(output, @"
+    new empty commit
+    add workspace 'default'
+
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "-r", "snapshots()"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Failed to parse opset: Function `snapshots` doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | snapshots()
+      | ^-------^
+      |
+      = Function `snapshots` doesn't exist
+    Hint: Did you mean `snapshot`?
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -432,6 +493,29 @@ fn test_op_log_template() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_op_log_json_output() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Graph, template, and op diff are disabled
+    let output = work_dir.run_jj(["--output=json", "op", "log", "-Tid", "--op-diff"]);
+    insta::assert_snapshot!(output, @r#"
+    {"id":"90267f31f90442f630dd8a2b5feaf8cf753dc64324e3d2d46bfd6d93f279a4d7630c2701a06a60ec04ca5c01a1e3f6758c0ab4f1efe6997ae82789328fb77fc9","parents":["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],"time":{"start":"2001-02-03T04:05:07+07:00","end":"2001-02-03T04:05:07+07:00"},"description":"add workspace 'default'","hostname":"host.example.com","username":"test-username","is_snapshot":false,"workspace_name":null,"attributes":{}}
+    {"id":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","parents":[],"time":{"start":"1970-01-01T00:00:00Z","end":"1970-01-01T00:00:00Z"},"description":"","hostname":"","username":"","is_snapshot":false,"workspace_name":null,"attributes":{}}
+    [EOF]
+    "#);
+
+    // Same as the config
+    let output = work_dir.run_jj(["op", "log", "--config=ui.output-format=json", "--reversed"]);
+    insta::assert_snapshot!(output, @r#"
+    {"id":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","parents":[],"time":{"start":"1970-01-01T00:00:00Z","end":"1970-01-01T00:00:00Z"},"description":"","hostname":"","username":"","is_snapshot":false,"workspace_name":null,"attributes":{}}
+    {"id":"90267f31f90442f630dd8a2b5feaf8cf753dc64324e3d2d46bfd6d93f279a4d7630c2701a06a60ec04ca5c01a1e3f6758c0ab4f1efe6997ae82789328fb77fc9","parents":["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],"time":{"start":"2001-02-03T04:05:07+07:00","end":"2001-02-03T04:05:07+07:00"},"description":"add workspace 'default'","hostname":"host.example.com","username":"test-username","is_snapshot":false,"workspace_name":null,"attributes":{}}
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_op_log_builtin_templates() {
     let test_env = TestEnvironment::default();
@@ -749,13 +749,213 @@ fn test_too_many_parents() -> TestResult {
     Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
     Error: Failed to resolve conflicts
     Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
-    Hint: Edit the conflict markers manually to resolve this.
+    Hint: Use `jj resolve --editor` to edit the conflict markers.
     [EOF]
     [exit status: 1]
     "#);
     Ok(())
 }
 
+#[test]
+fn test_resolve_with_editor() -> TestResult {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "base\n")]);
+    create_commit_with_files(&work_dir, "a", &["base"], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &["base"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "c", &["base"], &[("file", "c\n")]);
+    create_commit_with_files(&work_dir, "conflict", &["a", "b", "c"], &[]);
+    create_commit_with_files(&work_dir, "tip", &["conflict"], &[]);
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "conflict"]), @"
+    file    3-sided conflict
+    [EOF]
+    ");
+    let setup_opid = work_dir.current_operation_id();
+
+    // Conflicts with more than 2 sides can be resolved in the text editor,
+    // which is given the conflict markers
+    std::fs::write(
+        &editor_script,
+        ["dump editor0", "write\nresolution\n"].join("\0"),
+    )?;
+    work_dir
+        .run_jj(["resolve", "--editor", "-r", "conflict"])
+        .success();
+    let materialized = std::fs::read_to_string(test_env.env_root().join("editor0"))?;
+    assert!(materialized.starts_with("<<<<<<< conflict 1 of 1\n"));
+    assert!(materialized.ends_with(">>>>>>> conflict 1 of 1 ends\n"));
+    insta::assert_snapshot!(work_dir.run_jj(["file", "show", "-r", "conflict", "file"]), @"
+    resolution
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "conflict"]), @"
+    ------- stderr -------
+    Error: No conflicts found at this revision
+    [EOF]
+    [exit status: 2]
+    ");
+    // The working-copy commit was rebased onto the resolved commit
+    insta::assert_snapshot!(work_dir.read_file("file"), @"resolution");
+
+    // Exiting the editor without changes leaves the conflict as is
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    std::fs::write(&editor_script, "")?;
+    let output = work_dir.run_jj(["resolve", "--editor", "-r", "conflict"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Resolving conflicts in: file
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Editor failure is reported
+    std::fs::write(&editor_script, "fail")?;
+    let output = work_dir.run_jj(["resolve", "--editor", "-r", "conflict"]);
+    insta::with_settings!({
+        filters => [
+            (r"\bEditor '[^']*'", "Editor '<redacted>'"),
+            (r"in .*(editor-)[^_]*(_file)\b", "in <redacted>$1<redacted>$2"),
+            ("exit code", "exit status"), // Windows
+        ],
+    }, {
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Resolving conflicts in: file
+        Error: Failed to edit file
+        Caused by: Editor '<redacted>' exited with exit status: 1
+        Hint: Edited file is left in <redacted>editor-<redacted>_file
+        [EOF]
+        [exit status: 1]
+        ");
+    });
+    Ok(())
+}
+
+#[test]
+fn test_resolve_with_editor_partially() -> TestResult {
+    let mut test_env = TestEnvironment::default();
+    let editor_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let base = "a\nkeep1\nkeep2\nkeep3\nb\n";
+    let left = "a1\nkeep1\nkeep2\nkeep3\nb1\n";
+    let right = "a2\nkeep1\nkeep2\nkeep3\nb2\n";
+    create_commit_with_files(&work_dir, "base", &[], &[("file", base)]);
+    create_commit_with_files(&work_dir, "left", &["base"], &[("file", left)]);
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", right)]);
+    create_commit_with_files(&work_dir, "conflict", &["left", "right"], &[]);
+
+    // Resolve the first hunk only. The remaining conflict markers can use any
+    // supported style.
+    std::fs::write(
+        &editor_script,
+        indoc! {"
+            write
+            a12
+            keep1
+            keep2
+            keep3
+            <<<<<<< conflict 2 of 2
+            +++++++ left
+            b1
+            ------- base
+            b
+            +++++++ right
+            b2
+            >>>>>>> conflict 2 of 2 ends
+        "},
+    )?;
+    work_dir.run_jj(["resolve", "--editor"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list"]), @"
+    file    2-sided conflict
+    [EOF]
+    ");
+    let content = work_dir.read_file("file");
+    assert!(content.starts_with(b"a12\nkeep1\nkeep2\nkeep3\n<<<<<<< conflict 1 of 1\n"));
+    Ok(())
+}
+
+#[test]
+fn test_resolve_split() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let base = "a\nkeep1\nkeep2\nkeep3\nb\n";
+    create_commit_with_files(&work_dir, "base", &[], &[("file", base)]);
+    create_commit_with_files(
+        &work_dir,
+        "a",
+        &["base"],
+        &[("file", "a1\nkeep1\nkeep2\nkeep3\nb\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "b",
+        &["base"],
+        &[("file", "a\nkeep1\nkeep2\nkeep3\nb1\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "c",
+        &["base"],
+        &[("file", "a2\nkeep1\nkeep2\nkeep3\nb\n")],
+    );
+    create_commit_with_files(&work_dir, "conflict", &["a", "b", "c"], &[]);
+    create_commit_with_files(&work_dir, "tip", &["conflict"], &[]);
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "conflict"]), @"
+    file    3-sided conflict
+    [EOF]
+    ");
+
+    // Conflicts inherited from ancestors can't be split here
+    let output = work_dir.run_jj(["resolve", "--split"]);
+    insta::with_settings!({
+        filters => [(r"-r [k-z]{12}", "-r [change id]")],
+    }, {
+        insta::assert_snapshot!(output, @"
+        ------- stderr -------
+        Error: Conflicts can only be split in commits with more than 2 parents
+        Hint: The conflicts were inherited from these ancestors. Splitting or resolving them there would simplify the conflicts at this revision:
+          jj resolve --split -r [change id]
+        [EOF]
+        [exit status: 1]
+        ");
+    });
+
+    // "a" and "b" can be merged without conflicts
+    work_dir
+        .run_jj(["resolve", "--split", "-r", "conflict"])
+        .success();
+    let template = r#"parents.map(|c| c.parents().map(|p| p.description().first_line()).join("+")).join(" ") ++ "\n""#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-r", "conflict", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    a+b base
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "-r", "conflict"]), @"
+    file    2-sided conflict
+    [EOF]
+    ");
+    // The content of the conflicted commit is unchanged
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "-r", "conflict"]), @"");
+
+    // Conflicts with 2 sides don't have to be split
+    let output = work_dir.run_jj(["resolve", "--split", "-r", "conflict"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No conflicts with more than 2 sides found at this revision
+    Hint: Use `jj resolve` to resolve them with a 3-way merge tool.
+    [EOF]
+    [exit status: 1]
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_simplify_conflict_sides() -> TestResult {
     let mut test_env = TestEnvironment::default();
@@ -1000,6 +1200,10 @@ fn test_description_with_dir_and_deletion() -> TestResult {
     file    3-sided conflict including 1 deletion and a directory
     [EOF]
     ");
+    insta::assert_snapshot!(work_dir.run_jj(["--output=json", "resolve", "--list"]), @r#"
+    {"type":"conflict","path":"file","sides":3,"deletions":1,"special":["directory"]}
+    [EOF]
+    "#);
     // Test warning color. The deletion is fine, so it's not highlighted
     insta::assert_snapshot!(work_dir.run_jj(["resolve", "--list", "--color=always"]), @"
     file    [38;5;1m3-sided[38;5;3m conflict including 1 deletion and [38;5;1ma directory[39m
@@ -1312,6 +1516,14 @@ fn test_resolve_change_delete_executable() -> TestResult {
     file5    2-sided conflict including 1 deletion and an executable
     [EOF]
     ");
+    insta::assert_snapshot!(work_dir.run_jj(["--output=json", "resolve", "--list"]), @r#"
+    {"type":"conflict","path":"file1","sides":2,"deletions":1,"special":["executable"]}
+    {"type":"conflict","path":"file2","sides":2,"deletions":1,"special":["executable"]}
+    {"type":"conflict","path":"file3","sides":2,"deletions":0,"special":["executable"]}
+    {"type":"conflict","path":"file4","sides":2,"deletions":1,"special":[]}
+    {"type":"conflict","path":"file5","sides":2,"deletions":1,"special":["executable"]}
+    [EOF]
+    "#);
     insta::assert_snapshot!(file_list("all()"), @"
     file1 c -
     file2 c -
@@ -1422,7 +1634,7 @@ fn test_resolve_change_delete_executable() -> TestResult {
     insta::assert_snapshot!(output, @"
     ------- stderr -------
     Resolving conflicts in: file2
-    Working copy  (@) now at: kmkuslsw 3f8814ba conflict | (conflict) conflict
+    Working copy  (@) now at: kmkuslsw 8b7dedab conflict | (conflict) conflict
     Parent commit (@-)      : mzvwutvl e2d3924b a | a
     Parent commit (@-)      : vruxwmqv 888b6cc3 b | b
     Added 0 files, modified 1 files, removed 0 files
@@ -1453,7 +1665,7 @@ fn test_resolve_change_delete_executable() -> TestResult {
     let output = work_dir.run_jj(["resolve", "file4", "--tool=:ours"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    Working copy  (@) now at: kmkuslsw 0810a8d2 conflict | (conflict) conflict
+    Working copy  (@) now at: kmkuslsw 9e5a7dff conflict | (conflict) conflict
     Parent commit (@-)      : mzvwutvl e2d3924b a | a
     Parent commit (@-)      : vruxwmqv 888b6cc3 b | b
     Added 0 files, modified 1 files, removed 0 files
@@ -1464,9 +1676,9 @@ fn test_resolve_change_delete_executable() -> TestResult {
 
     // Take modified content, the executable bit should be kept as "x"
     let output = work_dir.run_jj(["resolve", "file5", "--tool=:theirs"]);
-    insta::assert_snapshot!(output, @r"
+    insta::assert_snapshot!(output, @"
     ------- stderr -------
-    Working copy  (@) now at: kmkuslsw 7337267a conflict | conflict
+    Working copy  (@) now at: kmkuslsw 3eb72cb0 conflict | conflict
     Parent commit (@-)      : mzvwutvl e2d3924b a | a
     Parent commit (@-)      : vruxwmqv 888b6cc3 b | b
     Added 0 files, modified 1 files, removed 0 files
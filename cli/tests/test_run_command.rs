@@ -137,7 +137,8 @@ fn test_run_noop() {
     // and emits to its own stdout. No tracked files in the working copy change,
     // so no commits get rewritten. Using a fixed string keeps the per-commit
     // output identical, so the concatenated stdout is stable regardless of the
-    // (non-deterministic) order in which the parallel jobs finish.
+    // (non-deterministic) order in which the parallel jobs finish. The working
+    // copy has the same tree as C, so it reuses C's result.
     let output = work_dir
         .run_jj(&[
             "run",
@@ -151,6 +152,7 @@ fn test_run_noop() {
         .success();
     insta::assert_snapshot!(output.stdout, @"foofoofoofoo[EOF]");
     insta::assert_snapshot!(output.stderr, @r"
+    Reused cached results for 1 commits.
     Nothing changed.
     [EOF]
     ");
@@ -539,11 +541,24 @@ fn test_run_shell_command() {
     "
     );
 
+    // The output depends on the commit ID, so it has to be part of the cache
+    // key. Otherwise the working copy would reuse the result of its parent,
+    // which has the same tree.
     let jj_args: &[&str] = if cfg!(windows) {
-        &["run", "-r", "..@", "--", "cmd", "/c", "echo %JJ_COMMIT_ID%"]
+        &[
+            "run",
+            r#"--config=run.cache.env=["JJ_COMMIT_ID"]"#,
+            "-r",
+            "..@",
+            "--",
+            "cmd",
+            "/c",
+            "echo %JJ_COMMIT_ID%",
+        ]
     } else {
         &[
             "run",
+            r#"--config=run.cache.env=["JJ_COMMIT_ID"]"#,
             "-r",
             "..@",
             "--",
@@ -1376,9 +1391,25 @@ fn test_run_order() {
     work_dir.run_jj(&["commit", "-m", "C"]).success();
 
     let jj_args: &[&str] = if cfg!(windows) {
-        &["run", "-r=..@", "--", "cmd", "/c", "echo %JJ_CHANGE_ID%"]
+        &[
+            "run",
+            r#"--config=run.cache.env=["JJ_CHANGE_ID"]"#,
+            "-r=..@",
+            "--",
+            "cmd",
+            "/c",
+            "echo %JJ_CHANGE_ID%",
+        ]
     } else {
-        &["run", "-r=..@", "--", "sh", "-c", "echo $JJ_CHANGE_ID"]
+        &[
+            "run",
+            r#"--config=run.cache.env=["JJ_CHANGE_ID"]"#,
+            "-r=..@",
+            "--",
+            "sh",
+            "-c",
+            "echo $JJ_CHANGE_ID",
+        ]
     };
     assert_snapshot!(work_dir.run_jj(jj_args).success().stdout,@"
     qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
@@ -1418,6 +1449,7 @@ fn test_run_passthrough() {
         &[
             "run",
             "--passthrough",
+            "--no-cache",
             "-r",
             "..@",
             "--",
@@ -1429,6 +1461,7 @@ fn test_run_passthrough() {
         &[
             "run",
             "--passthrough",
+            "--no-cache",
             "-r",
             "..@",
             "--",
@@ -1492,3 +1525,415 @@ fn test_run_passthrough_rejects_multi_job() {
     [EOF]
     ");
 }
+
+#[cfg(unix)]
+#[test]
+fn test_run_sandboxed_executor_scrubs_environment() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("RUN_TEST_SECRET", "hunter2");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    let script = r#"echo "secret=${RUN_TEST_SECRET:-unset}"
+        if test "$HOME" = "$TMPDIR" && test -d "$HOME"; then echo "commit=$JJ_COMMIT_ID"; fi"#;
+
+    // The local executor passes the whole environment through
+    let output = work_dir
+        .run_jj(&["run", "-r", "@-", "--", "sh", "-c", script])
+        .success();
+    insta::assert_snapshot!(output.stdout, @r"
+    secret=hunter2
+    [EOF]
+    ");
+
+    // The sandboxed executor only passes through `run.sandbox.env` and the
+    // commit's variables, with `HOME` pointing to a temporary directory
+    let output = work_dir
+        .run_jj(&[
+            "run",
+            "--config=run.executor=sandboxed",
+            "-r",
+            "@-",
+            "--",
+            "sh",
+            "-c",
+            script,
+        ])
+        .success();
+    insta::assert_snapshot!(output.stdout, @r"
+    secret=unset
+    commit=5fbe90560fed1c39d46a46a672ba98abd53bdc6d
+    [EOF]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_sandboxed_executor_wrapper() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"
+        run.executor = "sandboxed"
+        run.sandbox.wrapper = ["sh", "-c", 'echo "wrapped in $root"; exec "$@"', "wrapper"]
+        "#,
+    );
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    let output = work_dir
+        .run_jj(&["run", "-r", "@-", "--", "touch", "wrapped.txt"])
+        .success();
+    insta::assert_snapshot!(output.stdout, @r"
+    wrapped in $TEST_ENV/repo/.jj/run/default/1/working_copy
+    [EOF]
+    ");
+    let output = work_dir.run_jj(&["file", "list", "-r", "@-"]);
+    insta::assert_snapshot!(output, @r"
+    seed.txt
+    wrapped.txt
+    [EOF]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_external_executor() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    // A stand-in for a client which would run the command elsewhere
+    test_env.add_config(
+        r#"
+        run.executor = "external"
+        run.external.command = ["sh", "-c", 'echo "executing in client"; cd "$root" && exec "$@"', "client"]
+        "#,
+    );
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    let output = work_dir
+        .run_jj(&["run", "-r", "@-", "--", "touch", "external.txt"])
+        .success();
+    insta::assert_snapshot!(output.stdout, @r"
+    executing in client
+    [EOF]
+    ");
+    let output = work_dir.run_jj(&["file", "list", "-r", "@-"]);
+    insta::assert_snapshot!(output, @r"
+    external.txt
+    seed.txt
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_run_external_executor_requires_command() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(r#"run.executor = "external""#);
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(&["run", "-r", "@", "--", "true"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: Value not found for run.external.command
+    For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_cache() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    // Every time the command actually runs, it appends to a file outside of
+    // the repo
+    let counter_path = test_env.env_root().join("counter");
+    let counter = counter_path.to_str().unwrap();
+    let run = |extra_args: &[&str]| {
+        let mut args = vec!["run", "-r", "@-"];
+        args.extend_from_slice(extra_args);
+        args.extend([
+            "--",
+            "sh",
+            "-c",
+            r#"echo ran >> "$0"; echo output"#,
+            counter,
+        ]);
+        work_dir.run_jj(&args)
+    };
+    let num_runs = || {
+        std::fs::read_to_string(&counter_path)
+            .unwrap()
+            .lines()
+            .count()
+    };
+
+    let output = run(&[]);
+    insta::assert_snapshot!(output, @r"
+    output
+    [EOF]
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    assert_eq!(num_runs(), 1);
+
+    // The second run replays the cached output
+    let output = run(&[]);
+    insta::assert_snapshot!(output, @r"
+    output
+    [EOF]
+    ------- stderr -------
+    Reused cached results for 1 commits.
+    Nothing changed.
+    [EOF]
+    ");
+    assert_eq!(num_runs(), 1);
+
+    let output = run(&["--no-cache"]);
+    insta::assert_snapshot!(output, @r"
+    output
+    [EOF]
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    assert_eq!(num_runs(), 2);
+
+    // By default, rewriting the commit without changing its content reuses the
+    // results
+    work_dir
+        .run_jj(&["describe", "-r", "@-", "-m", "reworded"])
+        .success();
+    run(&[]).success();
+    assert_eq!(num_runs(), 2);
+
+    // Unless the commit ID is part of the cache key
+    let commit_id_env = r#"--config=run.cache.env=["JJ_COMMIT_ID"]"#;
+    run(&[commit_id_env]).success();
+    assert_eq!(num_runs(), 3);
+    run(&[commit_id_env]).success();
+    assert_eq!(num_runs(), 3);
+    work_dir
+        .run_jj(&["describe", "-r", "@-", "-m", "reworded again"])
+        .success();
+    run(&[commit_id_env]).success();
+    assert_eq!(num_runs(), 4);
+
+    // Garbage collection drops the cached results
+    work_dir.run_jj(["util", "gc", "--expire=now"]).success();
+    run(&[]).success();
+    assert_eq!(num_runs(), 5);
+}
+
+#[test]
+fn test_run_cache_replays_failure() {
+    let mut test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config(
+        r#"templates.commit_summary = 'coalesce(description.first_line(), "(no description)")'"#,
+    );
+    let fake_formatter = assert_cmd::cargo::cargo_bin("fake-formatter");
+    assert!(fake_formatter.is_file());
+    let fake_formatter_path = fake_formatter.to_string_lossy().into_owned();
+    test_env.add_paths_to_normalize(fake_formatter.clone(), "$FAKE_FORMATTER_PATH");
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    let output = work_dir.run_jj(&["run", "-r", "@-", "--", &fake_formatter_path, "--fail"]);
+    insta::assert_snapshot!(output.normalize_stderr_exit_status(), @r"
+    ------- stderr -------
+    Error: the command '$FAKE_FORMATTER_PATH --fail' failed with exit status: 1
+    Hint: Failed revision: seed
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(&["run", "-r", "@-", "--", &fake_formatter_path, "--fail"]);
+    insta::assert_snapshot!(output.normalize_stderr_exit_status(), @r"
+    ------- stderr -------
+    Error: the command '$FAKE_FORMATTER_PATH --fail' failed with exit status: 1
+    Hint: Failed revision: seed
+    Hint: The failure was recorded by a previous run. Use `--no-cache` to run the command again.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_run_named_pools() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    work_dir
+        .run_jj(&["run", "-r", "@-", "--", "touch", "ran.txt"])
+        .success();
+    work_dir
+        .run_jj(&[
+            "run", "--pool", "nightly", "-j", "2", "-r", "@-", "--", "touch", "ran2.txt",
+        ])
+        .success();
+    let run_dir = work_dir.root().join(".jj/run");
+    assert!(run_dir.join("default/1").is_dir());
+    assert!(run_dir.join("nightly/1").is_dir());
+
+    // The default pool can be changed in the config
+    work_dir
+        .run_jj(&[
+            "run",
+            "--config=run.pool=stable",
+            "-r",
+            "@-",
+            "--",
+            "touch",
+            "ran3.txt",
+        ])
+        .success();
+    assert!(run_dir.join("stable/1").is_dir());
+
+    let output = work_dir.run_jj(["run", "pool", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: 1 working copies
+    nightly: 1 working copies
+    stable: 1 working copies
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["run", "pool", "clean", "nightly", "stable"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Removed 1 working copies from pool nightly.
+    Removed 1 working copies from pool stable.
+    [EOF]
+    ");
+    assert!(!run_dir.join("nightly").exists());
+    assert!(run_dir.join("default/1").is_dir());
+
+    let output = work_dir.run_jj(["run", "pool", "clean", "nightly"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such workspace pool: nightly
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["run", "pool", "clean", "--all"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Removed 1 working copies from pool default.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["run", "pool", "list"]);
+    insta::assert_snapshot!(output, @"");
+}
+
+#[test]
+fn test_run_invalid_pool_name() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["run", "--pool", "../outside", "--", "true"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: invalid workspace pool name "../outside"
+    [EOF]
+    [exit status: 1]
+    "#);
+    assert!(!work_dir.root().join(".jj/outside").exists());
+}
+
+#[test]
+fn test_run_pool_dir() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    let pool_dir = test_env.env_root().join("pools");
+    let pool_dir_arg = format!("--config=run.pool-dir='{}'", pool_dir.display());
+    work_dir
+        .run_jj(&[&pool_dir_arg, "run", "-r", "@-", "--", "touch", "ran.txt"])
+        .success();
+    assert!(!work_dir.root().join(".jj/run").exists());
+    // The pools are stored in a subdirectory for the repository
+    let repo_pool_dirs: Vec<_> = fs::read_dir(&pool_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    let [repo_pool_dir] = repo_pool_dirs.as_slice() else {
+        panic!("expected a single directory: {repo_pool_dirs:?}");
+    };
+    assert!(repo_pool_dir.join("default/1/working_copy").is_dir());
+
+    let output = work_dir.run_jj([&pool_dir_arg, "run", "pool", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: 1 working copies
+    [EOF]
+    ");
+
+    // Another repository doesn't share the working copies
+    test_env.run_jj_in(".", ["git", "init", "repo2"]).success();
+    let work_dir2 = test_env.work_dir("repo2");
+    let output = work_dir2.run_jj([&pool_dir_arg, "run", "pool", "list"]);
+    insta::assert_snapshot!(output, @"");
+    work_dir2
+        .run_jj(&[&pool_dir_arg, "run", "-r", "@", "--", "touch", "ran.txt"])
+        .success();
+    assert_eq!(fs::read_dir(&pool_dir).unwrap().count(), 2);
+    let output = work_dir.run_jj([&pool_dir_arg, "run", "pool", "clean", "--all"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Removed 1 working copies from pool default.
+    [EOF]
+    ");
+    let output = work_dir2.run_jj([&pool_dir_arg, "run", "pool", "list"]);
+    insta::assert_snapshot!(output, @"
+    default: 1 working copies
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_run_respects_max_new_file_size() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("seed.txt", "seed");
+    work_dir.run_jj(&["commit", "-m", "seed"]).success();
+
+    work_dir
+        .run_jj(&[
+            "run",
+            "--config=snapshot.max-new-file-size=10",
+            "-r",
+            "@-",
+            "--",
+            "sh",
+            "-c",
+            "echo small > small.txt && echo 'this file is too large' > large.txt",
+        ])
+        .success();
+    let output = work_dir.run_jj(["file", "list", "-r", "@-"]);
+    insta::assert_snapshot!(output, @r"
+    seed.txt
+    small.txt
+    [EOF]
+    ");
+}
@@ -39,6 +39,14 @@ fn test_status_copies() {
     Parent commit (@-): qpvuntsm ebf799bc (no description set)
     [EOF]
     ");
+
+    let output = work_dir.run_jj(["--output=json", "status"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(omit_commit_lines), @r#"
+    {"type":"change","status":"modified","path":"copy-source"}
+    {"type":"change","status":"copied","path":"copy-target","source":"copy-source"}
+    {"type":"change","status":"renamed","path":"rename-target","source":"rename-source"}
+    [EOF]
+    "#);
 }
 
 #[test]
@@ -243,6 +251,13 @@ fn test_status_conflicted_bookmarks() {
     Concurrent modification detected, resolving automatically.
     [EOF]
     ");
+
+    let output = work_dir.run_jj(["--output=json", "status"]);
+    insta::assert_snapshot!(output.normalize_stdout_with(omit_commit_lines), @r#"
+    {"type":"conflicted_bookmark","name":"local_bookmark"}
+    {"type":"conflicted_bookmark","name":"remote_bookmark","remote":"origin"}
+    [EOF]
+    "#);
 }
 
 // See <https://github.com/jj-vcs/jj/issues/3108>
@@ -561,6 +576,16 @@ fn test_status_untracked_files() {
     [EOF]
     ");
 
+    let output = work_dir.run_jj(["--output=json", "status"]);
+    insta::assert_snapshot!(output, @r#"
+    {"type":"working_copy","commit":{"commit_id":"e8849ae12c709f2321908879bc724fdb2ab8a781","parents":["0000000000000000000000000000000000000000"],"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:07+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:07+07:00"}}}
+    {"type":"parent","commit":{"commit_id":"0000000000000000000000000000000000000000","parents":[],"change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","description":"","author":{"name":"","email":"","timestamp":"1970-01-01T00:00:00Z"},"committer":{"name":"","email":"","timestamp":"1970-01-01T00:00:00Z"}}}
+    {"type":"untracked","path":"always-untracked-file","is_dir":false}
+    {"type":"untracked","path":"initially-untracked-file","is_dir":false}
+    {"type":"untracked","path":"sub","is_dir":true}
+    [EOF]
+    "#);
+
     work_dir
         .run_jj([
             "file",
@@ -578,7 +603,7 @@ fn test_status_untracked_files() {
     Untracked paths:
     ? always-untracked-file
     ? sub/always-untracked
-    Working copy  (@) : qpvuntsm b8c1286d (no description set)
+    Working copy  (@) : qpvuntsm c8f2b97f (no description set)
     Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
     [EOF]
     ");
@@ -590,8 +615,8 @@ fn test_status_untracked_files() {
     Untracked paths:
     ? always-untracked-file
     ? sub/always-untracked
-    Working copy  (@) : mzvwutvl daa133b8 (empty) (no description set)
-    Parent commit (@-): qpvuntsm b8c1286d (no description set)
+    Working copy  (@) : royxmykx ae2762d0 (empty) (no description set)
+    Parent commit (@-): qpvuntsm c8f2b97f (no description set)
     [EOF]
     ");
 
@@ -612,8 +637,8 @@ fn test_status_untracked_files() {
     ? always-untracked-file
     ? initially-untracked-file
     ? sub/
-    Working copy  (@) : mzvwutvl 240f261a (no description set)
-    Parent commit (@-): qpvuntsm b8c1286d (no description set)
+    Working copy  (@) : royxmykx 42f72ff7 (no description set)
+    Parent commit (@-): qpvuntsm c8f2b97f (no description set)
     [EOF]
     ");
 
@@ -625,8 +650,8 @@ fn test_status_untracked_files() {
     ? always-untracked-file
     ? initially-untracked-file
     ? sub/
-    Working copy  (@) : yostqsxw 50beac0d (empty) (no description set)
-    Parent commit (@-): mzvwutvl 240f261a (no description set)
+    Working copy  (@) : znkkpsqq 32801e16 (empty) (no description set)
+    Parent commit (@-): royxmykx 42f72ff7 (no description set)
     [EOF]
     ");
 
@@ -636,8 +661,8 @@ fn test_status_untracked_files() {
     ? ../always-untracked-file
     ? ../initially-untracked-file
     ? ./
-    Working copy  (@) : yostqsxw 50beac0d (empty) (no description set)
-    Parent commit (@-): mzvwutvl 240f261a (no description set)
+    Working copy  (@) : znkkpsqq 32801e16 (empty) (no description set)
+    Parent commit (@-): royxmykx 42f72ff7 (no description set)
     [EOF]
     ");
 }
@@ -705,3 +730,10 @@ fn test_status_no_working_copy() {
     [EOF]
     ");
 }
+
+fn omit_commit_lines(stdout: String) -> String {
+    stdout
+        .split_inclusive('\n')
+        .filter(|line| !line.contains(r#""commit":"#))
+        .collect()
+}
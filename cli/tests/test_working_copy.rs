@@ -540,6 +540,97 @@ fn test_submodule_ignored() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_submodule_update() {
+    let test_env = TestEnvironment::default();
+
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "submodule"])
+        .success();
+    let submodule_dir = test_env.work_dir("submodule");
+    submodule_dir.write_file("sub", "sub");
+    submodule_dir
+        .run_jj(["commit", "-m", "Submodule commit"])
+        .success();
+
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj([
+            "util",
+            "exec",
+            "--",
+            "git",
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            &format!("{}/submodule", test_env.env_root().display()),
+            "sub",
+        ])
+        .success();
+    work_dir
+        .run_jj([
+            "util",
+            "exec",
+            "--",
+            "git",
+            "-c",
+            "user.email=test@example.com",
+            "-c",
+            "user.name=Test user",
+            "commit",
+            "-m",
+            "Add submodule",
+        ])
+        .success();
+
+    // The submodule pointer is shown in Git diff format
+    let output = work_dir.run_jj(["diff", "--git", "-r@-", "sub"]);
+    let stdout = output.stdout.raw();
+    assert!(stdout.contains("new file mode 160000"), "{stdout}");
+    assert!(stdout.contains("+Subproject commit "), "{stdout}");
+
+    // Check out the submodule from scratch
+    work_dir.run_jj(["prev"]).success();
+    std::fs::remove_dir_all(work_dir.root().join("sub")).unwrap();
+    let output = work_dir.run_jj([
+        "next",
+        "--config=working-copy.update-submodules=true",
+        "--config=working-copy.allow-local-submodule-urls=true",
+    ]);
+    let stderr = output.stderr.raw();
+    assert!(stderr.contains("Updated 1 submodules"), "{stderr}");
+    assert_eq!(work_dir.read_file("sub/sub"), "sub");
+
+    // Moving the submodule checkout is reported by status
+    std::process::Command::new("git")
+        .current_dir(work_dir.root().join("sub"))
+        .args([
+            "-c",
+            "user.email=test@example.com",
+            "-c",
+            "user.name=Test user",
+            "commit",
+            "--allow-empty",
+            "-m",
+            "Local commit",
+        ])
+        .output()
+        .unwrap();
+    let output = work_dir.run_jj(["status", "--config=working-copy.update-submodules=true"]);
+    let stdout = output.stdout.raw();
+    assert!(
+        stdout.contains("These submodules are not checked out at the pinned commits"),
+        "{stdout}"
+    );
+    let output = work_dir.run_jj(["status"]);
+    let stdout = output.stdout.raw();
+    assert!(!stdout.contains("These submodules"), "{stdout}");
+}
+
 #[test]
 fn test_snapshot_jjconflict_trees() -> TestResult {
     let test_env = TestEnvironment::default();
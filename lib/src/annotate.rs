@@ -19,6 +19,7 @@
 //! Like commit metadata and more.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::hash_map;
 use std::iter;
 use std::ops::Range;
@@ -49,6 +50,7 @@ use crate::repo::Repo;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
 use crate::revset::RevsetFilterPredicate;
@@ -152,9 +154,10 @@ impl FileAnnotation {
 /// Annotation process for a specific file.
 #[derive(Clone, Debug)]
 pub struct FileAnnotator {
-    // If we add copy-tracing support, file_path might be tracked by state.
-    file_path: RepoPathBuf,
     starting_text: BString,
+    /// Range of lines to annotate in the starting file.
+    line_range: Range<usize>,
+    ignored_revisions: Arc<ResolvedRevsetExpression>,
     state: AnnotationState,
 }
 
@@ -167,7 +170,7 @@ impl FileAnnotator {
         file_path: &RepoPath,
     ) -> BackendResult<Self> {
         let source = Source::load(starting_commit, file_path).await?;
-        Ok(Self::with_source(starting_commit.id(), file_path, source))
+        Ok(Self::with_source(starting_commit.id(), source))
     }
 
     /// Initializes annotator for a specific file path starting with the given
@@ -181,17 +184,14 @@ impl FileAnnotator {
         file_path: &RepoPath,
         starting_text: impl Into<Vec<u8>>,
     ) -> Self {
-        let source = Source::new(BString::new(starting_text.into()));
-        Self::with_source(starting_commit_id, file_path, source)
+        let source = Source::new(file_path.to_owned(), BString::new(starting_text.into()));
+        Self::with_source(starting_commit_id, source)
     }
 
-    fn with_source(
-        starting_commit_id: &CommitId,
-        file_path: &RepoPath,
-        mut source: Source,
-    ) -> Self {
+    fn with_source(starting_commit_id: &CommitId, mut source: Source) -> Self {
         source.fill_line_map();
         let starting_text = source.text.clone();
+        let line_range = 0..source.line_map.len();
         let state = AnnotationState {
             original_line_map: (0..source.line_map.len())
                 .map(|line_number| {
@@ -202,26 +202,81 @@ impl FileAnnotator {
                 })
                 .collect(),
             commit_source_map: HashMap::from([(starting_commit_id.clone(), source)]),
-            num_unresolved_roots: 0,
+            unresolved_roots: HashSet::new(),
+            resolved_lines: Vec::new(),
         };
         Self {
-            file_path: file_path.to_owned(),
             starting_text,
+            line_range,
+            ignored_revisions: RevsetExpression::none(),
             state,
         }
     }
 
+    /// Restricts the annotation to the given range of 0-based line numbers in
+    /// the starting file.
+    ///
+    /// Lines outside of the range are no longer tracked, which is usually
+    /// faster than annotating the whole file. The range is clamped to the
+    /// number of lines in the file.
+    pub fn set_line_range(&mut self, lines: Range<usize>) {
+        let end = lines.end.min(self.state.original_line_map.len());
+        let start = lines.start.min(end);
+        self.line_range = start..end;
+        for source in self.state.commit_source_map.values_mut() {
+            source
+                .line_map
+                .retain(|(_, starting)| self.line_range.contains(starting));
+        }
+    }
+
+    /// Sets revisions whose changes should be looked through.
+    ///
+    /// Lines modified by the ignored revisions are attributed to the
+    /// corresponding lines in the first parent, which is useful to skip
+    /// mass-reformatting commits. Lines that were purely added by the ignored
+    /// revisions are still attributed to them.
+    pub fn set_ignored_revisions(&mut self, revisions: Arc<ResolvedRevsetExpression>) {
+        self.ignored_revisions = revisions;
+    }
+
     /// Computes line-by-line annotation within the `domain`.
     ///
     /// The `domain` expression narrows the range of ancestors to search. It
     /// will be intersected as `domain & ::pending_commits & files(file_path)`.
     /// The `pending_commits` is assumed to be included in the `domain`.
+    ///
+    /// If the file was copied or renamed from another path, the search
+    /// continues from the source path.
     pub async fn compute(
         &mut self,
         repo: &dyn Repo,
         domain: &Arc<ResolvedRevsetExpression>,
     ) -> Result<(), RevsetEvaluationError> {
-        process_commits(repo, &mut self.state, domain, &self.file_path).await
+        self.compute_with(repo, domain, |_, _| {}).await
+    }
+
+    /// Like [`Self::compute()`], but calls `on_resolved(line_number, origin)`
+    /// as soon as the origin of a line is found.
+    ///
+    /// The `line_number` is a 0-based line number in the starting file. The
+    /// `origin` is `Err` if the search stopped at a commit outside of the
+    /// `domain`. This allows callers to show partial results while ancestors
+    /// are still being searched.
+    pub async fn compute_with(
+        &mut self,
+        repo: &dyn Repo,
+        domain: &Arc<ResolvedRevsetExpression>,
+        mut on_resolved: impl FnMut(usize, Result<&LineOrigin, &LineOrigin>),
+    ) -> Result<(), RevsetEvaluationError> {
+        process_commits(
+            repo,
+            &mut self.state,
+            domain,
+            &self.ignored_revisions,
+            &mut on_resolved,
+        )
+        .await
     }
 
     /// Remaining commit ids to visit from.
@@ -230,13 +285,17 @@ impl FileAnnotator {
     }
 
     /// Returns the current state as line-oriented annotation.
+    ///
+    /// If the line range is set, only the lines in the range are included.
     pub fn to_annotation(&self) -> FileAnnotation {
         // Just clone the line map. We might want to change the underlying data
         // model something akin to interleaved delta in order to get annotation
         // at a certain ancestor commit without recomputing.
+        let lines = self.starting_text.split_inclusive(|b| *b == b'\n');
+        let text = lines.collect_vec()[self.line_range.clone()].concat();
         FileAnnotation {
-            line_map: self.state.original_line_map.clone(),
-            text: self.starting_text.clone(),
+            line_map: self.state.original_line_map[self.line_range.clone()].to_vec(),
+            text: text.into(),
         }
     }
 }
@@ -247,8 +306,10 @@ struct AnnotationState {
     original_line_map: OriginalLineMap,
     /// Commits to file line mappings and contents.
     commit_source_map: HashMap<CommitId, Source>,
-    /// Number of unresolved root commits in `commit_source_map`.
-    num_unresolved_roots: usize,
+    /// Unresolved root commits in `commit_source_map`.
+    unresolved_roots: HashSet<CommitId>,
+    /// Starting line numbers whose origins were found since last reported.
+    resolved_lines: Vec<usize>,
 }
 
 /// Line mapping and file content at a certain commit.
@@ -259,20 +320,23 @@ struct Source {
     line_map: Vec<(usize, usize)>,
     /// File content at the current commit.
     text: BString,
+    /// File path at the current commit.
+    path: RepoPathBuf,
 }
 
 impl Source {
-    fn new(text: BString) -> Self {
+    fn new(path: RepoPathBuf, text: BString) -> Self {
         Self {
             line_map: Vec::new(),
             text,
+            path,
         }
     }
 
     async fn load(commit: &Commit, file_path: &RepoPath) -> Result<Self, BackendError> {
         let tree = commit.tree();
         let text = get_file_contents(commit.store(), file_path, &tree).await?;
-        Ok(Self::new(text))
+        Ok(Self::new(file_path.to_owned(), text))
     }
 
     fn fill_line_map(&mut self) {
@@ -300,101 +364,121 @@ async fn process_commits(
     repo: &dyn Repo,
     state: &mut AnnotationState,
     domain: &Arc<ResolvedRevsetExpression>,
-    file_name: &RepoPath,
+    ignored_revisions: &Arc<ResolvedRevsetExpression>,
+    on_resolved: &mut dyn FnMut(usize, Result<&LineOrigin, &LineOrigin>),
 ) -> Result<(), RevsetEvaluationError> {
-    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
-    // TODO: If the domain isn't a contiguous range, changes masked out by it
-    // might not be caught by the closest ancestor revision. For example,
-    // domain=merges() would pick up almost nothing because merge revisions
-    // are usually empty. Perhaps, we want to query `files(file_path,
-    // within_sub_graph=domain)`, not `domain & files(file_path)`.
-    let heads = RevsetExpression::commits(state.commit_source_map.keys().cloned().collect());
-    let revset = heads
-        .union(&domain.intersection(&heads.ancestors()).filtered(predicate))
-        .evaluate(repo)?;
-
-    state.num_unresolved_roots = 0;
-    let mut nodes = revset.stream_graph();
-    while let Some((commit_id, edge_list)) = nodes.try_next().await? {
-        process_commit(repo, file_name, state, &commit_id, &edge_list).await?;
-        if state.commit_source_map.len() == state.num_unresolved_roots {
-            // No more lines to propagate to ancestors.
-            break;
+    state.unresolved_roots.clear();
+    // If the file is found to be copied from another path, the pending commits
+    // are queried again with the source path.
+    loop {
+        let (head_ids, file_paths): (Vec<_>, Vec<_>) = state
+            .commit_source_map
+            .iter()
+            .filter(|(commit_id, _)| !state.unresolved_roots.contains(*commit_id))
+            .map(|(commit_id, source)| (commit_id.clone(), source.path.clone()))
+            .unzip();
+        if head_ids.is_empty() {
+            return Ok(());
+        }
+        let predicate = RevsetFilterPredicate::File(FilesetExpression::union_all(
+            file_paths
+                .into_iter()
+                .unique()
+                .map(FilesetExpression::file_path)
+                .collect(),
+        ));
+        // TODO: If the domain isn't a contiguous range, changes masked out by it
+        // might not be caught by the closest ancestor revision. For example,
+        // domain=merges() would pick up almost nothing because merge revisions
+        // are usually empty. Perhaps, we want to query `files(file_path,
+        // within_sub_graph=domain)`, not `domain & files(file_path)`.
+        let heads = RevsetExpression::commits(head_ids);
+        let ancestors = domain.intersection(&heads.ancestors());
+        let revset = heads.union(&ancestors.filtered(predicate)).evaluate(repo)?;
+        let domain_revset = ancestors.clone().evaluate(repo)?;
+        let ignored_revset = ignored_revisions.intersection(&ancestors).evaluate(repo)?;
+        let is_in_domain = domain_revset.containing_fn();
+        let is_ignored = ignored_revset.containing_fn();
+        let ctx = ProcessContext {
+            repo,
+            is_in_domain: &*is_in_domain,
+            is_ignored: &*is_ignored,
+        };
+
+        let mut nodes = revset.stream_graph();
+        let mut found_copies = false;
+        while let Some((commit_id, edge_list)) = nodes.try_next().await? {
+            found_copies = process_commit(&ctx, state, &commit_id, &edge_list).await?;
+            for line_number in state.resolved_lines.drain(..) {
+                on_resolved(line_number, state.original_line_map[line_number].as_ref());
+            }
+            if state.commit_source_map.len() == state.unresolved_roots.len() {
+                // No more lines to propagate to ancestors.
+                return Ok(());
+            }
+            if found_copies {
+                break;
+            }
+        }
+        if !found_copies {
+            return Ok(());
         }
     }
-    Ok(())
+}
+
+/// Repository and revision sets used while processing commits.
+struct ProcessContext<'a> {
+    repo: &'a dyn Repo,
+    is_in_domain: &'a RevsetContainingFn<'a>,
+    is_ignored: &'a RevsetContainingFn<'a>,
 }
 
 /// For a given commit, for each parent, we compare the version in the parent
 /// tree with the current version, updating the mappings for any lines in
 /// common. If the parent doesn't have the file, we skip it.
+///
+/// Returns true if the file was copied or renamed from another path.
 async fn process_commit(
-    repo: &dyn Repo,
-    file_name: &RepoPath,
+    ctx: &ProcessContext<'_>,
     state: &mut AnnotationState,
     current_commit_id: &CommitId,
     edges: &[GraphEdge<CommitId>],
-) -> Result<(), BackendError> {
+) -> Result<bool, RevsetEvaluationError> {
     let Some(mut current_source) = state.commit_source_map.remove(current_commit_id) else {
-        return Ok(());
+        return Ok(false);
     };
 
-    for parent_edge in edges {
-        let parent_commit_id = &parent_edge.target;
-        let parent_source = match state.commit_source_map.entry(parent_commit_id.clone()) {
-            hash_map::Entry::Occupied(entry) => entry.into_mut(),
-            hash_map::Entry::Vacant(entry) => {
-                let commit = repo.store().get_commit_async(entry.key()).await?;
-                entry.insert(Source::load(&commit, file_name).await?)
-            }
-        };
+    // Copy sources are looked up only if there are lines left to resolve.
+    let copy_edges = if current_source.line_map.is_empty() {
+        None
+    } else {
+        find_copy_source_edges(ctx, current_commit_id, &current_source.path, edges).await?
+    };
+    let found_copies = copy_edges.is_some();
+    let edges = copy_edges.unwrap_or_else(|| {
+        edges
+            .iter()
+            .map(|edge| (edge.clone(), current_source.path.clone()))
+            .collect()
+    });
+
+    for (parent_edge, parent_path) in &edges {
+        let parent_source =
+            load_parent_source(ctx.repo, state, &parent_edge.target, parent_path).await?;
+        let new_parent_line_map = take_same_lines(&mut current_source, &parent_source.text);
+        add_parent_line_map(state, parent_edge, new_parent_line_map);
+    }
 
-        // For two versions of the same file, for all the lines in common,
-        // overwrite the new mapping in the results for the new commit. Let's
-        // say I have a file in commit A and commit B. We know that according to
-        // local line_map, in commit A, line 3 corresponds to line 7 of the
-        // starting file. Now, line 3 in Commit A corresponds to line 6 in
-        // commit B. Then, we update local line_map to say that "Commit B line 6
-        // goes to line 7 of the starting file". We repeat this for all lines in
-        // common in the two commits.
-        let mut current_lines = current_source.line_map.iter().copied().peekable();
-        let mut new_current_line_map = Vec::new();
-        let mut new_parent_line_map = Vec::new();
-        copy_same_lines_with(
-            &current_source.text,
-            &parent_source.text,
-            |current_start, parent_start, count| {
-                new_current_line_map
-                    .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
-                while let Some((current, starting)) =
-                    current_lines.next_if(|&(cur, _)| cur < current_start + count)
-                {
-                    let parent = parent_start + (current - current_start);
-                    new_parent_line_map.push((parent, starting));
-                }
-            },
-        );
-        new_current_line_map.extend(current_lines);
-        current_source.line_map = new_current_line_map;
-        parent_source.line_map = if parent_source.line_map.is_empty() {
-            new_parent_line_map
-        } else {
-            itertools::merge(parent_source.line_map.iter().copied(), new_parent_line_map).collect()
-        };
-        if parent_source.line_map.is_empty() {
-            state.commit_source_map.remove(parent_commit_id);
-        } else if parent_edge.is_missing() {
-            // If an omitted parent had the file, leave these lines unresolved.
-            // The origin of the unresolved lines is represented as
-            // Err(LineOrigin { parent_commit_id, parent_line_number }).
-            for &(parent_line_number, starting_line_number) in &parent_source.line_map {
-                state.original_line_map[starting_line_number] = Err(LineOrigin {
-                    commit_id: parent_commit_id.clone(),
-                    line_number: parent_line_number,
-                });
-            }
-            state.num_unresolved_roots += 1;
-        }
+    // Lines changed in an ignored commit are mapped to the nearby lines in the
+    // first parent as if they weren't changed.
+    if !current_source.line_map.is_empty()
+        && let Some((parent_edge, parent_path)) = edges.first()
+        && (ctx.is_ignored)(current_commit_id)?
+    {
+        let parent_source =
+            load_parent_source(ctx.repo, state, &parent_edge.target, parent_path).await?;
+        let new_parent_line_map = take_changed_lines(&mut current_source, &parent_source.text);
+        add_parent_line_map(state, parent_edge, new_parent_line_map);
     }
 
     // Once we've looked at all parents of a commit, any leftover lines must be
@@ -405,9 +489,152 @@ async fn process_commit(
             commit_id: current_commit_id.clone(),
             line_number: current_line_number,
         });
+        state.resolved_lines.push(starting_line_number);
+    }
+
+    Ok(found_copies)
+}
+
+/// If the file at `file_path` doesn't exist in the parents, looks up where it
+/// was copied or renamed from. Returns edges to the actual parents paired with
+/// the source paths in them.
+async fn find_copy_source_edges(
+    ctx: &ProcessContext<'_>,
+    commit_id: &CommitId,
+    file_path: &RepoPath,
+    edges: &[GraphEdge<CommitId>],
+) -> Result<Option<Vec<(GraphEdge<CommitId>, RepoPathBuf)>>, RevsetEvaluationError> {
+    let store = ctx.repo.store();
+    // The graph edges point to the closest ancestors that modified the file
+    // path. If any of them has the file, this commit isn't the origin.
+    for edge in edges {
+        let parent = store.get_commit_async(&edge.target).await?;
+        if !parent.tree().path_value(file_path).await?.is_absent() {
+            return Ok(None);
+        }
     }
+    let commit = store.get_commit_async(commit_id).await?;
+    let mut copy_edges = Vec::new();
+    for parent_id in commit.parent_ids() {
+        if parent_id == store.root_commit_id() {
+            continue;
+        }
+        let paths = [file_path.to_owned()];
+        let mut records = store.get_copy_records(Some(paths.as_slice()), parent_id, commit_id)?;
+        while let Some(record) = records.try_next().await? {
+            if *record.target == *file_path {
+                let edge = if (ctx.is_in_domain)(parent_id)? {
+                    GraphEdge::direct(parent_id.clone())
+                } else {
+                    GraphEdge::missing(parent_id.clone())
+                };
+                copy_edges.push((edge, record.source));
+                break;
+            }
+        }
+    }
+    Ok((!copy_edges.is_empty()).then_some(copy_edges))
+}
 
-    Ok(())
+/// Returns the source of the parent commit, loading the file content at
+/// `file_path` if not yet loaded.
+async fn load_parent_source<'a>(
+    repo: &dyn Repo,
+    state: &'a mut AnnotationState,
+    parent_commit_id: &CommitId,
+    file_path: &RepoPath,
+) -> Result<&'a mut Source, BackendError> {
+    match state.commit_source_map.entry(parent_commit_id.clone()) {
+        hash_map::Entry::Occupied(entry) => Ok(entry.into_mut()),
+        hash_map::Entry::Vacant(entry) => {
+            let commit = repo.store().get_commit_async(entry.key()).await?;
+            Ok(entry.insert(Source::load(&commit, file_path).await?))
+        }
+    }
+}
+
+/// Moves the lines in common with the parent out of the `current_source`,
+/// returning the new line mappings for the parent.
+fn take_same_lines(current_source: &mut Source, parent_text: &[u8]) -> Vec<(usize, usize)> {
+    // For two versions of the same file, for all the lines in common,
+    // overwrite the new mapping in the results for the new commit. Let's
+    // say I have a file in commit A and commit B. We know that according to
+    // local line_map, in commit A, line 3 corresponds to line 7 of the
+    // starting file. Now, line 3 in Commit A corresponds to line 6 in
+    // commit B. Then, we update local line_map to say that "Commit B line 6
+    // goes to line 7 of the starting file". We repeat this for all lines in
+    // common in the two commits.
+    let mut current_lines = current_source.line_map.iter().copied().peekable();
+    let mut new_current_line_map = Vec::new();
+    let mut new_parent_line_map = Vec::new();
+    copy_same_lines_with(
+        &current_source.text,
+        parent_text,
+        |current_start, parent_start, count| {
+            new_current_line_map
+                .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
+            while let Some((current, starting)) =
+                current_lines.next_if(|&(cur, _)| cur < current_start + count)
+            {
+                let parent = parent_start + (current - current_start);
+                new_parent_line_map.push((parent, starting));
+            }
+        },
+    );
+    new_current_line_map.extend(current_lines);
+    current_source.line_map = new_current_line_map;
+    new_parent_line_map
+}
+
+/// Moves the lines changed from the parent out of the `current_source`,
+/// returning the new line mappings for the parent.
+fn take_changed_lines(current_source: &mut Source, parent_text: &[u8]) -> Vec<(usize, usize)> {
+    let mut current_lines = current_source.line_map.iter().copied().peekable();
+    let mut new_current_line_map = Vec::new();
+    let mut new_parent_line_map = Vec::new();
+    copy_changed_lines_with(&current_source.text, parent_text, |current, parent| {
+        new_current_line_map.extend(current_lines.peeking_take_while(|&(cur, _)| cur < current));
+        if let Some((_, starting)) = current_lines.next_if(|&(cur, _)| cur == current) {
+            new_parent_line_map.push((parent, starting));
+        }
+    });
+    new_current_line_map.extend(current_lines);
+    current_source.line_map = new_current_line_map;
+    new_parent_line_map
+}
+
+/// Merges the `new_parent_line_map` into the source of the parent commit,
+/// which should have been loaded.
+fn add_parent_line_map(
+    state: &mut AnnotationState,
+    parent_edge: &GraphEdge<CommitId>,
+    new_parent_line_map: Vec<(usize, usize)>,
+) {
+    let parent_commit_id = &parent_edge.target;
+    if parent_edge.is_missing() {
+        let new_lines = new_parent_line_map.iter().map(|&(_, starting)| starting);
+        state.resolved_lines.extend(new_lines);
+    }
+    let parent_source = state.commit_source_map.get_mut(parent_commit_id).unwrap();
+    parent_source.line_map = if parent_source.line_map.is_empty() {
+        new_parent_line_map
+    } else {
+        itertools::merge(parent_source.line_map.iter().copied(), new_parent_line_map).collect()
+    };
+    if parent_source.line_map.is_empty() {
+        state.commit_source_map.remove(parent_commit_id);
+    } else if parent_edge.is_missing() {
+        // If an omitted parent had the file, leave these lines unresolved.
+        // The origin of the unresolved lines is represented as
+        // Err(LineOrigin { parent_commit_id, parent_line_number }).
+        for &(parent_line_number, starting_line_number) in &parent_source.line_map {
+            state.original_line_map[starting_line_number] = Err(LineOrigin {
+                commit_id: parent_commit_id.clone(),
+                line_number: parent_line_number,
+            });
+        }
+        state.unresolved_roots.insert(parent_commit_id.clone());
+    }
 }
 
 /// For two files, calls `copy(current_start, parent_start, count)` for each
@@ -438,6 +665,32 @@ fn copy_same_lines_with(
     }
 }
 
+/// For two files, calls `copy(current, parent)` for each changed line, where
+/// `parent` is the line in the corresponding changed range of the parent file.
+/// Lines added without replacing any parent lines are skipped.
+fn copy_changed_lines_with(
+    current_contents: &[u8],
+    parent_contents: &[u8],
+    mut copy: impl FnMut(usize, usize),
+) {
+    let diff = ContentDiff::by_line([current_contents, parent_contents]);
+    let mut current_line_counter: usize = 0;
+    let mut parent_line_counter: usize = 0;
+    for hunk in diff.hunks() {
+        let current_count = count_lines(hunk.contents[0]);
+        let parent_count = count_lines(hunk.contents[1]);
+        if hunk.kind == DiffHunkKind::Different && parent_count > 0 {
+            // Spread the current lines evenly over the parent lines.
+            for i in 0..current_count {
+                let parent = parent_line_counter + i * parent_count / current_count;
+                copy(current_line_counter + i, parent);
+            }
+        }
+        current_line_counter += current_count;
+        parent_line_counter += parent_count;
+    }
+}
+
 /// Counts the number of lines in `text`, where the last line may not end with
 /// a newline character. Equivalent to `text.split_inclusive(|b| *b ==
 /// b'\n').count()`, but faster.
@@ -577,6 +577,21 @@ pub trait Backend: Any + Send + Sync + Debug {
         id: &FileId,
     ) -> BackendResult<Pin<Box<dyn AsyncRead + Send>>>;
 
+    /// Returns the size of a file in bytes.
+    ///
+    /// The default implementation reads the whole file. Backends which can
+    /// look up the size without reading the contents should override it.
+    async fn file_size(&self, path: &RepoPath, id: &FileId) -> BackendResult<u64> {
+        let reader = self.read_file(path, id).await?;
+        futures::io::copy(reader, &mut futures::io::sink())
+            .await
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })
+    }
+
     /// Writes the contents of the writer to the backend. Returns the ID of the
     /// written file.
     async fn write_file(
@@ -23,6 +23,7 @@ use std::task::ready;
 
 use futures::Stream;
 use futures::StreamExt as _;
+use futures::TryStreamExt as _;
 use futures::future::BoxFuture;
 use futures::future::ready;
 use futures::future::try_join_all;
@@ -35,11 +36,13 @@ use pollster::FutureExt as _;
 
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::CommitId;
 use crate::backend::CopyHistory;
 use crate::backend::CopyId;
 use crate::backend::CopyRecord;
 use crate::backend::TreeValue;
 use crate::dag_walk;
+use crate::matchers::Matcher;
 use crate::merge::Diff;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
@@ -49,6 +52,7 @@ use crate::merged_tree::TreeDiffEntry;
 use crate::merged_tree::TreeDiffStream;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::store::Store;
 
 /// A collection of CopyRecords.
 #[derive(Default, Debug)]
@@ -105,6 +109,67 @@ impl CopyRecords {
     }
 }
 
+/// Collects copy records from `root` to `head` commits. Only records whose
+/// targets match the `matcher` are returned.
+pub async fn get_copy_records(
+    store: &Store,
+    root: &CommitId,
+    head: &CommitId,
+    matcher: &dyn Matcher,
+) -> BackendResult<Vec<CopyRecord>> {
+    // TODO: teach backend about matching path prefixes?
+    let stream = store.get_copy_records(None, root, head)?;
+    // TODO: test record.source as well? should be AND-ed or OR-ed?
+    stream
+        .try_filter(|record| ready(matcher.matches(&record.target)))
+        .try_collect()
+        .await
+}
+
+/// Collects copy records from the `parents` to the `commit`. Only records
+/// whose targets match the `matcher` are included.
+pub async fn get_copy_records_from_parents(
+    store: &Store,
+    parent_ids: &[CommitId],
+    commit_id: &CommitId,
+    matcher: &dyn Matcher,
+) -> BackendResult<CopyRecords> {
+    let mut copy_records = CopyRecords::default();
+    for parent_id in parent_ids {
+        let records = get_copy_records(store, parent_id, commit_id, matcher).await?;
+        copy_records.add_records(records);
+    }
+    Ok(copy_records)
+}
+
+/// Collects copy records from the `root` commits to the `head` commit which
+/// apply to `from_tree`, a tree derived from the roots (such as the roots
+/// rebased onto the parents of the `head`.) Records whose source doesn't exist
+/// in `from_tree`, or whose target already exists in it, are dropped because
+/// they describe renames made by the rebase. Only records whose targets match
+/// the `matcher` are included.
+pub async fn get_copy_records_onto_tree(
+    store: &Store,
+    root_ids: impl IntoIterator<Item = &CommitId>,
+    head_id: &CommitId,
+    from_tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> BackendResult<CopyRecords> {
+    let mut copy_records = CopyRecords::default();
+    for root_id in root_ids {
+        let mut records = vec![];
+        for record in get_copy_records(store, root_id, head_id, matcher).await? {
+            if from_tree.path_value(&record.source).await?.is_present()
+                && from_tree.path_value(&record.target).await?.is_absent()
+            {
+                records.push(record);
+            }
+        }
+        copy_records.add_records(records);
+    }
+    Ok(copy_records)
+}
+
 /// Whether or not the source path was deleted.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CopyOperation {
@@ -47,6 +47,8 @@ use crate::commit::Commit;
 use crate::conflict_labels::ConflictLabels;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::materialize_tree_value;
+use crate::copies::CopiesTreeDiffEntry;
+use crate::copies::get_copy_records_from_parents;
 use crate::default_index::bit_set::AncestorsBitSet;
 use crate::diff::ContentDiff;
 use crate::diff::DiffHunkKind;
@@ -1426,7 +1428,7 @@ fn build_predicate_fn(
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.commits().entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
-                Ok(ext.matches_commit(&commit))
+                ext.matches_commit(&commit)
             })
         }
     }
@@ -1480,23 +1482,27 @@ async fn matches_diff_from_parent(
     let from_tree =
         rewrite::merge_commit_trees_no_resolve_without_repo(store, index, &parents).await?;
     let to_tree = commit.tree();
-    // TODO: handle copy tracking
-    let mut tree_diff = from_tree.diff_stream(&to_tree, files_matcher);
+    // Renamed files are compared against the source content so that only the
+    // lines changed since the rename are matched.
+    let copy_records =
+        get_copy_records_from_parents(store, commit.parent_ids(), commit.id(), files_matcher)
+            .await?;
+    let mut tree_diff = from_tree.diff_stream_with_copies(&to_tree, files_matcher, &copy_records);
     // TODO: Resolve values concurrently
-    while let Some(entry) = tree_diff.next().await {
-        let mut values = entry.values?;
-        values.before = resolve_file_values(store, &entry.path, values.before).await?;
+    while let Some(CopiesTreeDiffEntry { path, values }) = tree_diff.next().await {
+        let mut values = values?;
+        values.before = resolve_file_values(store, path.source(), values.before).await?;
         if !values.is_changed() {
             continue;
         }
         let conflict_labels = ConflictLabels::unlabeled();
         let left_future =
-            materialize_tree_value(store, &entry.path, values.before, &conflict_labels);
+            materialize_tree_value(store, path.source(), values.before, &conflict_labels);
         let right_future =
-            materialize_tree_value(store, &entry.path, values.after, &conflict_labels);
+            materialize_tree_value(store, path.target(), values.after, &conflict_labels);
         let (left_value, right_value) = futures::try_join!(left_future, right_future)?;
-        let left_contents = to_file_content(&entry.path, left_value).await?;
-        let right_contents = to_file_content(&entry.path, right_value).await?;
+        let left_contents = to_file_content(path.source(), left_value).await?;
+        let right_contents = to_file_content(path.target(), right_value).await?;
         let merge_options = store.merge_options();
         if diff_match_lines(
             &left_contents,
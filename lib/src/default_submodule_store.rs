@@ -14,38 +14,267 @@
 
 #![expect(missing_docs)]
 
+use std::ffi::OsStr;
+use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
 
+use bstr::ByteSlice as _;
+
+use crate::backend::CommitId;
+use crate::config::ConfigGetError;
+use crate::hex_util;
+use crate::object_id::ObjectId as _;
+use crate::settings::UserSettings;
+use crate::submodule_store::Submodule;
 use crate::submodule_store::SubmoduleStore;
+use crate::submodule_store::SubmoduleStoreError;
+use crate::submodule_store::SubmoduleStoreResult;
 
+/// Submodule store which keeps a bare clone of each submodule repository
+/// under `repos/`, and runs the `git` executable to fetch and check out them.
 #[derive(Debug)]
 pub struct DefaultSubmoduleStore {
-    #[expect(dead_code)]
     path: PathBuf,
+    git_executable_path: PathBuf,
+    allow_local_urls: bool,
 }
 
 impl DefaultSubmoduleStore {
     /// Load an existing SubmoduleStore
-    pub fn load(store_path: &Path) -> Self {
-        Self {
+    pub fn load(settings: &UserSettings, store_path: &Path) -> Result<Self, ConfigGetError> {
+        Ok(Self {
             path: store_path.to_path_buf(),
-        }
+            git_executable_path: settings.get("git.executable-path")?,
+            allow_local_urls: settings.get_bool("working-copy.allow-local-submodule-urls")?,
+        })
     }
 
-    pub fn init(store_path: &Path) -> Self {
-        Self {
-            path: store_path.to_path_buf(),
-        }
+    pub fn init(settings: &UserSettings, store_path: &Path) -> Result<Self, ConfigGetError> {
+        Self::load(settings, store_path)
     }
 
     pub fn name() -> &'static str {
         "default"
     }
+
+    fn repo_path(&self, submodule: &Submodule) -> PathBuf {
+        // Submodule names may contain path separators.
+        self.path
+            .join("repos")
+            .join(hex_util::encode_hex(submodule.name.as_bytes()))
+    }
+
+    fn validate_url(submodule: &Submodule) -> SubmoduleStoreResult<()> {
+        if submodule.url.starts_with("./") || submodule.url.starts_with("../") {
+            return Err(SubmoduleStoreError::RelativeUrl {
+                name: submodule.name.clone(),
+                url: submodule.url.clone(),
+            });
+        }
+        // The URL comes from .gitmodules, which is controlled by whoever
+        // wrote the commit. Don't let it be parsed as an option, or run
+        // arbitrary commands through the "ext" remote helper.
+        if submodule.url.starts_with('-') || submodule.url.starts_with("ext::") {
+            return Err(SubmoduleStoreError::InvalidUrl {
+                name: submodule.name.clone(),
+                url: submodule.url.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn git_command(&self, git_dir: Option<&Path>) -> Command {
+        let mut cmd = Command::new(&self.git_executable_path);
+        if let Some(git_dir) = git_dir {
+            cmd.arg("-C").arg(git_dir);
+        }
+        // Disable translation so the output can be parsed
+        cmd.env("LC_ALL", "C").stdin(Stdio::null());
+        cmd
+    }
+
+    fn run_git(
+        &self,
+        git_dir: Option<&Path>,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+    ) -> SubmoduleStoreResult<Output> {
+        let mut cmd = self.git_command(git_dir);
+        cmd.args(args);
+        self.output(cmd)
+    }
+
+    /// Runs `git` to fetch from the URL of a submodule. Like `git submodule`,
+    /// the URL is treated as untrusted, so only the transports which are
+    /// allowed by the user's `protocol.allow` settings can be used, and local
+    /// repositories are refused unless configured otherwise.
+    fn run_git_fetch(
+        &self,
+        git_dir: &Path,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+    ) -> SubmoduleStoreResult<Output> {
+        let file_allow = if self.allow_local_urls {
+            "protocol.file.allow=always"
+        } else {
+            "protocol.file.allow=user"
+        };
+        let mut cmd = self.git_command(Some(git_dir));
+        cmd.args(["-c", file_allow])
+            .args(args)
+            .env("GIT_PROTOCOL_FROM_USER", "0");
+        self.output(cmd)
+    }
+
+    fn output(&self, mut cmd: Command) -> SubmoduleStoreResult<Output> {
+        cmd.output()
+            .map_err(|source| SubmoduleStoreError::GitCommand {
+                path: self.git_executable_path.clone(),
+                source,
+            })
+    }
+
+    fn run_git_checked(
+        &self,
+        git_dir: Option<&Path>,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+    ) -> SubmoduleStoreResult<Output> {
+        check_output(self.run_git(git_dir, args)?)
+    }
 }
 
 impl SubmoduleStore for DefaultSubmoduleStore {
     fn name(&self) -> &str {
         Self::name()
     }
+
+    fn fetch(&self, submodule: &Submodule, commit_id: &CommitId) -> SubmoduleStoreResult<()> {
+        Self::validate_url(submodule)?;
+        let repo_path = self.repo_path(submodule);
+        if !repo_path.exists() {
+            fs::create_dir_all(&repo_path)?;
+            self.run_git_checked(
+                None,
+                [
+                    OsStr::new("init"),
+                    "--quiet".as_ref(),
+                    "--bare".as_ref(),
+                    repo_path.as_os_str(),
+                ],
+            )?;
+        }
+        check_output(self.run_git_fetch(
+            &repo_path,
+            [
+                "fetch",
+                "--quiet",
+                "--force",
+                "--",
+                &submodule.url,
+                "+refs/heads/*:refs/heads/*",
+                "+refs/tags/*:refs/tags/*",
+            ],
+        )?)?;
+        if self.has_commit(submodule, commit_id)? {
+            return Ok(());
+        }
+        // The pinned commit may not be reachable from any ref. Some servers
+        // allow fetching it directly.
+        let output = self.run_git_fetch(
+            &repo_path,
+            ["fetch", "--quiet", "--", &submodule.url, &commit_id.hex()],
+        )?;
+        if output.status.success() && self.has_commit(submodule, commit_id)? {
+            Ok(())
+        } else {
+            Err(SubmoduleStoreError::CommitNotFound {
+                name: submodule.name.clone(),
+                commit_id: commit_id.clone(),
+            })
+        }
+    }
+
+    fn has_commit(
+        &self,
+        submodule: &Submodule,
+        commit_id: &CommitId,
+    ) -> SubmoduleStoreResult<bool> {
+        let repo_path = self.repo_path(submodule);
+        if !repo_path.exists() {
+            return Ok(false);
+        }
+        let output = self.run_git(
+            Some(&repo_path),
+            ["cat-file", "-e", &format!("{}^{{commit}}", commit_id.hex())],
+        )?;
+        Ok(output.status.success())
+    }
+
+    fn check_out(
+        &self,
+        submodule: &Submodule,
+        commit_id: &CommitId,
+        dest: &Path,
+    ) -> SubmoduleStoreResult<()> {
+        Self::validate_url(submodule)?;
+        let repo_path = self.repo_path(submodule);
+        if !dest.join(".git").exists() {
+            fs::create_dir_all(dest)?;
+            self.run_git_checked(
+                None,
+                [
+                    OsStr::new("clone"),
+                    "--quiet".as_ref(),
+                    "--no-checkout".as_ref(),
+                    "--".as_ref(),
+                    repo_path.as_os_str(),
+                    dest.as_os_str(),
+                ],
+            )?;
+            self.run_git_checked(Some(dest), ["remote", "set-url", "origin", &submodule.url])?;
+        } else if self.checked_out_commit(dest)?.as_ref() == Some(commit_id) {
+            return Ok(());
+        } else {
+            self.run_git_checked(
+                Some(dest),
+                [
+                    OsStr::new("fetch"),
+                    "--quiet".as_ref(),
+                    "--".as_ref(),
+                    repo_path.as_os_str(),
+                    commit_id.hex().as_ref(),
+                ],
+            )?;
+        }
+        // Local changes in the submodule are never overwritten.
+        self.run_git_checked(
+            Some(dest),
+            ["checkout", "--quiet", "--detach", &commit_id.hex()],
+        )?;
+        Ok(())
+    }
+
+    fn checked_out_commit(&self, dest: &Path) -> SubmoduleStoreResult<Option<CommitId>> {
+        if !dest.join(".git").exists() {
+            return Ok(None);
+        }
+        let output = self.run_git(Some(dest), ["rev-parse", "--verify", "--quiet", "HEAD"])?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(CommitId::try_from_hex(output.stdout.trim()))
+    }
+}
+
+fn check_output(output: Output) -> SubmoduleStoreResult<Output> {
+    if output.status.success() {
+        Ok(output)
+    } else {
+        let stderr = output.stderr.to_str_lossy();
+        Err(SubmoduleStoreError::GitProcess(
+            stderr.trim_end().to_owned(),
+        ))
+    }
 }
@@ -97,12 +97,12 @@ pub async fn git_diff_part(
             };
         }
         MaterializedTreeValue::GitSubmodule(id) => {
-            // TODO: What should we actually do here?
-            mode = "040000";
+            // Same as `git diff --submodule=short`
+            mode = "160000";
             hash = id.hex();
             content = FileContent {
                 is_binary: false,
-                contents: BString::default(),
+                contents: format!("Subproject commit {hash}\n").into(),
             };
         }
         MaterializedTreeValue::FileConflict(file) => {
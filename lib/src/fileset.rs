@@ -15,16 +15,27 @@
 //! Functional language for selecting a set of paths.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::iter;
+use std::mem;
+use std::ops::RangeInclusive;
 use std::path;
 use std::slice;
 use std::sync::LazyLock;
 
+use either::Either;
+use futures::AsyncReadExt as _;
 use globset::Glob;
 use globset::GlobBuilder;
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::FileId;
+use crate::backend::TreeValue;
 use crate::dsl_util::collect_similar;
 use crate::fileset_parser;
 use crate::fileset_parser::BinaryOp;
@@ -39,18 +50,26 @@ use crate::fileset_parser::FunctionCallNode;
 use crate::fileset_parser::UnaryOp;
 use crate::matchers::DifferenceMatcher;
 use crate::matchers::EverythingMatcher;
+use crate::matchers::FileNamesMatcher;
+use crate::matchers::FilesInMatcher;
 use crate::matchers::FilesMatcher;
 use crate::matchers::GlobsMatcher;
 use crate::matchers::IntersectionMatcher;
 use crate::matchers::Matcher;
 use crate::matchers::NothingMatcher;
 use crate::matchers::PrefixMatcher;
+use crate::matchers::RegexMatcher;
 use crate::matchers::UnionMatcher;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::MergedTree;
 use crate::repo_path::RelativePathParseError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponentBuf;
 use crate::repo_path::RepoPathUiConverter;
 use crate::repo_path::UiPathParseError;
+use crate::settings::HumanByteSize;
+use crate::store::Store;
 
 /// Error occurred during file pattern parsing.
 #[derive(Debug, Error)]
@@ -67,6 +86,12 @@ pub enum FilePatternParseError {
     /// Failed to parse glob pattern.
     #[error(transparent)]
     GlobPattern(#[from] globset::Error),
+    /// Failed to parse regular expression.
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+    /// File name is empty or contains path separator.
+    #[error("Invalid file name {0:?}")]
+    InvalidFileName(String),
 }
 
 /// Basic pattern to match `RepoPath`.
@@ -90,9 +115,100 @@ pub enum FilePattern {
         /// Glob pattern relative to `dir`.
         pattern: Box<Glob>,
     },
-    // TODO: add more patterns:
-    // - FilesInPath: files in directory, non-recursively?
-    // - NameGlob or SuffixGlob: file name with glob?
+    /// Matches files in directory non-recursively.
+    FilesInPath(RepoPathBuf),
+    /// Matches files of the given name in directory recursively.
+    FileName {
+        /// Directory path where the files will be searched.
+        dir: RepoPathBuf,
+        /// File name to match.
+        name: RepoPathComponentBuf,
+    },
+    /// Matches file paths with regular expression.
+    FileRegex {
+        /// Prefix directory path where the `pattern` will be evaluated.
+        dir: RepoPathBuf,
+        /// Unanchored regular expression matched against paths relative to
+        /// `dir`.
+        pattern: Box<regex::bytes::Regex>,
+    },
+}
+
+// Regex doesn't implement Eq/Hash, so regex patterns are compared by source.
+impl PartialEq for FilePattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::FilePath(path1), Self::FilePath(path2))
+            | (Self::PrefixPath(path1), Self::PrefixPath(path2))
+            | (Self::FilesInPath(path1), Self::FilesInPath(path2)) => path1 == path2,
+            (
+                Self::FileGlob {
+                    dir: dir1,
+                    pattern: pattern1,
+                },
+                Self::FileGlob {
+                    dir: dir2,
+                    pattern: pattern2,
+                },
+            )
+            | (
+                Self::PrefixGlob {
+                    dir: dir1,
+                    pattern: pattern1,
+                },
+                Self::PrefixGlob {
+                    dir: dir2,
+                    pattern: pattern2,
+                },
+            ) => dir1 == dir2 && pattern1 == pattern2,
+            (
+                Self::FileName {
+                    dir: dir1,
+                    name: name1,
+                },
+                Self::FileName {
+                    dir: dir2,
+                    name: name2,
+                },
+            ) => dir1 == dir2 && name1 == name2,
+            (
+                Self::FileRegex {
+                    dir: dir1,
+                    pattern: pattern1,
+                },
+                Self::FileRegex {
+                    dir: dir2,
+                    pattern: pattern2,
+                },
+            ) => dir1 == dir2 && pattern1.as_str() == pattern2.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for FilePattern {}
+
+impl Hash for FilePattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Self::FilePath(path) | Self::PrefixPath(path) | Self::FilesInPath(path) => {
+                path.hash(state);
+            }
+            Self::FileGlob { dir, pattern } | Self::PrefixGlob { dir, pattern } => {
+                dir.hash(state);
+                pattern.hash(state);
+            }
+            Self::FileName { dir, name } => {
+                dir.hash(state);
+                name.hash(state);
+            }
+            Self::FileRegex { dir, pattern } => {
+                dir.hash(state);
+                pattern.as_str().hash(state);
+            }
+        }
+    }
 }
 
 impl FilePattern {
@@ -110,12 +226,12 @@ impl FilePattern {
         //   * file: exact file path
         //   * prefix: path prefix (files under directory recursively)
         //   * files-in: files in directory non-recursively
-        //   * name: file name component (or suffix match?)
+        //   * name: file name component in directory recursively
         //   * substring: substring match?
         // * string pattern syntax (+ case sensitivity?)
         //   * path: literal path (default) (default anchor: prefix)
         //   * glob: glob pattern (default anchor: file)
-        //   * regex?
+        //   * regex: regular expression (unanchored)
         match kind {
             "cwd" => Self::cwd_prefix_path(path_converter, input),
             "cwd-file" | "file" => Self::cwd_file_path(path_converter, input),
@@ -123,12 +239,18 @@ impl FilePattern {
             "cwd-glob-i" | "glob-i" => Self::cwd_file_glob_i(path_converter, input),
             "cwd-prefix-glob" | "prefix-glob" => Self::cwd_prefix_glob(path_converter, input),
             "cwd-prefix-glob-i" | "prefix-glob-i" => Self::cwd_prefix_glob_i(path_converter, input),
+            "cwd-files-in" | "files-in" => Self::cwd_files_in_path(path_converter, input),
+            "cwd-name" | "name" => Self::cwd_file_name(path_converter, input),
+            "cwd-regex" | "regex" => Self::cwd_file_regex(path_converter, input),
             "root" => Self::root_prefix_path(input),
             "root-file" => Self::root_file_path(input),
             "root-glob" => Self::root_file_glob(input),
             "root-glob-i" => Self::root_file_glob_i(input),
             "root-prefix-glob" => Self::root_prefix_glob(input),
             "root-prefix-glob-i" => Self::root_prefix_glob_i(input),
+            "root-files-in" => Self::root_files_in_path(input),
+            "root-name" => Self::root_file_name(input),
+            "root-regex" => Self::root_file_regex(input),
             _ => Err(FilePatternParseError::InvalidKind(kind.to_owned())),
         }
     }
@@ -192,6 +314,33 @@ impl FilePattern {
         Self::prefix_glob_at(dir, pattern, true)
     }
 
+    /// Pattern that matches files in cwd-relative directory non-recursively.
+    pub fn cwd_files_in_path(
+        path_converter: &RepoPathUiConverter,
+        input: impl AsRef<str>,
+    ) -> Result<Self, FilePatternParseError> {
+        let path = path_converter.parse_file_path(input.as_ref())?;
+        Ok(Self::FilesInPath(path))
+    }
+
+    /// Pattern that matches files of the given name in the cwd recursively.
+    pub fn cwd_file_name(
+        path_converter: &RepoPathUiConverter,
+        input: impl AsRef<str>,
+    ) -> Result<Self, FilePatternParseError> {
+        let dir = path_converter.parse_file_path("")?;
+        Self::file_name_at(dir, input.as_ref())
+    }
+
+    /// Pattern that matches cwd-relative file paths with regular expression.
+    pub fn cwd_file_regex(
+        path_converter: &RepoPathUiConverter,
+        input: impl AsRef<str>,
+    ) -> Result<Self, FilePatternParseError> {
+        let dir = path_converter.parse_file_path("")?;
+        Self::file_regex_at(dir, input.as_ref())
+    }
+
     /// Pattern that matches workspace-relative file (or exact) path.
     pub fn root_file_path(input: impl AsRef<str>) -> Result<Self, FilePatternParseError> {
         // TODO: Let caller pass in converter for root-relative paths too
@@ -235,6 +384,36 @@ impl FilePattern {
         Self::prefix_glob_at(dir, pattern, true)
     }
 
+    /// Pattern that matches files in workspace-relative directory
+    /// non-recursively.
+    pub fn root_files_in_path(input: impl AsRef<str>) -> Result<Self, FilePatternParseError> {
+        let path = RepoPathBuf::from_relative_path(input.as_ref())?;
+        Ok(Self::FilesInPath(path))
+    }
+
+    /// Pattern that matches files of the given name in the workspace
+    /// recursively.
+    pub fn root_file_name(input: impl AsRef<str>) -> Result<Self, FilePatternParseError> {
+        Self::file_name_at(RepoPathBuf::root(), input.as_ref())
+    }
+
+    /// Pattern that matches workspace-relative file paths with regular
+    /// expression.
+    pub fn root_file_regex(input: impl AsRef<str>) -> Result<Self, FilePatternParseError> {
+        Self::file_regex_at(RepoPathBuf::root(), input.as_ref())
+    }
+
+    fn file_name_at(dir: RepoPathBuf, input: &str) -> Result<Self, FilePatternParseError> {
+        let name = RepoPathComponentBuf::new(input)
+            .map_err(|_| FilePatternParseError::InvalidFileName(input.to_owned()))?;
+        Ok(Self::FileName { dir, name })
+    }
+
+    fn file_regex_at(dir: RepoPathBuf, input: &str) -> Result<Self, FilePatternParseError> {
+        let pattern = Box::new(regex::bytes::Regex::new(input)?);
+        Ok(Self::FileRegex { dir, pattern })
+    }
+
     fn file_glob_at(
         dir: RepoPathBuf,
         input: &str,
@@ -275,7 +454,11 @@ impl FilePattern {
         match self {
             Self::FilePath(path) => Some(path),
             Self::PrefixPath(path) => Some(path),
-            Self::FileGlob { .. } | Self::PrefixGlob { .. } => None,
+            Self::FileGlob { .. }
+            | Self::PrefixGlob { .. }
+            | Self::FilesInPath(_)
+            | Self::FileName { .. }
+            | Self::FileRegex { .. } => None,
         }
     }
 }
@@ -322,8 +505,131 @@ fn split_glob_path_i(input: &str) -> (&str, &str) {
     input.split_at(prefix_len)
 }
 
+/// Property of a file which can only be determined by looking at a tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FilePredicate {
+    /// Matches executable files.
+    Executable,
+    /// Matches symbolic links.
+    Symlink,
+    /// Matches Git submodules.
+    Submodule,
+    /// Matches paths with unresolved conflicts.
+    Conflicted,
+    /// Matches files whose size in bytes is in the range.
+    Size(RangeInclusive<u64>),
+    /// Matches files which look like binary files, i.e. which contain a NUL
+    /// byte near the beginning.
+    Binary,
+}
+
+impl FilePredicate {
+    /// Number of bytes to look at when checking if a file is binary. This is
+    /// the same limit Git uses.
+    const BINARY_PROBE_LIMIT: u64 = 8000;
+
+    /// Returns true if the `value` at `path` has this property. A conflicted
+    /// value has the property if any of its sides has.
+    async fn matches(
+        &self,
+        store: &Store,
+        path: &RepoPath,
+        value: &MergedTreeValue,
+    ) -> BackendResult<bool> {
+        let file_ids = value.adds().filter_map(|value| match value {
+            Some(TreeValue::File { id, .. }) => Some(id),
+            _ => None,
+        });
+        match self {
+            Self::Executable => Ok(value.adds().any(|value| {
+                matches!(
+                    value,
+                    Some(TreeValue::File {
+                        executable: true,
+                        ..
+                    })
+                )
+            })),
+            Self::Symlink => Ok(value
+                .adds()
+                .any(|value| matches!(value, Some(TreeValue::Symlink(_))))),
+            Self::Submodule => Ok(value
+                .adds()
+                .any(|value| matches!(value, Some(TreeValue::GitSubmodule(_))))),
+            Self::Conflicted => Ok(!value.is_resolved()),
+            Self::Size(range) => {
+                for id in file_ids {
+                    let size = store.file_size(path, id).await?;
+                    if range.contains(&size) {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Self::Binary => {
+                for id in file_ids {
+                    let mut is_binary = false;
+                    read_file_prefix(store, path, id, Self::BINARY_PROBE_LIMIT, |chunk| {
+                        is_binary |= chunk.contains(&0);
+                    })
+                    .await?;
+                    if is_binary {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Reads up to `limit` bytes of the file, passing each chunk to `f`.
+async fn read_file_prefix(
+    store: &Store,
+    path: &RepoPath,
+    id: &FileId,
+    limit: u64,
+    mut f: impl FnMut(&[u8]),
+) -> BackendResult<()> {
+    let to_backend_error = |err: std::io::Error| BackendError::ReadFile {
+        path: path.to_owned(),
+        id: id.clone(),
+        source: err.into(),
+    };
+    let mut reader = store.read_file(path, id).await?.take(limit);
+    let mut buf = vec![0; 8192];
+    loop {
+        let n = reader.read(&mut buf).await.map_err(to_backend_error)?;
+        if n == 0 {
+            return Ok(());
+        }
+        f(&buf[..n]);
+    }
+}
+
+/// Parses a comparison such as `>1M` into the range of sizes it matches.
+fn parse_size_range(text: &str) -> Result<RangeInclusive<u64>, &'static str> {
+    let (op, size) = [">=", "<=", ">", "<"]
+        .iter()
+        .find_map(|op| Some((*op, text.strip_prefix(op)?)))
+        .ok_or("Expected a comparison such as `>1M`")?;
+    let HumanByteSize(size) = size.parse()?;
+    let range = match op {
+        ">=" => size..=u64::MAX,
+        "<=" => 0..=size,
+        ">" => size.saturating_add(1)..=u64::MAX,
+        "<" => {
+            0..=size
+                .checked_sub(1)
+                .ok_or("No file is smaller than 0 bytes")?
+        }
+        _ => unreachable!(),
+    };
+    Ok(range)
+}
+
 /// AST-level representation of the fileset expression.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum FilesetExpression {
     /// Matches nothing.
     None,
@@ -425,12 +731,329 @@ impl FilesetExpression {
         })
     }
 
-    /// Transforms the expression tree to `Matcher` object.
+    /// Transforms the expression tree to `Matcher` object. The expression is
+    /// simplified by `optimize()` first.
     pub fn to_matcher(&self) -> Box<dyn Matcher> {
-        build_union_matcher(self.as_union_all())
+        let expression = optimize(self.clone());
+        build_union_matcher(expression.as_union_all())
+    }
+}
+
+/// Fileset expression which may contain predicates such as `executable()`.
+///
+/// Predicates depend on the files' metadata or contents, so they have to be
+/// resolved against trees by `resolve_predicates()` before a matcher can be
+/// built.
+#[derive(Clone, Debug)]
+pub enum UnresolvedFilesetExpression {
+    /// Expression without predicates.
+    Resolved(FilesetExpression),
+    /// Matches files with the given property.
+    Predicate(FilePredicate),
+    /// Matches any of the expressions.
+    UnionAll(Vec<Self>),
+    /// Matches both expressions.
+    Intersection(Box<Self>, Box<Self>),
+    /// Matches the first expression, but not the second expression.
+    Difference(Box<Self>, Box<Self>),
+}
+
+impl UnresolvedFilesetExpression {
+    /// Expression that matches files with the given property.
+    pub fn predicate(predicate: FilePredicate) -> Self {
+        Self::Predicate(predicate)
+    }
+
+    /// Expression that matches any of the given `expressions`.
+    pub fn union_all(expressions: Vec<Self>) -> Self {
+        if expressions.iter().all(|expr| expr.as_resolved().is_some()) {
+            let expressions = expressions
+                .into_iter()
+                .map(|expr| match expr {
+                    Self::Resolved(expr) => expr,
+                    _ => unreachable!(),
+                })
+                .collect();
+            return Self::Resolved(FilesetExpression::union_all(expressions));
+        }
+        Self::UnionAll(expressions)
+    }
+
+    /// Expression that matches both `self` and `other`.
+    pub fn intersection(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Resolved(expr1), Self::Resolved(expr2)) => {
+                Self::Resolved(expr1.intersection(expr2))
+            }
+            (expr1, expr2) => Self::Intersection(Box::new(expr1), Box::new(expr2)),
+        }
+    }
+
+    /// Expression that matches `self` but not `other`.
+    pub fn difference(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Resolved(expr1), Self::Resolved(expr2)) => {
+                Self::Resolved(expr1.difference(expr2))
+            }
+            (expr1, expr2) => Self::Difference(Box::new(expr1), Box::new(expr2)),
+        }
+    }
+
+    /// Returns the expression if it doesn't contain predicates.
+    pub fn as_resolved(&self) -> Option<&FilesetExpression> {
+        match self {
+            Self::Resolved(expr) => Some(expr),
+            _ => None,
+        }
+    }
+
+    fn dfs_pre(&self) -> impl Iterator<Item = &Self> {
+        let mut stack: Vec<&Self> = vec![self];
+        iter::from_fn(move || {
+            let expr = stack.pop()?;
+            match expr {
+                Self::Resolved(_) | Self::Predicate(_) => {}
+                Self::UnionAll(exprs) => stack.extend(exprs.iter().rev()),
+                Self::Intersection(expr1, expr2) | Self::Difference(expr1, expr2) => {
+                    stack.push(expr2);
+                    stack.push(expr1);
+                }
+            }
+            Some(expr)
+        })
+    }
+
+    /// Iterates literal paths recursively from this expression.
+    pub fn explicit_paths(&self) -> impl Iterator<Item = &RepoPath> {
+        self.dfs_pre()
+            .filter_map(|expr| expr.as_resolved())
+            .flat_map(|expr| expr.explicit_paths())
+    }
+
+    /// Returns true if the expression contains predicates.
+    pub fn has_predicates(&self) -> bool {
+        self.as_resolved().is_none()
+    }
+
+    /// Rewrites the parts of the expression which don't contain predicates to
+    /// optimized form.
+    pub fn optimize(self) -> Self {
+        match self {
+            Self::Resolved(expr) => Self::Resolved(optimize(expr)),
+            Self::Predicate(_) => self,
+            Self::UnionAll(exprs) => {
+                // Merge the resolved alternatives so they can be folded together.
+                let (unresolved, resolved): (Vec<_>, Vec<_>) = exprs
+                    .into_iter()
+                    .map(Self::optimize)
+                    .partition_map(|expr| match expr {
+                        Self::Resolved(expr) => Either::Right(expr),
+                        expr => Either::Left(expr),
+                    });
+                let resolved = match optimize(FilesetExpression::union_all(resolved)) {
+                    FilesetExpression::None => None,
+                    expr => Some(Self::Resolved(expr)),
+                };
+                Self::union_all(unresolved.into_iter().chain(resolved).collect())
+            }
+            Self::Intersection(expr1, expr2) => {
+                Self::Intersection(Box::new(expr1.optimize()), Box::new(expr2.optimize()))
+            }
+            Self::Difference(expr1, expr2) => {
+                Self::Difference(Box::new(expr1.optimize()), Box::new(expr2.optimize()))
+            }
+        }
+    }
+
+    /// Replaces predicates such as `executable()` with the paths in `trees`
+    /// which match them. A path matches if it does in any of the trees.
+    pub async fn resolve_predicates(
+        &self,
+        trees: &[MergedTree],
+    ) -> BackendResult<FilesetExpression> {
+        if let Some(expression) = self.as_resolved() {
+            return Ok(expression.clone());
+        }
+        let mut predicates: Vec<(&FilePredicate, Vec<RepoPathBuf>)> = vec![];
+        for expr in self.dfs_pre() {
+            if let Self::Predicate(predicate) = expr
+                && !predicates.iter().any(|(p, _)| *p == predicate)
+            {
+                predicates.push((predicate, vec![]));
+            }
+        }
+        // Only paths which can be matched by the whole expression need to be
+        // checked.
+        let candidates = self.predicates_as_all().to_matcher();
+        for tree in trees {
+            for (path, value) in tree.entries_matching(candidates.as_ref()) {
+                let value = value?;
+                for (predicate, paths) in &mut predicates {
+                    if predicate.matches(tree.store(), &path, &value).await? {
+                        paths.push(path.clone());
+                    }
+                }
+            }
+        }
+        Ok(self.map_predicates(&|predicate| {
+            let (_, paths) = predicates.iter().find(|(p, _)| *p == predicate).unwrap();
+            let paths = paths.iter().sorted().dedup().cloned();
+            FilesetExpression::union_all(paths.map(FilesetExpression::file_path).collect())
+        }))
+    }
+
+    /// Returns an expression without predicates which matches at least the
+    /// paths this expression does.
+    fn predicates_as_all(&self) -> FilesetExpression {
+        match self {
+            Self::Resolved(expr) => expr.clone(),
+            Self::Predicate(_) => FilesetExpression::All,
+            Self::UnionAll(exprs) => {
+                FilesetExpression::UnionAll(exprs.iter().map(Self::predicates_as_all).collect())
+            }
+            Self::Intersection(expr1, expr2) => expr1
+                .predicates_as_all()
+                .intersection(expr2.predicates_as_all()),
+            // Excluding more paths than the predicates would is wrong, so
+            // the subtrahend is dropped if it has predicates.
+            Self::Difference(expr1, expr2) => match expr2.as_resolved() {
+                Some(expr2) => expr1.predicates_as_all().difference(expr2.clone()),
+                None => expr1.predicates_as_all(),
+            },
+        }
+    }
+
+    fn map_predicates(
+        &self,
+        f: &impl Fn(&FilePredicate) -> FilesetExpression,
+    ) -> FilesetExpression {
+        match self {
+            Self::Resolved(expr) => expr.clone(),
+            Self::Predicate(predicate) => f(predicate),
+            Self::UnionAll(exprs) => FilesetExpression::UnionAll(
+                exprs.iter().map(|expr| expr.map_predicates(f)).collect(),
+            ),
+            Self::Intersection(expr1, expr2) => expr1
+                .map_predicates(f)
+                .intersection(expr2.map_predicates(f)),
+            Self::Difference(expr1, expr2) => {
+                expr1.map_predicates(f).difference(expr2.map_predicates(f))
+            }
+        }
+    }
+}
+
+impl From<FilesetExpression> for UnresolvedFilesetExpression {
+    fn from(expression: FilesetExpression) -> Self {
+        Self::Resolved(expression)
     }
 }
 
+/// Rewrites the given `expression` tree to reduce the number and depth of the
+/// matchers built from it. Returns new tree.
+///
+/// Nested unions are flattened so patterns of the same kind end up in a single
+/// matcher, `none()` and `all()` are folded, and double negations are
+/// eliminated. Patterns covered by a path prefix in the same union are
+/// removed.
+pub fn optimize(expression: FilesetExpression) -> FilesetExpression {
+    match expression {
+        FilesetExpression::None | FilesetExpression::All | FilesetExpression::Pattern(_) => {
+            expression
+        }
+        FilesetExpression::UnionAll(exprs) => {
+            let mut flattened = Vec::with_capacity(exprs.len());
+            for expr in exprs {
+                match optimize(expr) {
+                    FilesetExpression::None => {}
+                    FilesetExpression::All => return FilesetExpression::All,
+                    FilesetExpression::UnionAll(exprs) => flattened.extend(exprs),
+                    expr => flattened.push(expr),
+                }
+            }
+            FilesetExpression::union_all(fold_subsumed_patterns(flattened))
+        }
+        FilesetExpression::Intersection(expr1, expr2) => {
+            fold_intersection(optimize(*expr1), optimize(*expr2))
+        }
+        FilesetExpression::Difference(expr1, expr2) => {
+            fold_difference(optimize(*expr1), optimize(*expr2))
+        }
+    }
+}
+
+/// Builds `expr1 & expr2` from optimized expressions.
+fn fold_intersection(expr1: FilesetExpression, expr2: FilesetExpression) -> FilesetExpression {
+    match (expr1, expr2) {
+        (FilesetExpression::None, _) | (_, FilesetExpression::None) => FilesetExpression::None,
+        (FilesetExpression::All, expr) | (expr, FilesetExpression::All) => expr,
+        // x & ~y => x ~ y
+        (expr1, FilesetExpression::Difference(all, expr2))
+            if matches!(*all, FilesetExpression::All) =>
+        {
+            fold_difference(expr1, *expr2)
+        }
+        (FilesetExpression::Difference(all, expr1), expr2)
+            if matches!(*all, FilesetExpression::All) =>
+        {
+            fold_difference(expr2, *expr1)
+        }
+        (expr1, expr2) => expr1.intersection(expr2),
+    }
+}
+
+/// Builds `expr1 ~ expr2` from optimized expressions.
+fn fold_difference(expr1: FilesetExpression, expr2: FilesetExpression) -> FilesetExpression {
+    match (expr1, expr2) {
+        (FilesetExpression::None, _) | (_, FilesetExpression::All) => FilesetExpression::None,
+        (expr, FilesetExpression::None) => expr,
+        // x ~ ~y => x & y
+        (expr1, FilesetExpression::Difference(all, expr2))
+            if matches!(*all, FilesetExpression::All) =>
+        {
+            fold_intersection(expr1, *expr2)
+        }
+        (expr1, expr2) => expr1.difference(expr2),
+    }
+}
+
+/// Removes patterns from the union `expressions` which only match paths under
+/// a path prefix in the same union. Duplicated literal paths are removed as
+/// well.
+fn fold_subsumed_patterns(expressions: Vec<FilesetExpression>) -> Vec<FilesetExpression> {
+    let prefixes: HashSet<RepoPathBuf> = expressions
+        .iter()
+        .filter_map(|expr| match expr {
+            FilesetExpression::Pattern(FilePattern::PrefixPath(path)) => Some(path.clone()),
+            _ => None,
+        })
+        .collect();
+    let is_covered = |path: &RepoPath| path.ancestors().any(|dir| prefixes.contains(dir));
+    let mut seen_paths = HashSet::new();
+    expressions
+        .into_iter()
+        .filter(|expr| {
+            let FilesetExpression::Pattern(pattern) = expr else {
+                return true;
+            };
+            match pattern {
+                FilePattern::PrefixPath(path) => {
+                    let is_nested = path.parent().is_some_and(is_covered);
+                    !is_nested && seen_paths.insert((path.clone(), true))
+                }
+                FilePattern::FilePath(path) => {
+                    !is_covered(path) && seen_paths.insert((path.clone(), false))
+                }
+                FilePattern::FileGlob { dir, .. }
+                | FilePattern::PrefixGlob { dir, .. }
+                | FilePattern::FilesInPath(dir)
+                | FilePattern::FileName { dir, .. }
+                | FilePattern::FileRegex { dir, .. } => !is_covered(dir),
+            }
+        })
+        .collect()
+}
+
 /// Transforms the union `expressions` to `Matcher` object.
 ///
 /// Since `Matcher` typically accepts a set of patterns to be OR-ed, this
@@ -440,6 +1063,9 @@ fn build_union_matcher(expressions: &[FilesetExpression]) -> Box<dyn Matcher> {
     let mut prefix_paths = Vec::new();
     let mut file_globs = GlobsMatcher::builder().prefix_paths(false);
     let mut prefix_globs = GlobsMatcher::builder().prefix_paths(true);
+    let mut files_in_paths = Vec::new();
+    let mut file_names = Vec::new();
+    let mut file_regexes = Vec::new();
     let mut matchers: Vec<Option<Box<dyn Matcher>>> = Vec::new();
     for expr in expressions {
         let matcher: Box<dyn Matcher> = match expr {
@@ -452,6 +1078,11 @@ fn build_union_matcher(expressions: &[FilesetExpression]) -> Box<dyn Matcher> {
                     FilePattern::PrefixPath(path) => prefix_paths.push(path),
                     FilePattern::FileGlob { dir, pattern } => file_globs.add(dir, pattern),
                     FilePattern::PrefixGlob { dir, pattern } => prefix_globs.add(dir, pattern),
+                    FilePattern::FilesInPath(path) => files_in_paths.push(path),
+                    FilePattern::FileName { dir, name } => file_names.push((dir, name)),
+                    FilePattern::FileRegex { dir, pattern } => {
+                        file_regexes.push((dir, pattern.as_ref()));
+                    }
                 }
                 continue;
             }
@@ -483,6 +1114,15 @@ fn build_union_matcher(expressions: &[FilesetExpression]) -> Box<dyn Matcher> {
     if !prefix_globs.is_empty() {
         matchers.push(Some(Box::new(prefix_globs.build())));
     }
+    if !files_in_paths.is_empty() {
+        matchers.push(Some(Box::new(FilesInMatcher::new(files_in_paths))));
+    }
+    if !file_names.is_empty() {
+        matchers.push(Some(Box::new(FileNamesMatcher::new(file_names))));
+    }
+    if !file_regexes.is_empty() {
+        matchers.push(Some(Box::new(RegexMatcher::new(file_regexes))));
+    }
     union_all_matchers(&mut matchers)
 }
 
@@ -506,32 +1146,76 @@ fn union_all_matchers(matchers: &mut [Option<Box<dyn Matcher>>]) -> Box<dyn Matc
 
 type FilesetFunction = fn(
     &mut FilesetDiagnostics,
-    &RepoPathUiConverter,
+    &ResolveContext,
     &FunctionCallNode,
-) -> FilesetParseResult<FilesetExpression>;
+) -> FilesetParseResult<UnresolvedFilesetExpression>;
 
 static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, FilesetFunction>> = LazyLock::new(|| {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
     let mut map: HashMap<&str, FilesetFunction> = HashMap::new();
-    map.insert("none", |_diagnostics, _path_converter, function| {
+    map.insert("none", |_diagnostics, _context, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::none().into())
+    });
+    map.insert("all", |_diagnostics, _context, function| {
         function.expect_no_arguments()?;
-        Ok(FilesetExpression::none())
+        Ok(FilesetExpression::all().into())
     });
-    map.insert("all", |_diagnostics, _path_converter, function| {
+    map.insert("executable", |_diagnostics, context, function| {
         function.expect_no_arguments()?;
-        Ok(FilesetExpression::all())
+        expect_predicate(context, function, FilePredicate::Executable)
+    });
+    map.insert("symlinks", |_diagnostics, context, function| {
+        function.expect_no_arguments()?;
+        expect_predicate(context, function, FilePredicate::Symlink)
+    });
+    map.insert("submodules", |_diagnostics, context, function| {
+        function.expect_no_arguments()?;
+        expect_predicate(context, function, FilePredicate::Submodule)
+    });
+    map.insert("conflicted", |_diagnostics, context, function| {
+        function.expect_no_arguments()?;
+        expect_predicate(context, function, FilePredicate::Conflicted)
+    });
+    map.insert("size", |_diagnostics, context, function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let text = fileset_parser::expect_string_literal("size comparison", arg)?;
+        let range = parse_size_range(text).map_err(|message| {
+            FilesetParseError::expression(format!("Invalid size comparison: {message}"), arg.span)
+        })?;
+        expect_predicate(context, function, FilePredicate::Size(range))
+    });
+    map.insert("binary", |_diagnostics, context, function| {
+        function.expect_no_arguments()?;
+        expect_predicate(context, function, FilePredicate::Binary)
     });
     map
 });
 
+/// Turns `predicate` into an expression if the context supports predicates.
+fn expect_predicate(
+    context: &ResolveContext,
+    function: &FunctionCallNode,
+    predicate: FilePredicate,
+) -> FilesetParseResult<UnresolvedFilesetExpression> {
+    if context.allow_predicates {
+        Ok(UnresolvedFilesetExpression::predicate(predicate))
+    } else {
+        Err(FilesetParseError::expression(
+            format!("Function `{}` can't be used in this context", function.name),
+            function.name_span,
+        ))
+    }
+}
+
 fn resolve_function(
     diagnostics: &mut FilesetDiagnostics,
-    path_converter: &RepoPathUiConverter,
+    context: &ResolveContext,
     function: &FunctionCallNode,
-) -> FilesetParseResult<FilesetExpression> {
+) -> FilesetParseResult<UnresolvedFilesetExpression> {
     if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
-        func(diagnostics, path_converter, function)
+        func(diagnostics, context, function)
     } else {
         Err(FilesetParseError::new(
             FilesetParseErrorKind::NoSuchFunction {
@@ -543,11 +1227,18 @@ fn resolve_function(
     }
 }
 
+/// Parse context plus whether functions like `executable()` are allowed.
+struct ResolveContext<'a> {
+    parse_context: &'a FilesetParseContext<'a>,
+    allow_predicates: bool,
+}
+
 fn resolve_expression(
     diagnostics: &mut FilesetDiagnostics,
-    path_converter: &RepoPathUiConverter,
+    context: &ResolveContext,
     node: &ExpressionNode,
-) -> FilesetParseResult<FilesetExpression> {
+) -> FilesetParseResult<UnresolvedFilesetExpression> {
+    let path_converter = context.parse_context.path_converter;
     fileset_parser::catch_aliases(diagnostics, node, |diagnostics, node| {
         let wrap_pattern_error =
             |err| FilesetParseError::expression("Invalid file pattern", node.span).with_source(err);
@@ -555,28 +1246,31 @@ fn resolve_expression(
             ExpressionKind::Identifier(name) => {
                 let pattern = FilePattern::cwd_prefix_glob(path_converter, name)
                     .map_err(wrap_pattern_error)?;
-                Ok(FilesetExpression::pattern(pattern))
+                Ok(FilesetExpression::pattern(pattern).into())
             }
             ExpressionKind::String(name) => {
                 let pattern = FilePattern::cwd_prefix_glob(path_converter, name)
                     .map_err(wrap_pattern_error)?;
-                Ok(FilesetExpression::pattern(pattern))
+                Ok(FilesetExpression::pattern(pattern).into())
             }
             ExpressionKind::Pattern(pattern) => {
                 let value = fileset_parser::expect_string_literal("string", &pattern.value)?;
                 let pattern = FilePattern::from_str_kind(path_converter, value, pattern.name)
                     .map_err(wrap_pattern_error)?;
-                Ok(FilesetExpression::pattern(pattern))
+                Ok(FilesetExpression::pattern(pattern).into())
             }
             ExpressionKind::Unary(op, arg_node) => {
-                let arg = resolve_expression(diagnostics, path_converter, arg_node)?;
+                let arg = resolve_expression(diagnostics, context, arg_node)?;
                 match op {
-                    UnaryOp::Negate => Ok(FilesetExpression::all().difference(arg)),
+                    UnaryOp::Negate => {
+                        Ok(UnresolvedFilesetExpression::from(FilesetExpression::all())
+                            .difference(arg))
+                    }
                 }
             }
             ExpressionKind::Binary(op, lhs_node, rhs_node) => {
-                let lhs = resolve_expression(diagnostics, path_converter, lhs_node)?;
-                let rhs = resolve_expression(diagnostics, path_converter, rhs_node)?;
+                let lhs = resolve_expression(diagnostics, context, lhs_node)?;
+                let rhs = resolve_expression(diagnostics, context, rhs_node)?;
                 match op {
                     BinaryOp::Intersection => Ok(lhs.intersection(rhs)),
                     BinaryOp::Difference => Ok(lhs.difference(rhs)),
@@ -585,12 +1279,12 @@ fn resolve_expression(
             ExpressionKind::UnionAll(nodes) => {
                 let expressions = nodes
                     .iter()
-                    .map(|node| resolve_expression(diagnostics, path_converter, node))
+                    .map(|node| resolve_expression(diagnostics, context, node))
                     .try_collect()?;
-                Ok(FilesetExpression::union_all(expressions))
+                Ok(UnresolvedFilesetExpression::union_all(expressions))
             }
             ExpressionKind::FunctionCall(function) => {
-                resolve_function(diagnostics, path_converter, function)
+                resolve_function(diagnostics, context, function)
             }
             ExpressionKind::AliasExpanded(..) => unreachable!(),
         }
@@ -606,6 +1300,13 @@ pub struct FilesetParseContext<'a> {
     pub path_converter: &'a RepoPathUiConverter,
 }
 
+fn expect_resolved(expression: UnresolvedFilesetExpression) -> FilesetExpression {
+    match expression {
+        UnresolvedFilesetExpression::Resolved(expression) => expression,
+        _ => unreachable!("predicates should have been rejected by the parser"),
+    }
+}
+
 /// Parses text into `FilesetExpression` without bare string fallback.
 pub fn parse(
     diagnostics: &mut FilesetDiagnostics,
@@ -614,8 +1315,27 @@ pub fn parse(
 ) -> FilesetParseResult<FilesetExpression> {
     let node = fileset_parser::parse_program(text)?;
     let node = fileset_parser::expand_aliases(node, context.aliases_map)?;
-    // TODO: add basic tree substitution pass to eliminate redundant expressions
-    resolve_expression(diagnostics, context.path_converter, &node)
+    let context = ResolveContext {
+        parse_context: context,
+        allow_predicates: false,
+    };
+    resolve_expression(diagnostics, &context, &node).map(expect_resolved)
+}
+
+/// Parses text into `UnresolvedFilesetExpression` without bare string
+/// fallback. Unlike `parse()`, functions like `executable()` are allowed.
+pub fn parse_with_predicates(
+    diagnostics: &mut FilesetDiagnostics,
+    text: &str,
+    context: &FilesetParseContext,
+) -> FilesetParseResult<UnresolvedFilesetExpression> {
+    let node = fileset_parser::parse_program(text)?;
+    let node = fileset_parser::expand_aliases(node, context.aliases_map)?;
+    let context = ResolveContext {
+        parse_context: context,
+        allow_predicates: true,
+    };
+    resolve_expression(diagnostics, &context, &node)
 }
 
 /// Parses text into `FilesetExpression` with bare string fallback.
@@ -629,8 +1349,27 @@ pub fn parse_maybe_bare(
 ) -> FilesetParseResult<FilesetExpression> {
     let node = fileset_parser::parse_program_or_bare_string(text)?;
     let node = fileset_parser::expand_aliases(node, context.aliases_map)?;
-    // TODO: add basic tree substitution pass to eliminate redundant expressions
-    resolve_expression(diagnostics, context.path_converter, &node)
+    let context = ResolveContext {
+        parse_context: context,
+        allow_predicates: false,
+    };
+    resolve_expression(diagnostics, &context, &node).map(expect_resolved)
+}
+
+/// Parses text into `UnresolvedFilesetExpression` with bare string fallback.
+/// Unlike `parse_maybe_bare()`, functions like `executable()` are allowed.
+pub fn parse_maybe_bare_with_predicates(
+    diagnostics: &mut FilesetDiagnostics,
+    text: &str,
+    context: &FilesetParseContext,
+) -> FilesetParseResult<UnresolvedFilesetExpression> {
+    let node = fileset_parser::parse_program_or_bare_string(text)?;
+    let node = fileset_parser::expand_aliases(node, context.aliases_map)?;
+    let context = ResolveContext {
+        parse_context: context,
+        allow_predicates: true,
+    };
+    resolve_expression(diagnostics, &context, &node)
 }
 
 #[cfg(test)]
@@ -730,6 +1469,63 @@ mod tests {
             parse("root-file:bar")?,
             @r#"Pattern(FilePath("bar"))"#);
 
+        // files-in patterns
+        insta::assert_debug_snapshot!(
+            parse("files-in:.")?,
+            @r#"Pattern(FilesInPath("cur"))"#);
+        insta::assert_debug_snapshot!(
+            parse("cwd-files-in:foo")?,
+            @r#"Pattern(FilesInPath("cur/foo"))"#);
+        insta::assert_debug_snapshot!(
+            parse("root-files-in:foo")?,
+            @r#"Pattern(FilesInPath("foo"))"#);
+        assert!(parse("root-files-in:..").is_err());
+
+        // name patterns
+        insta::assert_debug_snapshot!(
+            parse("name:BUILD.bazel")?, @r#"
+        Pattern(
+            FileName {
+                dir: "cur",
+                name: "BUILD.bazel",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("root-name:BUILD.bazel")?, @r#"
+        Pattern(
+            FileName {
+                dir: "",
+                name: "BUILD.bazel",
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("name:foo/bar").unwrap_err().kind(),
+            @r#"Expression("Invalid file pattern")"#);
+        assert!(parse("name:''").is_err());
+
+        // regex patterns
+        insta::assert_debug_snapshot!(
+            parse(r#"regex:'\.rs$'"#)?, @r#"
+        Pattern(
+            FileRegex {
+                dir: "cur",
+                pattern: Regex("\\.rs$"),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse(r#"root-regex:'^src/.*\.rs$'"#)?, @r#"
+        Pattern(
+            FileRegex {
+                dir: "",
+                pattern: Regex("^src/.*\\.rs$"),
+            },
+        )
+        "#);
+        assert!(parse("regex:'('").is_err());
+
         insta::assert_debug_snapshot!(
             parse("file:(foo|bar)").unwrap_err().kind(),
             @r#"Expression("Expected string")"#);
@@ -1217,6 +2013,35 @@ mod tests {
             ],
         }
         "#);
+
+        // Predicates are only available if the caller can resolve them
+        insta::assert_debug_snapshot!(
+            parse("executable()").unwrap_err().kind(),
+            @r#"Expression("Function `executable` can't be used in this context")"#);
+        let parse =
+            |text| parse_maybe_bare_with_predicates(&mut FilesetDiagnostics::new(), text, &context);
+        insta::assert_debug_snapshot!(parse("executable()")?, @"Predicate(Executable)");
+        insta::assert_debug_snapshot!(parse("symlinks()")?, @"Predicate(Symlink)");
+        insta::assert_debug_snapshot!(parse("submodules()")?, @"Predicate(Submodule)");
+        insta::assert_debug_snapshot!(parse("conflicted()")?, @"Predicate(Conflicted)");
+        insta::assert_debug_snapshot!(parse("binary()")?, @"Predicate(Binary)");
+        insta::assert_debug_snapshot!(parse("size(>1K)")?, @"Predicate(Size(1025..=18446744073709551615))");
+        insta::assert_debug_snapshot!(parse("size(>=1K)")?, @"Predicate(Size(1024..=18446744073709551615))");
+        insta::assert_debug_snapshot!(parse("size(<1K)")?, @"Predicate(Size(0..=1023))");
+        insta::assert_debug_snapshot!(parse("size('<=10')")?, @"Predicate(Size(0..=10))");
+        insta::assert_debug_snapshot!(
+            parse("size(1K)").unwrap_err().kind(),
+            @r#"
+        Expression(
+            "Invalid size comparison: Expected a comparison such as `>1M`",
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("size(<0)").unwrap_err().kind(),
+            @r#"Expression("Invalid size comparison: No file is smaller than 0 bytes")"#);
+        insta::assert_debug_snapshot!(
+            parse("size(>1X)").unwrap_err().kind(),
+            @r#"Expression("Invalid size comparison: unrecognized unit prefix")"#);
         Ok(())
     }
 
@@ -1373,6 +2198,45 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_build_matcher_files_in_name_regex_patterns() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+
+        let expr = FilesetExpression::union_all(vec![
+            FilesetExpression::pattern(FilePattern::FilesInPath(repo_path_buf("foo"))),
+            FilesetExpression::pattern(FilePattern::FilesInPath(repo_path_buf("foo/bar"))),
+        ]);
+        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        FilesInMatcher {
+            tree: Dir {
+                "foo": FilesIn {
+                    "bar": FilesIn {},
+                },
+            },
+        }
+        "#);
+
+        let expr = FilesetExpression::pattern(FilePattern::root_file_name("BUILD").unwrap());
+        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        FileNamesMatcher {
+            tree: {
+                "BUILD",
+            } {},
+        }
+        "#);
+
+        let expr = FilesetExpression::union_all(vec![
+            FilesetExpression::pattern(FilePattern::root_file_regex("x").unwrap()),
+            FilesetExpression::pattern(FilePattern::root_file_regex("y").unwrap()),
+        ]);
+        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        RegexMatcher {
+            tree: Some(RegexSet(["x", "y"])) {},
+        }
+        "#);
+    }
+
     #[test]
     fn test_build_matcher_union_patterns_of_same_kind() {
         let settings = insta_settings();
@@ -1395,13 +2259,14 @@ mod tests {
         let expr = FilesetExpression::union_all(vec![
             FilesetExpression::prefix_path(repo_path_buf("bar")),
             FilesetExpression::prefix_path(repo_path_buf("bar/baz")),
+            FilesetExpression::prefix_path(repo_path_buf("qux")),
         ]);
+        // "bar/baz" is covered by "bar"
         insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
         PrefixMatcher {
             tree: Dir {
-                "bar": Prefix {
-                    "baz": Prefix {},
-                },
+                "bar": Prefix {},
+                "qux": Prefix {},
             },
         }
         "#);
@@ -1437,17 +2302,21 @@ mod tests {
         let settings = insta_settings();
         let _guard = settings.bind_to_scope();
 
+        // Matchers are built from unoptimized expressions as is
+        let build_matcher = |expr: &FilesetExpression| build_union_matcher(expr.as_union_all());
+
         let expr = FilesetExpression::UnionAll(vec![]);
-        insta::assert_debug_snapshot!(expr.to_matcher(), @"NothingMatcher");
+        insta::assert_debug_snapshot!(build_matcher(&expr), @"NothingMatcher");
 
         let expr =
             FilesetExpression::UnionAll(vec![FilesetExpression::None, FilesetExpression::All]);
-        insta::assert_debug_snapshot!(expr.to_matcher(), @"
+        insta::assert_debug_snapshot!(build_matcher(&expr), @"
         UnionMatcher {
             input1: NothingMatcher,
             input2: EverythingMatcher,
         }
         ");
+        insta::assert_debug_snapshot!(expr.to_matcher(), @"EverythingMatcher");
     }
 
     #[test]
@@ -1461,7 +2330,7 @@ mod tests {
             FilesetExpression::file_path(repo_path_buf("foo")),
             FilesetExpression::prefix_path(repo_path_buf("bar")),
         ]);
-        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        insta::assert_debug_snapshot!(build_union_matcher(expr.as_union_all()), @r#"
         UnionMatcher {
             input1: UnionMatcher {
                 input1: IntersectionMatcher {
@@ -1487,5 +2356,163 @@ mod tests {
             },
         }
         "#);
+        insta::assert_debug_snapshot!(expr.to_matcher(), @r#"
+        UnionMatcher {
+            input1: FilesMatcher {
+                tree: Dir {
+                    "foo": File {},
+                },
+            },
+            input2: PrefixMatcher {
+                tree: Dir {
+                    "bar": Prefix {},
+                },
+            },
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_optimize_union() -> TestResult {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+        let context = FilesetParseContext {
+            aliases_map: &FilesetAliasesMap::new(),
+            path_converter: &RepoPathUiConverter::Fs {
+                cwd: PathBuf::from("/ws"),
+                base: PathBuf::from("/ws"),
+            },
+        };
+        let optimize_str = |text| -> FilesetParseResult<_> {
+            let expr = parse(&mut FilesetDiagnostics::new(), text, &context)?;
+            Ok(optimize(expr))
+        };
+
+        // Nested unions are flattened, and none() is dropped
+        insta::assert_debug_snapshot!(
+            optimize_str("(a | (b | none())) | (file:c | none())")?, @r#"
+        UnionAll(
+            [
+                Pattern(PrefixPath("a")),
+                Pattern(PrefixPath("b")),
+                Pattern(FilePath("c")),
+            ],
+        )
+        "#);
+        insta::assert_debug_snapshot!(optimize_str("a | all() | b")?, @"All");
+        insta::assert_debug_snapshot!(optimize_str("none() | none()")?, @"None");
+
+        // Patterns under path prefixes are removed
+        insta::assert_debug_snapshot!(
+            optimize_str("a/b | a | file:a/c | glob:'a/*.rs' | name:x | files-in:a/d | a")?, @r#"
+        UnionAll(
+            [
+                Pattern(PrefixPath("a")),
+                Pattern(
+                    FileName {
+                        dir: "",
+                        name: "x",
+                    },
+                ),
+            ],
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            optimize_str("file:a | file:a | ab | file:ab | file:a/b")?, @r#"
+        UnionAll(
+            [
+                Pattern(FilePath("a")),
+                Pattern(PrefixPath("ab")),
+                Pattern(FilePath("a/b")),
+            ],
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            optimize_str("root:. | a | file:b")?,
+            @r#"Pattern(PrefixPath(""))"#);
+
+        // Prefixes in nested expressions don't cover patterns outside
+        insta::assert_debug_snapshot!(optimize_str("(a ~ b) | a/c")?, @r#"
+        UnionAll(
+            [
+                Difference(
+                    Pattern(PrefixPath("a")),
+                    Pattern(PrefixPath("b")),
+                ),
+                Pattern(PrefixPath("a/c")),
+            ],
+        )
+        "#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_intersection_difference() -> TestResult {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+        let context = FilesetParseContext {
+            aliases_map: &FilesetAliasesMap::new(),
+            path_converter: &RepoPathUiConverter::Fs {
+                cwd: PathBuf::from("/ws"),
+                base: PathBuf::from("/ws"),
+            },
+        };
+        let optimize_str = |text| -> FilesetParseResult<_> {
+            let expr = parse(&mut FilesetDiagnostics::new(), text, &context)?;
+            Ok(optimize(expr))
+        };
+
+        insta::assert_debug_snapshot!(optimize_str("a & all()")?, @r#"Pattern(PrefixPath("a"))"#);
+        insta::assert_debug_snapshot!(optimize_str("all() & a")?, @r#"Pattern(PrefixPath("a"))"#);
+        insta::assert_debug_snapshot!(optimize_str("a & none()")?, @"None");
+        insta::assert_debug_snapshot!(optimize_str("a ~ none()")?, @r#"Pattern(PrefixPath("a"))"#);
+        insta::assert_debug_snapshot!(optimize_str("a ~ all()")?, @"None");
+        insta::assert_debug_snapshot!(optimize_str("none() ~ a")?, @"None");
+        insta::assert_debug_snapshot!(optimize_str("~none()")?, @"All");
+        insta::assert_debug_snapshot!(optimize_str("~all()")?, @"None");
+
+        // Double negation
+        insta::assert_debug_snapshot!(optimize_str("~~a")?, @r#"Pattern(PrefixPath("a"))"#);
+        insta::assert_debug_snapshot!(optimize_str("~(~a)")?, @r#"Pattern(PrefixPath("a"))"#);
+        insta::assert_debug_snapshot!(optimize_str("~~~a")?, @r#"
+        Difference(
+            All,
+            Pattern(PrefixPath("a")),
+        )
+        "#);
+
+        // Negated intersection becomes difference
+        insta::assert_debug_snapshot!(optimize_str("a & ~b")?, @r#"
+        Difference(
+            Pattern(PrefixPath("a")),
+            Pattern(PrefixPath("b")),
+        )
+        "#);
+        insta::assert_debug_snapshot!(optimize_str("~b & a")?, @r#"
+        Difference(
+            Pattern(PrefixPath("a")),
+            Pattern(PrefixPath("b")),
+        )
+        "#);
+        insta::assert_debug_snapshot!(optimize_str("a ~ ~b")?, @r#"
+        Intersection(
+            Pattern(PrefixPath("a")),
+            Pattern(PrefixPath("b")),
+        )
+        "#);
+
+        // Operands are optimized recursively
+        insta::assert_debug_snapshot!(optimize_str("(a | a/b | ~~c) ~ (d | none())")?, @r#"
+        Difference(
+            UnionAll(
+                [
+                    Pattern(PrefixPath("a")),
+                    Pattern(PrefixPath("c")),
+                ],
+            ),
+            Pattern(PrefixPath("d")),
+        )
+        "#);
+        Ok(())
     }
 }
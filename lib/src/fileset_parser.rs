@@ -74,6 +74,8 @@ impl Rule {
             Self::function => None,
             Self::function_name => None,
             Self::function_arguments => None,
+            Self::function_argument => None,
+            Self::comparison => None,
             Self::formal_parameters => None,
             Self::pattern => None,
             Self::bare_string_pattern => None,
@@ -316,7 +318,14 @@ fn parse_function_call_node(pair: Pair<Rule>) -> FilesetParseResult<FunctionCall
     let name = name_pair.as_str();
     let args = args_pair
         .into_inner()
-        .map(parse_expression_node)
+        .map(|pair| match pair.as_rule() {
+            // Comparisons are passed to the function as strings.
+            Rule::comparison => Ok(ExpressionNode::new(
+                ExpressionKind::String(pair.as_str().to_owned()),
+                pair.as_span(),
+            )),
+            _ => parse_expression_node(pair),
+        })
         .try_collect()?;
     Ok(FunctionCallNode {
         name,
@@ -937,6 +946,21 @@ mod tests {
         assert_eq!(function.args[0].span.as_str(), "a");
         assert_eq!(function.args[1].span.as_str(), "(b)");
         assert_eq!(function.args[2].span.as_str(), "~(c)");
+
+        // Comparisons are parsed as strings, but only as function arguments
+        let function = unwrap_function_call(parse_program("foo(>1M, <=2)")?);
+        assert_eq!(
+            function.args[0].kind,
+            ExpressionKind::String(">1M".to_owned())
+        );
+        assert_eq!(
+            function.args[1].kind,
+            ExpressionKind::String("<=2".to_owned())
+        );
+        assert_eq!(function.args[1].span.as_str(), "<=2");
+        assert!(parse_into_kind("foo(<= 2)").is_err());
+        assert!(parse_into_kind(">1M").is_err());
+        assert!(parse_into_kind("foo(>1M | a)").is_err());
         Ok(())
     }
 
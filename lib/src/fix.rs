@@ -28,10 +28,13 @@ use jj_lib::backend::CommitId;
 use jj_lib::backend::FileId;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
+use jj_lib::copies::CopiesTreeDiffEntry;
+use jj_lib::copies::CopyOperation;
+use jj_lib::copies::CopyRecords;
+use jj_lib::copies::get_copy_records;
 use jj_lib::diff::ContentDiff;
 use jj_lib::diff::DiffHunkKind;
 use jj_lib::matchers::Matcher;
-use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::merged_tree_builder::MergedTreeBuilder;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::Repo as _;
@@ -252,19 +255,30 @@ pub async fn fix_files(
             &base_tree
         };
 
-        // TODO: handle copy tracking
-        let mut diff_stream = diff_base_tree.diff_stream(&commit.tree(), &matcher);
-        while let Some(TreeDiffEntry {
-            path: repo_path,
-            values,
-        }) = diff_stream.next().await
-        {
+        // Renamed files are diffed against their source, so only the lines
+        // changed since the rename are considered modified. Copied files are
+        // new files, and are diffed against nothing.
+        let mut copy_records = CopyRecords::default();
+        if !include_unchanged_files {
+            for base_commit_id in base_commit_ids {
+                let records =
+                    get_copy_records(repo_mut.store(), base_commit_id, commit.id(), matcher)
+                        .await?;
+                copy_records.add_records(records);
+            }
+        }
+        let mut diff_stream =
+            diff_base_tree.diff_stream_with_copies(&commit.tree(), matcher, &copy_records);
+        while let Some(CopiesTreeDiffEntry { path, values }) = diff_stream.next().await {
+            let repo_path = path.target().to_owned();
             let values = values?;
             if values.after.is_absent() {
                 continue;
             }
             let before = if include_unchanged_files {
                 base_tree.path_value(&repo_path).await?.into_iter().next()
+            } else if path.copy_operation() == Some(CopyOperation::Copy) {
+                None
             } else {
                 values.before.into_iter().next()
             };
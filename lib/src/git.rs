@@ -91,6 +91,9 @@ const REMOTE_BOOKMARK_REF_NAMESPACE: &str = "refs/remotes/";
 const REMOTE_TAG_REF_NAMESPACE: &str = "refs/jj/remote-tags/";
 /// Ref name used as a placeholder to unset HEAD without a commit.
 const UNBORN_ROOT_REF_NAME: &str = "refs/jj/root";
+/// Git ref where a single remote ref is fetched by
+/// [`GitFetch::fetch_commit()`].
+const FETCHED_COMMIT_REF_NAME: &str = "refs/jj/fetched-commit";
 /// Dummy file to be added to the index to indicate that the user is editing a
 /// commit with a conflict that isn't represented in the Git index.
 const INDEX_DUMMY_CONFLICT_FILE: &str = ".jj-do-not-resolve-this-conflict";
@@ -2676,6 +2679,8 @@ pub enum GitFetchError {
     RejectedUpdates(Vec<GitRefNameBuf>),
     #[error(transparent)]
     Subprocess(#[from] GitSubprocessError),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
 }
 
 #[derive(Error, Debug)]
@@ -3143,6 +3148,66 @@ impl<'a> GitFetch<'a> {
         Ok(default_branch)
     }
 
+    /// Fetches a single remote ref which isn't mapped to a bookmark or tag,
+    /// such as Gerrit's `refs/changes/*`, and returns the commit it points to.
+    ///
+    /// The commit is imported to the backend, but isn't added to the view.
+    /// Returns `None` if the ref doesn't exist on the remote.
+    #[tracing::instrument(skip(self, callback))]
+    pub fn fetch_commit(
+        &mut self,
+        remote_name: &RemoteName,
+        remote_ref: &GitRefName,
+        callback: &mut dyn GitSubprocessCallback,
+    ) -> Result<Option<CommitId>, GitFetchError> {
+        validate_remote_name(remote_name)?;
+        if self
+            .git_repo
+            .try_find_remote(remote_name.as_str())
+            .is_none()
+        {
+            return Err(GitFetchError::NoSuchRemote(remote_name.to_owned()));
+        }
+
+        // The fetched ref also protects the commit from being garbage
+        // collected until it gets imported.
+        let refspec = RefSpec::forced(remote_ref.as_str(), FETCHED_COMMIT_REF_NAME);
+        let updates =
+            match self
+                .git_ctx
+                .spawn_fetch(remote_name, &[refspec], &[], callback, None)?
+            {
+                GitFetchStatus::Updates(updates) => updates,
+                GitFetchStatus::NoRemoteRef(_) => return Ok(None),
+            };
+        if !updates.rejected.is_empty() {
+            let names = updates.rejected.into_iter().map(|(name, _)| name).collect();
+            return Err(GitFetchError::RejectedUpdates(names));
+        }
+
+        let Some(git_ref) = self
+            .git_repo
+            .try_find_reference(FETCHED_COMMIT_REF_NAME)
+            .ok()
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        let commit_id = resolve_git_ref_to_commit_id(&git_ref, None)
+            .map(|oid| CommitId::from_bytes(oid.as_bytes()));
+        if let Some(id) = &commit_id {
+            let git_backend = get_git_backend(self.mut_repo.store())
+                .expect("backend type should have been tested");
+            git_backend.import_head_commits([id])?;
+        }
+        // The imported commit is protected from garbage collection by the
+        // backend, so the fetched ref is no longer needed.
+        self.git_repo
+            .edit_reference(remove_ref(git_ref))
+            .map_err(|err| BackendError::Other(err.into()))?;
+        Ok(commit_id)
+    }
+
     /// Import the previously fetched remote-tracking branches and tags into the
     /// jj repo and update jj's local bookmarks and tags.
     ///
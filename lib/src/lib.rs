@@ -82,6 +82,9 @@ pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod opset;
+mod opset_parser;
+pub mod packed_op_store;
 #[expect(missing_docs)]
 pub mod protos;
 pub mod ref_name;
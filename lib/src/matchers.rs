@@ -14,6 +14,7 @@
 
 #![expect(missing_docs)]
 
+use std::borrow::Borrow;
 use std::collections::HashSet;
 use std::fmt::Debug;
 
@@ -22,6 +23,7 @@ use itertools::Itertools as _;
 use tracing::instrument;
 
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathComponent;
 use crate::repo_path::RepoPathComponentBuf;
 use crate::repo_path::RepoPathTree;
 
@@ -369,6 +371,172 @@ fn glob_to_prefix_regex(glob: &Glob) -> String {
     format!("{prefix}(?:/|$)")
 }
 
+/// Matches files in directories non-recursively.
+#[derive(PartialEq, Eq, Debug)]
+pub struct FilesInMatcher {
+    tree: RepoPathTree<FilesInNodeKind>,
+}
+
+impl FilesInMatcher {
+    pub fn new(dirs: impl IntoIterator<Item = impl AsRef<RepoPath>>) -> Self {
+        let mut tree = RepoPathTree::default();
+        for dir in dirs {
+            tree.add(dir.as_ref()).set_value(FilesInNodeKind::FilesIn);
+        }
+        Self { tree }
+    }
+}
+
+impl Matcher for FilesInMatcher {
+    fn matches(&self, file: &RepoPath) -> bool {
+        file.parent()
+            .and_then(|dir| self.tree.get(dir))
+            .is_some_and(|sub| *sub.value() == FilesInNodeKind::FilesIn)
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        let Some(sub) = self.tree.get(dir) else {
+            return Visit::Nothing;
+        };
+        // should visit only directories leading to other matched directories
+        let dirs = sub.children().map(|(name, _)| name.to_owned()).collect();
+        match sub.value() {
+            FilesInNodeKind::Dir => Visit::sets(dirs, HashSet::new()),
+            FilesInNodeKind::FilesIn => Visit::Specific {
+                dirs: VisitDirs::Set(dirs),
+                files: VisitFiles::All,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum FilesInNodeKind {
+    /// Represents an intermediate directory.
+    #[default]
+    Dir,
+    /// Represents a directory whose files match.
+    FilesIn,
+}
+
+/// Matches files of the given names in directories recursively.
+#[derive(PartialEq, Eq, Debug)]
+pub struct FileNamesMatcher {
+    tree: RepoPathTree<HashSet<RepoPathComponentBuf>>,
+}
+
+impl FileNamesMatcher {
+    pub fn new(
+        dir_names: impl IntoIterator<Item = (impl AsRef<RepoPath>, impl AsRef<RepoPathComponent>)>,
+    ) -> Self {
+        let mut tree: RepoPathTree<HashSet<RepoPathComponentBuf>> = RepoPathTree::default();
+        for (dir, name) in dir_names {
+            tree.add(dir.as_ref())
+                .value_mut()
+                .insert(name.as_ref().to_owned());
+        }
+        Self { tree }
+    }
+}
+
+impl Matcher for FileNamesMatcher {
+    fn matches(&self, file: &RepoPath) -> bool {
+        let Some((dir, name)) = file.split() else {
+            return false;
+        };
+        self.tree
+            .walk_to(dir)
+            .any(|(sub, _)| sub.value().contains(name))
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        // names to match in 'dir' and its ancestors apply to 'dir'
+        let mut names = HashSet::new();
+        for (sub, tail_path) in self.tree.walk_to(dir) {
+            names.extend(sub.value().iter().cloned());
+            // 'dir' found, and is an ancestor of other directories to search
+            if tail_path.is_root() && names.is_empty() {
+                let sub_dirs = sub.children().map(|(name, _)| name.to_owned()).collect();
+                return Visit::sets(sub_dirs, HashSet::new());
+            }
+        }
+        if names.is_empty() {
+            Visit::Nothing
+        } else {
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::Set(names),
+            }
+        }
+    }
+}
+
+/// Matches file paths with regular expressions.
+///
+/// Each regular expression is matched against the paths relative to its
+/// directory. Since regular expressions can match anything, only the
+/// directories outside of the pattern directories can be skipped.
+#[derive(Clone, Debug)]
+pub struct RegexMatcher {
+    tree: RepoPathTree<Option<regex::bytes::RegexSet>>,
+}
+
+impl RegexMatcher {
+    pub fn new<D, P>(dir_patterns: impl IntoIterator<Item = (D, P)>) -> Self
+    where
+        D: AsRef<RepoPath>,
+        P: Borrow<regex::bytes::Regex>,
+    {
+        let mut dir_patterns = dir_patterns
+            .into_iter()
+            .map(|(dir, pattern)| {
+                (
+                    dir.as_ref().to_owned(),
+                    pattern.borrow().as_str().to_owned(),
+                )
+            })
+            .collect_vec();
+        // keep the order of patterns in the same directory
+        dir_patterns.sort_by(|(dir1, _), (dir2, _)| dir1.cmp(dir2));
+
+        let mut tree: RepoPathTree<Option<regex::bytes::RegexSet>> = Default::default();
+        for (dir, chunk) in &dir_patterns.into_iter().chunk_by(|(dir, _)| dir.clone()) {
+            let regex = regex::bytes::RegexSet::new(chunk.map(|(_, pattern)| pattern))
+                .expect("regex should have been validated");
+            tree.add(&dir).set_value(Some(regex));
+        }
+        Self { tree }
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn matches(&self, file: &RepoPath) -> bool {
+        // check if any ancestor (dir, patterns) matches 'file'
+        self.tree
+            .walk_to(file)
+            .take_while(|(_, tail_path)| !tail_path.is_root()) // only dirs
+            .any(|(sub, tail_path)| {
+                let tail = tail_path.as_internal_file_string().as_bytes();
+                sub.value().as_ref().is_some_and(|pat| pat.is_match(tail))
+            })
+    }
+
+    fn visit(&self, dir: &RepoPath) -> Visit {
+        for (sub, tail_path) in self.tree.walk_to(dir) {
+            // ancestor of 'dir' has patterns
+            if sub.value().is_some() {
+                return Visit::SOME;
+            }
+            // 'dir' found, and is an ancestor of pattern paths
+            if tail_path.is_root() {
+                let sub_dirs = sub.children().map(|(name, _)| name.to_owned()).collect();
+                return Visit::sets(sub_dirs, HashSet::new());
+            }
+        }
+        Visit::Nothing
+    }
+}
+
 /// Matches paths that are matched by any of the input matchers.
 #[derive(Clone, Debug)]
 pub struct UnionMatcher<M1, M2> {
@@ -939,6 +1107,158 @@ mod tests {
         assert_eq!(m.visit(repo_path("foo/bar/baz")), Visit::AllRecursively);
     }
 
+    #[test]
+    fn test_files_in_matcher() {
+        let m = FilesInMatcher::new([repo_path("foo"), repo_path("foo/bar/baz")]);
+        assert!(!m.matches(RepoPath::root()));
+        assert!(!m.matches(repo_path("foo")));
+        assert!(m.matches(repo_path("foo/x")));
+        assert!(!m.matches(repo_path("foo/bar/x")));
+        assert!(m.matches(repo_path("foo/bar/baz/x")));
+        assert!(!m.matches(repo_path("foo/bar/baz/qux/x")));
+        assert!(!m.matches(repo_path("x")));
+        assert_eq!(
+            m.visit(RepoPath::root()),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {repo_path_component_buf("foo")}),
+                files: VisitFiles::Set(hashset! {}),
+            }
+        );
+        assert_eq!(
+            m.visit(repo_path("foo")),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {repo_path_component_buf("bar")}),
+                files: VisitFiles::All,
+            }
+        );
+        assert_eq!(
+            m.visit(repo_path("foo/bar")),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {repo_path_component_buf("baz")}),
+                files: VisitFiles::Set(hashset! {}),
+            }
+        );
+        assert_eq!(
+            m.visit(repo_path("foo/bar/baz")),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {}),
+                files: VisitFiles::All,
+            }
+        );
+        assert_eq!(m.visit(repo_path("foo/bar/baz/qux")), Visit::Nothing);
+        assert_eq!(m.visit(repo_path("foo/qux")), Visit::Nothing);
+        assert_eq!(m.visit(repo_path("qux")), Visit::Nothing);
+    }
+
+    #[test]
+    fn test_file_names_matcher() {
+        let name = |value| RepoPathComponent::new(value).unwrap();
+        let m = FileNamesMatcher::new([
+            (RepoPath::root(), name("BUILD")),
+            (repo_path("foo"), name("x")),
+            (repo_path("foo/bar"), name("y")),
+        ]);
+        assert!(!m.matches(RepoPath::root()));
+        assert!(m.matches(repo_path("BUILD")));
+        assert!(m.matches(repo_path("foo/bar/BUILD")));
+        assert!(!m.matches(repo_path("x")));
+        assert!(m.matches(repo_path("foo/x")));
+        assert!(m.matches(repo_path("foo/baz/x")));
+        assert!(!m.matches(repo_path("foo/y")));
+        assert!(m.matches(repo_path("foo/bar/y")));
+        assert!(!m.matches(repo_path("foo/x/z")));
+        assert_eq!(
+            m.visit(RepoPath::root()),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::Set(hashset! {repo_path_component_buf("BUILD")}),
+            }
+        );
+        assert_eq!(
+            m.visit(repo_path("foo/bar")),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::Set(hashset! {
+                    repo_path_component_buf("BUILD"),
+                    repo_path_component_buf("x"),
+                    repo_path_component_buf("y"),
+                }),
+            }
+        );
+        assert_eq!(
+            m.visit(repo_path("foo/bar/baz")),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::Set(hashset! {
+                    repo_path_component_buf("BUILD"),
+                    repo_path_component_buf("x"),
+                    repo_path_component_buf("y"),
+                }),
+            }
+        );
+
+        // Directories outside of the search directories can be skipped
+        let m = FileNamesMatcher::new([(repo_path("foo/bar"), name("x"))]);
+        assert!(!m.matches(repo_path("x")));
+        assert!(!m.matches(repo_path("foo/x")));
+        assert!(m.matches(repo_path("foo/bar/x")));
+        assert_eq!(
+            m.visit(RepoPath::root()),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {repo_path_component_buf("foo")}),
+                files: VisitFiles::Set(hashset! {}),
+            }
+        );
+        assert_eq!(
+            m.visit(repo_path("foo/bar/baz")),
+            Visit::Specific {
+                dirs: VisitDirs::All,
+                files: VisitFiles::Set(hashset! {repo_path_component_buf("x")}),
+            }
+        );
+        assert_eq!(m.visit(repo_path("qux")), Visit::Nothing);
+    }
+
+    #[test]
+    fn test_regex_matcher() {
+        let regex = |value| regex::bytes::Regex::new(value).unwrap();
+        let m = RegexMatcher::new([
+            (repo_path("foo"), regex(r"\.rs$")),
+            (repo_path("foo"), regex(r"^x")),
+            (repo_path("bar/baz"), regex("qux")),
+        ]);
+        assert!(!m.matches(repo_path("foo")));
+        assert!(!m.matches(repo_path("x.rs")));
+        assert!(m.matches(repo_path("foo/x.rs")));
+        assert!(m.matches(repo_path("foo/y/z.rs")));
+        assert!(!m.matches(repo_path("foo/a.rs/b")));
+        assert!(m.matches(repo_path("foo/x/y")));
+        assert!(!m.matches(repo_path("foo/y/x")));
+        assert!(!m.matches(repo_path("bar/qux")));
+        assert!(m.matches(repo_path("bar/baz/a-qux-b")));
+        assert_eq!(
+            m.visit(RepoPath::root()),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {
+                    repo_path_component_buf("foo"),
+                    repo_path_component_buf("bar"),
+                }),
+                files: VisitFiles::Set(hashset! {}),
+            }
+        );
+        assert_eq!(m.visit(repo_path("foo")), Visit::SOME);
+        assert_eq!(m.visit(repo_path("foo/y")), Visit::SOME);
+        assert_eq!(
+            m.visit(repo_path("bar")),
+            Visit::Specific {
+                dirs: VisitDirs::Set(hashset! {repo_path_component_buf("baz")}),
+                files: VisitFiles::Set(hashset! {}),
+            }
+        );
+        assert_eq!(m.visit(repo_path("bar/baz")), Visit::SOME);
+        assert_eq!(m.visit(repo_path("qux")), Visit::Nothing);
+    }
+
     #[test]
     fn test_union_matcher_concatenate_roots() {
         let m1 = PrefixMatcher::new([repo_path("foo"), repo_path("bar")]);
@@ -497,6 +497,39 @@ impl dyn OpStore {
     }
 }
 
+/// Copies operations and views reachable from the `head_ids` to the `target`
+/// store. Returns the number of copied operations.
+///
+/// The operation IDs must be preserved by the `target` store, so the existing
+/// operation heads can be used as is.
+pub async fn copy_operations(
+    source: &dyn OpStore,
+    target: &dyn OpStore,
+    head_ids: &[OperationId],
+) -> OpStoreResult<usize> {
+    let mut visited_ids = HashSet::new();
+    let mut copied_view_ids = HashSet::new();
+    let mut to_visit = head_ids.to_vec();
+    while let Some(id) = to_visit.pop() {
+        if id == *source.root_operation_id() || !visited_ids.insert(id.clone()) {
+            continue;
+        }
+        let operation = source.read_operation(&id).await?;
+        if copied_view_ids.insert(operation.view_id.clone()) {
+            let view = source.read_view(&operation.view_id).await?;
+            target.write_view(&view).await?;
+        }
+        let new_id = target.write_operation(&operation).await?;
+        if new_id != id {
+            return Err(OpStoreError::Other(
+                format!("Operation {} was written as {}", id.hex(), new_id.hex()).into(),
+            ));
+        }
+        to_visit.extend(operation.parents);
+    }
+    Ok(visited_ids.len())
+}
+
 #[cfg(test)]
 mod tests {
     use maplit::btreemap;
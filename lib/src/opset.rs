@@ -0,0 +1,732 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functional language for selecting a set of operations.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
+use futures::future;
+use futures::stream;
+use futures::stream::LocalBoxStream;
+use itertools::Itertools as _;
+
+use crate::dsl_util::collect_similar;
+use crate::graph::GraphEdge;
+use crate::graph::GraphNode;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreResult;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::operation::Operation;
+use crate::opset_parser;
+use crate::opset_parser::BinaryOp;
+use crate::opset_parser::ExpressionKind;
+use crate::opset_parser::ExpressionNode;
+use crate::opset_parser::FunctionCallNode;
+pub use crate::opset_parser::OpsetParseError;
+pub use crate::opset_parser::OpsetParseErrorKind;
+use crate::opset_parser::UnaryOp;
+use crate::str_util::StringPattern;
+
+/// Predicate to select operations by metadata.
+#[derive(Clone, Debug)]
+pub enum OperationFilterPredicate {
+    /// Operations run by the matching user name.
+    Username(StringPattern),
+    /// Operations run on the matching host.
+    Hostname(StringPattern),
+    /// Operations run in the matching workspace.
+    Workspace(StringPattern),
+    /// Operations with the matching description.
+    Description(StringPattern),
+    /// Operations which only snapshot the working copy.
+    Snapshot,
+    /// Operations having the attribute `key` with the matching value.
+    Attribute(String, StringPattern),
+}
+
+/// Tree of operation set expression.
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// All operations reachable from the head operations.
+    All,
+    /// No operations.
+    None,
+    /// The root operation.
+    Root,
+    /// Operation ID prefix or `@`, optionally followed by `-`/`+` operators.
+    Operation(String),
+    /// `::heads`
+    Ancestors(Arc<Self>),
+    /// `roots::`
+    Descendants(Arc<Self>),
+    /// `::heads ~ ::roots`
+    Range {
+        /// Operations to exclude along with their ancestors.
+        roots: Arc<Self>,
+        /// Operations to include along with their ancestors.
+        heads: Arc<Self>,
+    },
+    /// `roots:: & ::heads`
+    DagRange {
+        /// Operations to include along with their descendants.
+        roots: Arc<Self>,
+        /// Operations to include along with their ancestors.
+        heads: Arc<Self>,
+    },
+    /// Operations matching the predicate.
+    Filter(OperationFilterPredicate),
+    /// `~x`
+    NotIn(Arc<Self>),
+    /// `x | y`
+    Union(Arc<Self>, Arc<Self>),
+    /// `x & y`
+    Intersection(Arc<Self>, Arc<Self>),
+    /// `x ~ y`
+    Difference(Arc<Self>, Arc<Self>),
+}
+
+impl OpsetExpression {
+    /// Evaluates the expression against the `head_ops` and their ancestors.
+    ///
+    /// The returned operations are sorted in reverse topological order.
+    pub async fn evaluate(
+        &self,
+        op_store: &Arc<dyn OpStore>,
+        head_ops: &[Operation],
+    ) -> Result<Vec<Operation>, OpsetEvaluationError> {
+        let ops = self.evaluate_lazy(op_store, head_ops).await?;
+        Ok(ops.try_collect().await?)
+    }
+
+    /// Evaluates the expression, walking the ancestors of the `head_ops` only
+    /// as far as the returned stream is consumed.
+    ///
+    /// The operations are emitted in reverse topological order.
+    pub async fn evaluate_lazy(
+        &self,
+        op_store: &Arc<dyn OpStore>,
+        head_ops: &[Operation],
+    ) -> Result<LocalBoxStream<'static, OpStoreResult<Operation>>, OpsetEvaluationError> {
+        let ops = self
+            .walk_matches(op_store, head_ops)
+            .await?
+            .try_filter_map(|(op, matched)| future::ready(Ok(matched.then_some(op))));
+        Ok(ops.boxed_local())
+    }
+
+    /// Evaluates the expression lazily as a graph. Each operation is emitted
+    /// with edges to its nearest ancestors in the set, in reverse topological
+    /// order.
+    ///
+    /// Like the revset graph iterator, edges to ancestors which are reachable
+    /// through the other edges are omitted.
+    pub async fn evaluate_graph(
+        &self,
+        op_store: &Arc<dyn OpStore>,
+        head_ops: &[Operation],
+    ) -> Result<
+        LocalBoxStream<'static, OpStoreResult<GraphNode<Operation, OperationId>>>,
+        OpsetEvaluationError,
+    > {
+        let walk = OpsetGraphWalk {
+            ops: self.walk_matches(op_store, head_ops).await?,
+            visited: HashMap::new(),
+            pending_ops: VecDeque::new(),
+        };
+        let nodes = stream::try_unfold(walk, async |mut walk| {
+            let Some(op) = walk.next_matched_op().await? else {
+                return Ok(None);
+            };
+            let edges = walk.edges_from(&op).await?;
+            Ok(Some(((op, edges), walk)))
+        });
+        Ok(nodes.boxed_local())
+    }
+
+    /// Walks the ancestors of the `head_ops`, testing whether each operation
+    /// is included in the set. The walk stops once no more ancestors can be
+    /// included.
+    async fn walk_matches(
+        &self,
+        op_store: &Arc<dyn OpStore>,
+        head_ops: &[Operation],
+    ) -> Result<LocalBoxStream<'static, OpStoreResult<(Operation, bool)>>, OpsetEvaluationError>
+    {
+        let mut resolved_ids = HashMap::new();
+        for op_str in self.symbols() {
+            let op = op_walk::resolve_op_at(op_store, head_ops, op_str).await?;
+            resolved_ids.insert(op_str, op.id().clone());
+        }
+        // Descendants can't be determined until their ancestors are visited.
+        let (all_ops, ops) = if self.has_descendants() {
+            let all_ops: Vec<Operation> = op_walk::walk_ancestors(head_ops).try_collect().await?;
+            let ops = stream::iter(all_ops.clone().into_iter().map(Ok)).boxed_local();
+            (all_ops, ops)
+        } else {
+            (vec![], op_walk::walk_ancestors(head_ops).boxed_local())
+        };
+        let mut matcher = OpMatcher::new(self, &resolved_ids, &all_ops);
+        let ops = ops
+            .map_ok(move |op| {
+                if !matcher.can_match_more() {
+                    return None;
+                }
+                let matched = matcher.matches(&op);
+                Some((op, matched))
+            })
+            .try_take_while(|item| future::ready(Ok(item.is_some())))
+            .map_ok(Option::unwrap);
+        Ok(ops.boxed_local())
+    }
+
+    /// Operation symbols which need to be resolved prior to evaluation.
+    fn symbols(&self) -> Vec<&str> {
+        match self {
+            Self::All | Self::None | Self::Root | Self::Filter(_) => vec![],
+            Self::Operation(op_str) => vec![op_str],
+            Self::Ancestors(x) | Self::Descendants(x) | Self::NotIn(x) => x.symbols(),
+            Self::Range { roots, heads } | Self::DagRange { roots, heads } => {
+                itertools::chain(roots.symbols(), heads.symbols()).collect()
+            }
+            Self::Union(x, y) | Self::Intersection(x, y) | Self::Difference(x, y) => {
+                itertools::chain(x.symbols(), y.symbols()).collect()
+            }
+        }
+    }
+
+    /// Whether the expression contains `x::` or `x::y`.
+    fn has_descendants(&self) -> bool {
+        match self {
+            Self::All | Self::None | Self::Root | Self::Operation(_) | Self::Filter(_) => false,
+            Self::Descendants(_) | Self::DagRange { .. } => true,
+            Self::Ancestors(x) | Self::NotIn(x) => x.has_descendants(),
+            Self::Range { roots, heads } => roots.has_descendants() || heads.has_descendants(),
+            Self::Union(x, y) | Self::Intersection(x, y) | Self::Difference(x, y) => {
+                x.has_descendants() || y.has_descendants()
+            }
+        }
+    }
+}
+
+/// Tests whether each operation is included in the set. Operations must be
+/// visited in reverse topological order.
+enum OpMatcher {
+    Constant(bool),
+    Root,
+    /// Operation to be included, or `None` if already visited.
+    Id(Option<OperationId>),
+    Set(HashSet<OperationId>),
+    Ancestors {
+        heads: Box<Self>,
+        /// Parents of the included operations, not yet visited.
+        pending: HashSet<OperationId>,
+    },
+    Filter(Box<dyn Fn(&Operation) -> bool>),
+    NotIn(Box<Self>),
+    Union(Box<Self>, Box<Self>),
+    Intersection(Box<Self>, Box<Self>),
+    Difference(Box<Self>, Box<Self>),
+}
+
+impl OpMatcher {
+    /// Builds matcher for the `expression`. The `all_ops` must be populated if
+    /// the expression contains descendants.
+    fn new(
+        expression: &OpsetExpression,
+        resolved_ids: &HashMap<&str, OperationId>,
+        all_ops: &[Operation],
+    ) -> Self {
+        let new = |x: &OpsetExpression| Box::new(Self::new(x, resolved_ids, all_ops));
+        let new_ancestors = |x: &OpsetExpression| {
+            Box::new(Self::Ancestors {
+                heads: new(x),
+                pending: HashSet::new(),
+            })
+        };
+        let new_descendants = |x: &OpsetExpression| {
+            let mut roots = Self::new(x, resolved_ids, all_ops);
+            Box::new(Self::Set(descendants(roots.matching_ids(all_ops), all_ops)))
+        };
+        match expression {
+            OpsetExpression::All => Self::Constant(true),
+            OpsetExpression::None => Self::Constant(false),
+            OpsetExpression::Root => Self::Root,
+            OpsetExpression::Operation(op_str) => {
+                // Operations unreachable from the heads are excluded.
+                Self::Id(Some(resolved_ids[op_str.as_str()].clone()))
+            }
+            OpsetExpression::Ancestors(heads) => *new_ancestors(heads),
+            OpsetExpression::Descendants(roots) => *new_descendants(roots),
+            OpsetExpression::Range { roots, heads } => {
+                Self::Difference(new_ancestors(heads), new_ancestors(roots))
+            }
+            OpsetExpression::DagRange { roots, heads } => {
+                Self::Intersection(new_descendants(roots), new_ancestors(heads))
+            }
+            OpsetExpression::Filter(predicate) => Self::Filter(build_predicate_fn(predicate)),
+            OpsetExpression::NotIn(x) => Self::NotIn(new(x)),
+            OpsetExpression::Union(x, y) => Self::Union(new(x), new(y)),
+            OpsetExpression::Intersection(x, y) => Self::Intersection(new(x), new(y)),
+            OpsetExpression::Difference(x, y) => Self::Difference(new(x), new(y)),
+        }
+    }
+
+    /// Returns true if the `op` is included. Since the matcher is stateful,
+    /// all sub-matchers have to be evaluated for each operation.
+    fn matches(&mut self, op: &Operation) -> bool {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Root => op.parent_ids().is_empty(),
+            Self::Id(id) => {
+                let matched = id.as_ref() == Some(op.id());
+                if matched {
+                    *id = None;
+                }
+                matched
+            }
+            Self::Set(ids) => ids.contains(op.id()),
+            Self::Ancestors { heads, pending } => {
+                let is_head = heads.matches(op);
+                if pending.remove(op.id()) || is_head {
+                    pending.extend(op.parent_ids().iter().cloned());
+                    true
+                } else {
+                    false
+                }
+            }
+            Self::Filter(predicate) => predicate(op),
+            Self::NotIn(x) => !x.matches(op),
+            Self::Union(x, y) => {
+                let (x, y) = (x.matches(op), y.matches(op));
+                x || y
+            }
+            Self::Intersection(x, y) => {
+                let (x, y) = (x.matches(op), y.matches(op));
+                x && y
+            }
+            Self::Difference(x, y) => {
+                let (x, y) = (x.matches(op), y.matches(op));
+                x && !y
+            }
+        }
+    }
+
+    /// Returns false if none of the remaining operations can be included.
+    fn can_match_more(&self) -> bool {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Root | Self::Set(_) | Self::Filter(_) | Self::NotIn(_) => true,
+            Self::Id(id) => id.is_some(),
+            Self::Ancestors { heads, pending } => !pending.is_empty() || heads.can_match_more(),
+            Self::Union(x, y) => x.can_match_more() || y.can_match_more(),
+            Self::Intersection(x, y) => x.can_match_more() && y.can_match_more(),
+            Self::Difference(x, y) => match (&**x, &**y) {
+                // If all pending ancestors will be excluded, so will be their
+                // ancestors.
+                (
+                    Self::Ancestors { heads, pending },
+                    Self::Ancestors {
+                        pending: excluded, ..
+                    },
+                ) if !heads.can_match_more() => !pending.is_subset(excluded),
+                _ => x.can_match_more(),
+            },
+        }
+    }
+
+    fn matching_ids(&mut self, ops: &[Operation]) -> HashSet<OperationId> {
+        ops.iter()
+            .filter(|op| self.matches(op))
+            .map(|op| op.id().clone())
+            .collect()
+    }
+}
+
+/// State of [`OpsetExpression::evaluate_graph()`].
+struct OpsetGraphWalk {
+    /// Ancestors of the heads and whether they are included in the set.
+    ops: LocalBoxStream<'static, OpStoreResult<(Operation, bool)>>,
+    /// Operations visited so far.
+    visited: HashMap<OperationId, VisitedOp>,
+    /// Included operations visited but not yet emitted.
+    pending_ops: VecDeque<Operation>,
+}
+
+struct VisitedOp {
+    /// Index in the walk, which is sorted in reverse topological order.
+    index: usize,
+    parent_ids: Vec<OperationId>,
+    matched: bool,
+}
+
+impl OpsetGraphWalk {
+    /// Visits the next operation. Returns false if there are no more
+    /// operations to visit.
+    async fn visit_next(&mut self) -> OpStoreResult<bool> {
+        let Some((op, matched)) = self.ops.try_next().await? else {
+            return Ok(false);
+        };
+        let visited_op = VisitedOp {
+            index: self.visited.len(),
+            parent_ids: op.parent_ids().to_vec(),
+            matched,
+        };
+        self.visited.insert(op.id().clone(), visited_op);
+        if matched {
+            self.pending_ops.push_back(op);
+        }
+        Ok(true)
+    }
+
+    async fn next_matched_op(&mut self) -> OpStoreResult<Option<Operation>> {
+        while self.pending_ops.is_empty() {
+            if !self.visit_next().await? {
+                return Ok(None);
+            }
+        }
+        Ok(self.pending_ops.pop_front())
+    }
+
+    /// Visits operations until the `id` is found. Returns `None` if the `id`
+    /// isn't visited before the walk ends, which means there are no more
+    /// operations to include.
+    async fn visit_until(&mut self, id: &OperationId) -> OpStoreResult<Option<&VisitedOp>> {
+        while !self.visited.contains_key(id) {
+            if !self.visit_next().await? {
+                return Ok(None);
+            }
+        }
+        Ok(self.visited.get(id))
+    }
+
+    /// Builds graph edges from the `op` to the nearest ancestors in the set.
+    async fn edges_from(&mut self, op: &Operation) -> OpStoreResult<Vec<GraphEdge<OperationId>>> {
+        let mut edges = vec![];
+        let mut queued: HashSet<OperationId> = op.parent_ids().iter().cloned().collect();
+        let mut pending: VecDeque<OperationId> = op.parent_ids().iter().cloned().collect();
+        while let Some(id) = pending.pop_front() {
+            let Some(visited_op) = self.visit_until(&id).await? else {
+                continue;
+            };
+            if visited_op.matched {
+                if op.parent_ids().contains(&id) {
+                    edges.push(GraphEdge::direct(id));
+                } else {
+                    edges.push(GraphEdge::indirect(id));
+                }
+            } else {
+                for parent_id in &visited_op.parent_ids {
+                    if queued.insert(parent_id.clone()) {
+                        pending.push_back(parent_id.clone());
+                    }
+                }
+            }
+        }
+        self.remove_transitive_edges(&mut edges);
+        Ok(edges)
+    }
+
+    /// Removes edges to the operations which are reachable through the other
+    /// edges.
+    fn remove_transitive_edges(&self, edges: &mut Vec<GraphEdge<OperationId>>) {
+        if !edges.iter().any(|edge| edge.is_indirect()) {
+            return;
+        }
+        let index_of = |id: &OperationId| self.visited[id].index;
+        let max_index = edges.iter().map(|edge| index_of(&edge.target)).max();
+        let initial_targets: HashSet<&OperationId> =
+            edges.iter().map(|edge| &edge.target).collect();
+        let mut work: Vec<&OperationId> = initial_targets
+            .iter()
+            .flat_map(|id| &self.visited[*id].parent_ids)
+            .collect();
+        let mut unwanted = HashSet::new();
+        let mut visited = HashSet::new();
+        while let Some(id) = work.pop() {
+            // Operations beyond the edge targets can't be the targets.
+            let Some(visited_op) = self.visited.get(id) else {
+                continue;
+            };
+            if Some(visited_op.index) > max_index || !visited.insert(id) {
+                continue;
+            }
+            if initial_targets.contains(id) {
+                unwanted.insert(id.clone());
+            }
+            work.extend(&visited_op.parent_ids);
+        }
+        edges.retain(|edge| !unwanted.contains(&edge.target));
+    }
+}
+
+/// Returns the `roots` and their descendants within the `all_ops`, which are
+/// sorted in reverse topological order.
+fn descendants(roots: HashSet<OperationId>, all_ops: &[Operation]) -> HashSet<OperationId> {
+    let mut ids = roots;
+    for op in all_ops.iter().rev() {
+        if op.parent_ids().iter().any(|id| ids.contains(id)) {
+            ids.insert(op.id().clone());
+        }
+    }
+    ids
+}
+
+fn build_predicate_fn(predicate: &OperationFilterPredicate) -> Box<dyn Fn(&Operation) -> bool> {
+    match predicate {
+        OperationFilterPredicate::Username(pattern) => {
+            let matcher = pattern.to_matcher();
+            Box::new(move |op| matcher.is_match(&op.metadata().username))
+        }
+        OperationFilterPredicate::Hostname(pattern) => {
+            let matcher = pattern.to_matcher();
+            Box::new(move |op| matcher.is_match(&op.metadata().hostname))
+        }
+        OperationFilterPredicate::Workspace(pattern) => {
+            let matcher = pattern.to_matcher();
+            Box::new(move |op| {
+                op.metadata()
+                    .workspace_name
+                    .as_ref()
+                    .is_some_and(|name| matcher.is_match(name.as_str()))
+            })
+        }
+        OperationFilterPredicate::Description(pattern) => {
+            let matcher = pattern.to_matcher();
+            Box::new(move |op| matcher.is_match(&op.metadata().description))
+        }
+        OperationFilterPredicate::Snapshot => Box::new(|op| op.metadata().is_snapshot),
+        OperationFilterPredicate::Attribute(key, pattern) => {
+            let key = key.clone();
+            let matcher = pattern.to_matcher();
+            Box::new(move |op| {
+                op.metadata()
+                    .attributes
+                    .get(&key)
+                    .is_some_and(|value| matcher.is_match(value))
+            })
+        }
+    }
+}
+
+type OpsetFunction = fn(&FunctionCallNode) -> Result<Arc<OpsetExpression>, OpsetParseError>;
+
+static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, OpsetFunction>> = LazyLock::new(|| {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map: HashMap<&str, OpsetFunction> = HashMap::new();
+    map.insert("all", |function| {
+        function.expect_no_arguments()?;
+        Ok(Arc::new(OpsetExpression::All))
+    });
+    map.insert("none", |function| {
+        function.expect_no_arguments()?;
+        Ok(Arc::new(OpsetExpression::None))
+    });
+    map.insert("root", |function| {
+        function.expect_no_arguments()?;
+        Ok(Arc::new(OpsetExpression::Root))
+    });
+    map.insert("user", |function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(filter(OperationFilterPredicate::Username(pattern)))
+    });
+    map.insert("hostname", |function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(filter(OperationFilterPredicate::Hostname(pattern)))
+    });
+    map.insert("workspace", |function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(filter(OperationFilterPredicate::Workspace(pattern)))
+    });
+    map.insert("description", |function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(filter(OperationFilterPredicate::Description(pattern)))
+    });
+    map.insert("snapshot", |function| {
+        function.expect_no_arguments()?;
+        Ok(filter(OperationFilterPredicate::Snapshot))
+    });
+    map.insert("attribute", |function| {
+        let [key_arg, value_arg] = function.expect_exact_arguments()?;
+        let key = opset_parser::expect_string_literal("string", key_arg)?;
+        let pattern = expect_string_pattern(value_arg)?;
+        Ok(filter(OperationFilterPredicate::Attribute(
+            key.to_owned(),
+            pattern,
+        )))
+    });
+    map
+});
+
+fn filter(predicate: OperationFilterPredicate) -> Arc<OpsetExpression> {
+    Arc::new(OpsetExpression::Filter(predicate))
+}
+
+/// Parses the given `node` as a string pattern. The default pattern kind is
+/// `glob`.
+fn expect_string_pattern(node: &ExpressionNode) -> Result<StringPattern, OpsetParseError> {
+    let pattern_error = || OpsetParseError::expression("Invalid string pattern", node.span);
+    match &node.kind {
+        ExpressionKind::Identifier(value) => {
+            StringPattern::glob(value).map_err(|err| pattern_error().with_source(err))
+        }
+        ExpressionKind::String(value) => {
+            StringPattern::glob(value).map_err(|err| pattern_error().with_source(err))
+        }
+        ExpressionKind::Pattern(pattern) => {
+            let value = opset_parser::expect_string_literal("string", &pattern.value)?;
+            StringPattern::from_str_kind(value, pattern.name)
+                .map_err(|err| pattern_error().with_source(err))
+        }
+        _ => Err(OpsetParseError::expression(
+            "Expected string pattern",
+            node.span,
+        )),
+    }
+}
+
+fn lower_function_call(
+    function: &FunctionCallNode,
+) -> Result<Arc<OpsetExpression>, OpsetParseError> {
+    if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
+        func(function)
+    } else {
+        Err(OpsetParseError::new(
+            OpsetParseErrorKind::NoSuchFunction {
+                name: function.name.to_owned(),
+                candidates: collect_similar(function.name, BUILTIN_FUNCTION_MAP.keys()),
+            },
+            function.name_span,
+        ))
+    }
+}
+
+fn lower_expression(node: &ExpressionNode) -> Result<Arc<OpsetExpression>, OpsetParseError> {
+    match &node.kind {
+        ExpressionKind::Identifier(name) => {
+            Ok(Arc::new(OpsetExpression::Operation((*name).to_owned())))
+        }
+        ExpressionKind::String(name) => Ok(Arc::new(OpsetExpression::Operation(name.clone()))),
+        ExpressionKind::Pattern(_) => Err(OpsetParseError::expression(
+            "String patterns may not be used as operation expressions",
+            node.span,
+        )),
+        ExpressionKind::DagRangeAll => Ok(Arc::new(OpsetExpression::All)),
+        ExpressionKind::RangeAll => Ok(Arc::new(OpsetExpression::Range {
+            roots: Arc::new(OpsetExpression::Root),
+            heads: Arc::new(OpsetExpression::All),
+        })),
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = lower_expression(arg_node)?;
+            let expression = match op {
+                UnaryOp::Negate => OpsetExpression::NotIn(arg),
+                UnaryOp::DagRangePre => OpsetExpression::Ancestors(arg),
+                UnaryOp::DagRangePost => OpsetExpression::Descendants(arg),
+                UnaryOp::RangePre => OpsetExpression::Range {
+                    roots: Arc::new(OpsetExpression::Root),
+                    heads: arg,
+                },
+                UnaryOp::RangePost => OpsetExpression::Range {
+                    roots: arg,
+                    heads: Arc::new(OpsetExpression::All),
+                },
+            };
+            Ok(Arc::new(expression))
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = lower_expression(lhs_node)?;
+            let rhs = lower_expression(rhs_node)?;
+            let expression = match op {
+                BinaryOp::Intersection => OpsetExpression::Intersection(lhs, rhs),
+                BinaryOp::Difference => OpsetExpression::Difference(lhs, rhs),
+                BinaryOp::DagRange => OpsetExpression::DagRange {
+                    roots: lhs,
+                    heads: rhs,
+                },
+                BinaryOp::Range => OpsetExpression::Range {
+                    roots: lhs,
+                    heads: rhs,
+                },
+            };
+            Ok(Arc::new(expression))
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes.iter().map(lower_expression).try_collect()?;
+            let expression = expressions
+                .into_iter()
+                .reduce(|acc, x| Arc::new(OpsetExpression::Union(acc, x)))
+                .expect("union should have at least two nodes");
+            Ok(expression)
+        }
+        ExpressionKind::FunctionCall(function) => lower_function_call(function),
+    }
+}
+
+/// Parses text into operation set expression.
+pub fn parse(text: &str) -> Result<Arc<OpsetExpression>, OpsetParseError> {
+    let node = opset_parser::parse_program(text)?;
+    lower_expression(&node)
+}
+
+/// Operation range `roots..heads`.
+#[derive(Clone, Debug)]
+pub struct OperationRange {
+    /// Operations in the range which have no descendants in the range.
+    pub heads: Vec<Operation>,
+    /// Parents of the range which aren't included in the range.
+    pub roots: Vec<Operation>,
+}
+
+/// Finds the range `roots..heads` which contains exactly the given `ops`.
+///
+/// Returns `None` if the operations can't be denoted as a range, e.g. if
+/// there's a gap between the operations.
+pub async fn to_range(ops: &[Operation]) -> OpStoreResult<Option<OperationRange>> {
+    let ids: HashSet<&OperationId> = ops.iter().map(Operation::id).collect();
+    let parent_ids: HashSet<&OperationId> = ops.iter().flat_map(Operation::parent_ids).collect();
+    let heads = ops
+        .iter()
+        .filter(|op| !parent_ids.contains(op.id()))
+        .cloned()
+        .collect_vec();
+    let mut roots: Vec<Operation> = vec![];
+    for op in ops {
+        for parent in op.parents().await? {
+            if !ids.contains(parent.id()) && !roots.contains(&parent) {
+                roots.push(parent);
+            }
+        }
+    }
+    let range_ids: HashSet<OperationId> = op_walk::walk_ancestors_range(&heads, &roots)
+        .map_ok(|op| op.id().clone())
+        .try_collect()
+        .await?;
+    if range_ids.len() == ids.len() && ids.iter().all(|id| range_ids.contains(*id)) {
+        Ok(Some(OperationRange { heads, roots }))
+    } else {
+        Ok(None)
+    }
+}
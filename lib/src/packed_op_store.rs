@@ -0,0 +1,799 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation store which packs all operations and views into a single file.
+//!
+//! The pack file starts with a header, followed by operation and view
+//! records. Each record payload is encoded in the same format as
+//! [`SimpleOpStore`](crate::simple_op_store::SimpleOpStore). Records are
+//! appended while holding a lock file, so readers only need to scan records
+//! past the last known offset. Garbage collection rewrites the pack with a new
+//! pack ID, which tells the readers to rebuild their index.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read as _;
+use std::io::Seek as _;
+use std::io::SeekFrom;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendInitError;
+use crate::content_hash::blake2b_hash;
+use crate::dag_walk_async;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::lock::FileLock;
+use crate::object_id::HexPrefix;
+use crate::object_id::ObjectId;
+use crate::object_id::PrefixResolution;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreError;
+use crate::op_store::OpStoreResult;
+use crate::op_store::Operation;
+use crate::op_store::OperationId;
+use crate::op_store::RootOperationData;
+use crate::op_store::View;
+use crate::op_store::ViewId;
+use crate::simple_op_store::decode_operation;
+use crate::simple_op_store::decode_view;
+use crate::simple_op_store::encode_operation;
+use crate::simple_op_store::encode_view;
+use crate::simple_op_store::io_to_write_error;
+use crate::simple_op_store::to_read_error;
+
+// BLAKE2b-512 hash length in bytes
+const OBJECT_ID_LENGTH: usize = 64;
+
+const PACK_FILE_NAME: &str = "pack";
+const LOCK_FILE_NAME: &str = "pack.lock";
+
+const PACK_MAGIC: &[u8; 8] = b"JJOPPACK";
+const PACK_FORMAT_VERSION: u32 = 1;
+const PACK_ID_LENGTH: usize = 16;
+// magic, format version, pack ID
+const PACK_HEADER_LENGTH: usize = PACK_MAGIC.len() + 4 + PACK_ID_LENGTH;
+// record kind, object ID, write time in milliseconds, payload length
+const RECORD_HEADER_LENGTH: usize = 1 + OBJECT_ID_LENGTH + 8 + 4;
+
+type PackId = [u8; PACK_ID_LENGTH];
+
+/// Error that may occur during [`PackedOpStore`] initialization.
+#[derive(Debug, Error)]
+#[error("Failed to initialize packed operation store")]
+pub struct PackedOpStoreInitError(#[from] pub PathError);
+
+impl From<PackedOpStoreInitError> for BackendInitError {
+    fn from(err: PackedOpStoreInitError) -> Self {
+        Self(err.into())
+    }
+}
+
+#[derive(Debug, Error)]
+enum PackError {
+    #[error("Invalid operation pack header")]
+    InvalidHeader,
+    #[error("Unsupported operation pack format version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Invalid record kind {kind} at offset {offset}")]
+    InvalidRecordKind { kind: u8, offset: u64 },
+    #[error("Object not found in operation pack")]
+    ObjectNotFound,
+}
+
+impl From<PackError> for io::Error {
+    fn from(err: PackError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RecordKind {
+    Operation = 1,
+    View = 2,
+}
+
+impl RecordKind {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(Self::Operation),
+            2 => Some(Self::View),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RecordLocation {
+    /// Offset of the record payload in the pack file.
+    offset: u64,
+    len: u32,
+    /// Milliseconds since the epoch when the record was first written.
+    written_at: u64,
+}
+
+/// In-memory index of the records in the pack file.
+#[derive(Debug, Default)]
+struct PackIndex {
+    /// Pack file the index was built from.
+    file: Option<File>,
+    pack_id: PackId,
+    /// Offset where the next record would be appended.
+    end_offset: u64,
+    operations: BTreeMap<OperationId, RecordLocation>,
+    views: HashMap<ViewId, RecordLocation>,
+}
+
+impl PackIndex {
+    fn contains(&self, kind: RecordKind, id_bytes: &[u8]) -> bool {
+        match kind {
+            RecordKind::Operation => self
+                .operations
+                .contains_key(&OperationId::from_bytes(id_bytes)),
+            RecordKind::View => self.views.contains_key(&ViewId::from_bytes(id_bytes)),
+        }
+    }
+
+    fn insert(&mut self, kind: RecordKind, id_bytes: &[u8], location: RecordLocation) {
+        // Keep the first record if the same object was written concurrently.
+        match kind {
+            RecordKind::Operation => {
+                self.operations
+                    .entry(OperationId::from_bytes(id_bytes))
+                    .or_insert(location);
+            }
+            RecordKind::View => {
+                self.views
+                    .entry(ViewId::from_bytes(id_bytes))
+                    .or_insert(location);
+            }
+        }
+    }
+
+    /// Indexes records appended to the `file` since the last update. If the
+    /// pack was rewritten, the index is rebuilt from scratch.
+    fn update(&mut self, file: File) -> io::Result<()> {
+        let pack_id = read_pack_header(&file)?;
+        if self.file.is_none() || self.pack_id != pack_id {
+            *self = Self {
+                pack_id,
+                end_offset: PACK_HEADER_LENGTH as u64,
+                ..Self::default()
+            };
+        }
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(&file);
+        reader.seek(SeekFrom::Start(self.end_offset))?;
+        let mut offset = self.end_offset;
+        let mut header = [0; RECORD_HEADER_LENGTH];
+        while offset + RECORD_HEADER_LENGTH as u64 <= file_len {
+            reader.read_exact(&mut header)?;
+            let (kind, rest) = header.split_first().unwrap();
+            let (id_bytes, rest) = rest.split_at(OBJECT_ID_LENGTH);
+            let (written_at, len) = rest.split_at(8);
+            let written_at = u64::from_le_bytes(written_at.try_into().unwrap());
+            let len = u32::from_le_bytes(len.try_into().unwrap());
+            let payload_offset = offset + RECORD_HEADER_LENGTH as u64;
+            if payload_offset + u64::from(len) > file_len {
+                // The record is being written, or the write was interrupted.
+                break;
+            }
+            let kind = RecordKind::from_byte(*kind).ok_or(PackError::InvalidRecordKind {
+                kind: *kind,
+                offset,
+            })?;
+            let location = RecordLocation {
+                offset: payload_offset,
+                len,
+                written_at,
+            };
+            self.insert(kind, id_bytes, location);
+            reader.seek_relative(len.into())?;
+            offset = payload_offset + u64::from(len);
+        }
+        self.end_offset = offset;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn read_payload(&self, location: RecordLocation) -> io::Result<Vec<u8>> {
+        let mut file = self.file.as_ref().expect("index should have been loaded");
+        file.seek(SeekFrom::Start(location.offset))?;
+        let mut buf = vec![0; location.len as usize];
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+fn new_pack_id() -> PackId {
+    rand::random()
+}
+
+fn pack_header(pack_id: &PackId) -> Vec<u8> {
+    let mut header = Vec::with_capacity(PACK_HEADER_LENGTH);
+    header.extend_from_slice(PACK_MAGIC);
+    header.extend_from_slice(&PACK_FORMAT_VERSION.to_le_bytes());
+    header.extend_from_slice(pack_id);
+    header
+}
+
+fn read_pack_header(mut file: &File) -> io::Result<PackId> {
+    let mut header = [0; PACK_HEADER_LENGTH];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut header)?;
+    let (magic, rest) = header.split_at(PACK_MAGIC.len());
+    if magic != PACK_MAGIC {
+        return Err(PackError::InvalidHeader.into());
+    }
+    let (version, pack_id) = rest.split_at(4);
+    let version = u32::from_le_bytes(version.try_into().unwrap());
+    if version != PACK_FORMAT_VERSION {
+        return Err(PackError::UnsupportedVersion(version).into());
+    }
+    Ok(pack_id.try_into().unwrap())
+}
+
+fn encode_record(kind: RecordKind, id_bytes: &[u8], written_at: u64, payload: &[u8]) -> Vec<u8> {
+    assert_eq!(id_bytes.len(), OBJECT_ID_LENGTH);
+    let len = u32::try_from(payload.len()).expect("object should be smaller than 4GiB");
+    let mut record = Vec::with_capacity(RECORD_HEADER_LENGTH + payload.len());
+    record.push(kind as u8);
+    record.extend_from_slice(id_bytes);
+    record.extend_from_slice(&written_at.to_le_bytes());
+    record.extend_from_slice(&len.to_le_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    let duration = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Operation store which appends operations and views to a single pack file.
+#[derive(Debug)]
+pub struct PackedOpStore {
+    path: PathBuf,
+    root_data: RootOperationData,
+    root_operation_id: OperationId,
+    root_view_id: ViewId,
+    index: Mutex<PackIndex>,
+}
+
+impl PackedOpStore {
+    /// Name of this op store, as written to the store's `type` file.
+    pub fn name() -> &'static str {
+        "packed_op_store"
+    }
+
+    /// Creates an empty OpStore. Returns error if it already exists.
+    pub fn init(
+        store_path: &Path,
+        root_data: RootOperationData,
+    ) -> Result<Self, PackedOpStoreInitError> {
+        let store = Self::new(store_path, root_data);
+        let pack_path = store.pack_path();
+        let mut file = File::create_new(&pack_path).context(&pack_path)?;
+        file.write_all(&pack_header(&new_pack_id()))
+            .context(&pack_path)?;
+        Ok(store)
+    }
+
+    /// Load an existing OpStore
+    pub fn load(store_path: &Path, root_data: RootOperationData) -> Self {
+        Self::new(store_path, root_data)
+    }
+
+    fn new(store_path: &Path, root_data: RootOperationData) -> Self {
+        Self {
+            path: store_path.to_path_buf(),
+            root_data,
+            root_operation_id: OperationId::from_bytes(&[0; OBJECT_ID_LENGTH]),
+            root_view_id: ViewId::from_bytes(&[0; OBJECT_ID_LENGTH]),
+            index: Mutex::new(PackIndex::default()),
+        }
+    }
+
+    fn pack_path(&self) -> PathBuf {
+        self.path.join(PACK_FILE_NAME)
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.path.join(LOCK_FILE_NAME)
+    }
+
+    /// Picks up records written since the last refresh.
+    fn refresh(&self, index: &mut PackIndex) -> Result<(), PathError> {
+        let path = self.pack_path();
+        let file = File::open(&path).context(&path)?;
+        index.update(file).context(&path)
+    }
+
+    fn lock_pack(&self) -> Result<FileLock, OpStoreError> {
+        FileLock::lock(self.lock_path()).map_err(|err| OpStoreError::Other(err.into()))
+    }
+
+    fn read_object_data<T: ObjectId>(
+        &self,
+        id: &T,
+        find: impl Fn(&PackIndex) -> Option<RecordLocation>,
+    ) -> OpStoreResult<Vec<u8>> {
+        let mut index = self.index.lock().unwrap();
+        let location = if let Some(location) = find(&index) {
+            location
+        } else {
+            self.refresh(&mut index)
+                .map_err(|err| to_read_error(err.into(), id))?;
+            find(&index).ok_or_else(|| OpStoreError::ObjectNotFound {
+                object_type: id.object_type(),
+                hash: id.hex(),
+                source: Box::new(PackError::ObjectNotFound),
+            })?
+        };
+        index
+            .read_payload(location)
+            .context(self.pack_path())
+            .map_err(|err| to_read_error(err.into(), id))
+    }
+
+    fn write_object_data(
+        &self,
+        kind: RecordKind,
+        id_bytes: &[u8],
+        payload: &[u8],
+        object_type: &'static str,
+    ) -> OpStoreResult<()> {
+        let mut index = self.index.lock().unwrap();
+        let _lock = self.lock_pack()?;
+        self.refresh(&mut index)
+            .map_err(|err| io_to_write_error(err, object_type))?;
+        if index.contains(kind, id_bytes) {
+            return Ok(());
+        }
+
+        let written_at = to_millis(SystemTime::now());
+        let record = encode_record(kind, id_bytes, written_at, payload);
+        let path = self.pack_path();
+        let append = || -> io::Result<()> {
+            let mut file = fs::OpenOptions::new().write(true).open(&path)?;
+            // Discard incomplete record left by an interrupted write.
+            file.set_len(index.end_offset)?;
+            file.seek(SeekFrom::Start(index.end_offset))?;
+            file.write_all(&record)?;
+            file.sync_data()
+        };
+        append()
+            .context(&path)
+            .map_err(|err| io_to_write_error(err, object_type))?;
+
+        let location = RecordLocation {
+            offset: index.end_offset + RECORD_HEADER_LENGTH as u64,
+            len: payload.len() as u32,
+            written_at,
+        };
+        index.insert(kind, id_bytes, location);
+        index.end_offset += record.len() as u64;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OpStore for PackedOpStore {
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn root_operation_id(&self) -> &OperationId {
+        &self.root_operation_id
+    }
+
+    async fn read_view(&self, id: &ViewId) -> OpStoreResult<View> {
+        if *id == self.root_view_id {
+            return Ok(View::make_root(self.root_data.root_commit_id.clone()));
+        }
+        let buf = self.read_object_data(id, |index| index.views.get(id).copied())?;
+        decode_view(&buf).map_err(|err| to_read_error(err, id))
+    }
+
+    async fn write_view(&self, view: &View) -> OpStoreResult<ViewId> {
+        let id = ViewId::new(blake2b_hash(view).to_vec());
+        let payload = encode_view(view);
+        self.write_object_data(RecordKind::View, id.as_bytes(), &payload, "view")?;
+        Ok(id)
+    }
+
+    async fn read_operation(&self, id: &OperationId) -> OpStoreResult<Operation> {
+        if *id == self.root_operation_id {
+            return Ok(Operation::make_root(self.root_view_id.clone()));
+        }
+        let buf = self.read_object_data(id, |index| index.operations.get(id).copied())?;
+        decode_operation(&buf).map_err(|err| to_read_error(err, id))
+    }
+
+    async fn write_operation(&self, operation: &Operation) -> OpStoreResult<OperationId> {
+        assert!(!operation.parents.is_empty());
+        let id = OperationId::new(blake2b_hash(operation).to_vec());
+        let payload = encode_operation(operation);
+        self.write_object_data(RecordKind::Operation, id.as_bytes(), &payload, "operation")?;
+        Ok(id)
+    }
+
+    async fn resolve_operation_id_prefix(
+        &self,
+        prefix: &HexPrefix,
+    ) -> OpStoreResult<PrefixResolution<OperationId>> {
+        let mut index = self.index.lock().unwrap();
+        self.refresh(&mut index)
+            .map_err(|err| OpStoreError::Other(err.into()))?;
+        let mut matched = prefix
+            .matches(&self.root_operation_id)
+            .then(|| self.root_operation_id.clone());
+        let min_id = OperationId::from_bytes(prefix.min_prefix_bytes());
+        let candidates = index
+            .operations
+            .range(min_id..)
+            .map(|(id, _)| id)
+            .take_while(|id| prefix.matches(*id));
+        for id in candidates {
+            if matched.is_some() {
+                return Ok(PrefixResolution::AmbiguousMatch);
+            }
+            matched = Some(id.clone());
+        }
+        if let Some(id) = matched {
+            Ok(PrefixResolution::SingleMatch(id))
+        } else {
+            Ok(PrefixResolution::NoMatch)
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn gc(&self, head_ids: &[OperationId], keep_newer: SystemTime) -> OpStoreResult<()> {
+        let read_op = |id: &OperationId| {
+            self.read_operation(id)
+                .block_on()
+                .map(|data| (id.clone(), data))
+        };
+        let reachable_ops: HashMap<OperationId, Operation> = dag_walk_async::dfs(
+            head_ids.iter().map(read_op),
+            |(id, _)| id.clone(),
+            |(_, data)| data.parents.iter().map(read_op).collect_vec(),
+        )
+        .try_collect()?;
+        tracing::info!(
+            reachable_op_count = reachable_ops.len(),
+            "collected reachable operations"
+        );
+
+        let keep_newer = to_millis(keep_newer);
+        let mut index = self.index.lock().unwrap();
+        let _lock = self.lock_pack()?;
+        let path = self.pack_path();
+        self.refresh(&mut index)
+            .map_err(|err| OpStoreError::Other(err.into()))?;
+
+        let kept_ops = index
+            .operations
+            .iter()
+            .filter(|(id, location)| {
+                reachable_ops.contains_key(*id) || location.written_at > keep_newer
+            })
+            .collect_vec();
+        let mut kept_view_ids: HashSet<ViewId> = reachable_ops
+            .values()
+            .map(|data| data.view_id.clone())
+            .collect();
+        // New operations may refer to old views which aren't reachable from
+        // the heads.
+        for (id, location) in &kept_ops {
+            if reachable_ops.contains_key(*id) {
+                continue;
+            }
+            let buf = index
+                .read_payload(**location)
+                .context(&path)
+                .map_err(|err| to_read_error(err.into(), *id))?;
+            let data = decode_operation(&buf).map_err(|err| to_read_error(err, *id))?;
+            kept_view_ids.insert(data.view_id);
+        }
+        let kept_views = index.views.iter().filter(|(id, location)| {
+            kept_view_ids.contains(*id) || location.written_at > keep_newer
+        });
+        let kept_records = itertools::chain(
+            kept_ops
+                .iter()
+                .map(|(id, location)| (RecordKind::Operation, id.as_bytes(), **location)),
+            kept_views.map(|(id, location)| (RecordKind::View, id.as_bytes(), *location)),
+        )
+        .sorted_by_key(|(_, _, location)| location.offset)
+        .collect_vec();
+        tracing::info!(
+            kept_record_count = kept_records.len(),
+            total_record_count = index.operations.len() + index.views.len(),
+            "rewriting operation pack"
+        );
+
+        let write_temp_pack = || -> io::Result<NamedTempFile> {
+            let temp_file = NamedTempFile::new_in(&self.path)?;
+            let mut writer = BufWriter::new(temp_file.as_file());
+            writer.write_all(&pack_header(&new_pack_id()))?;
+            for (kind, id_bytes, location) in &kept_records {
+                let payload = index.read_payload(*location)?;
+                writer.write_all(&encode_record(
+                    *kind,
+                    id_bytes,
+                    location.written_at,
+                    &payload,
+                ))?;
+            }
+            writer.flush()?;
+            drop(writer);
+            temp_file.as_file().sync_data()?;
+            Ok(temp_file)
+        };
+        let temp_file = write_temp_pack()
+            .context(&path)
+            .map_err(|err| OpStoreError::Other(err.into()))?;
+        // Close the old pack file before replacing it, which would otherwise
+        // fail on Windows. The index will be rebuilt from the new pack.
+        *index = PackIndex::default();
+        temp_file
+            .persist(&path)
+            .map_err(|err| err.error)
+            .context(&path)
+            .map_err(|err| OpStoreError::Other(err.into()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::backend::CommitId;
+    use crate::tests::TestResult;
+    use crate::tests::new_temp_dir;
+
+    fn root_data() -> RootOperationData {
+        RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        }
+    }
+
+    fn create_view(head_hex: &'static str) -> View {
+        View::make_root(CommitId::from_hex(head_hex))
+    }
+
+    fn create_operation(view_id: &ViewId, parent_id: &OperationId, description: &str) -> Operation {
+        let mut operation = Operation::make_root(view_id.clone());
+        operation.parents = vec![parent_id.clone()];
+        operation.metadata.description = description.to_owned();
+        operation
+    }
+
+    #[test]
+    fn test_read_write_objects() -> TestResult {
+        let temp_dir = new_temp_dir();
+        let store = PackedOpStore::init(temp_dir.path(), root_data())?;
+        let view = create_view("aaa111");
+        let view_id = store.write_view(&view).block_on()?;
+        let operation = create_operation(&view_id, store.root_operation_id(), "op1");
+        let op_id = store.write_operation(&operation).block_on()?;
+        assert_eq!(store.read_view(&view_id).block_on()?, view);
+        assert_eq!(store.read_operation(&op_id).block_on()?, operation);
+
+        // Objects should be hashed in the same way as the simple op store.
+        assert_eq!(view_id, ViewId::new(blake2b_hash(&view).to_vec()));
+        assert_eq!(op_id, OperationId::new(blake2b_hash(&operation).to_vec()));
+
+        // Writing the same object again shouldn't grow the pack.
+        let pack_len = fs::metadata(store.pack_path())?.len();
+        store.write_view(&view).block_on()?;
+        store.write_operation(&operation).block_on()?;
+        assert_eq!(fs::metadata(store.pack_path())?.len(), pack_len);
+
+        let store = PackedOpStore::load(temp_dir.path(), root_data());
+        assert_eq!(store.read_view(&view_id).block_on()?, view);
+        assert_eq!(store.read_operation(&op_id).block_on()?, operation);
+
+        let missing_id = OperationId::from_bytes(&[1; OBJECT_ID_LENGTH]);
+        assert!(matches!(
+            store.read_operation(&missing_id).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_writes() -> TestResult {
+        let temp_dir = new_temp_dir();
+        let store1 = PackedOpStore::init(temp_dir.path(), root_data())?;
+        let store2 = PackedOpStore::load(temp_dir.path(), root_data());
+        let view_id = store1.write_view(&create_view("aaa111")).block_on()?;
+        let op1 = create_operation(&view_id, store1.root_operation_id(), "op1");
+        let op1_id = store1.write_operation(&op1).block_on()?;
+        // The second store picks up the records written by the first store.
+        assert_eq!(store2.read_operation(&op1_id).block_on()?, op1);
+
+        let op2 = create_operation(&view_id, &op1_id, "op2");
+        let op2_id = store2.write_operation(&op2).block_on()?;
+        let op3 = create_operation(&view_id, &op1_id, "op3");
+        let op3_id = store1.write_operation(&op3).block_on()?;
+        assert_eq!(store1.read_operation(&op2_id).block_on()?, op2);
+        assert_eq!(store2.read_operation(&op3_id).block_on()?, op3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_interrupted_write() -> TestResult {
+        let temp_dir = new_temp_dir();
+        let store = PackedOpStore::init(temp_dir.path(), root_data())?;
+        let view_id = store.write_view(&create_view("aaa111")).block_on()?;
+        let op1 = create_operation(&view_id, store.root_operation_id(), "op1");
+        let op1_id = store.write_operation(&op1).block_on()?;
+
+        // Simulate a write interrupted in the middle of the record.
+        let record = encode_record(RecordKind::Operation, &[2; OBJECT_ID_LENGTH], 0, b"payload");
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(store.pack_path())?;
+        file.write_all(&record[..record.len() - 1])?;
+        drop(file);
+
+        let store = PackedOpStore::load(temp_dir.path(), root_data());
+        assert_eq!(store.read_operation(&op1_id).block_on()?, op1);
+        let op2 = create_operation(&view_id, &op1_id, "op2");
+        let op2_id = store.write_operation(&op2).block_on()?;
+
+        let store = PackedOpStore::load(temp_dir.path(), root_data());
+        assert_eq!(store.read_operation(&op1_id).block_on()?, op1);
+        assert_eq!(store.read_operation(&op2_id).block_on()?, op2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_operation_id_prefix() -> TestResult {
+        let temp_dir = new_temp_dir();
+        let store = PackedOpStore::init(temp_dir.path(), root_data())?;
+        let view_id = store.write_view(&create_view("aaa111")).block_on()?;
+        let op_ids: Vec<OperationId> = (0..10)
+            .map(|i| {
+                let op = create_operation(&view_id, store.root_operation_id(), &format!("op{i}"));
+                store.write_operation(&op).block_on()
+            })
+            .try_collect()?;
+        let resolve = |hex: &str| {
+            let prefix = HexPrefix::try_from_hex(hex).unwrap();
+            store.resolve_operation_id_prefix(&prefix).block_on()
+        };
+
+        for id in &op_ids {
+            assert_eq!(
+                resolve(&id.hex())?,
+                PrefixResolution::SingleMatch(id.clone())
+            );
+            let hex = id.hex();
+            let shortest_hex = (1..)
+                .map(|len| &hex[..len])
+                .find(|prefix| {
+                    op_ids
+                        .iter()
+                        .filter(|id| id.hex().starts_with(prefix))
+                        .count()
+                        == 1
+                })
+                .unwrap()
+                .to_owned();
+            if !"0".repeat(shortest_hex.len()).starts_with(&shortest_hex) {
+                assert_eq!(
+                    resolve(&shortest_hex)?,
+                    PrefixResolution::SingleMatch(id.clone())
+                );
+            }
+        }
+        assert_eq!(resolve("")?, PrefixResolution::AmbiguousMatch);
+        assert_eq!(
+            resolve(&"0".repeat(OBJECT_ID_LENGTH * 2))?,
+            PrefixResolution::SingleMatch(store.root_operation_id().clone())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_gc() -> TestResult {
+        let temp_dir = new_temp_dir();
+        let store = PackedOpStore::init(temp_dir.path(), root_data())?;
+        let other_store = PackedOpStore::load(temp_dir.path(), root_data());
+        let view1_id = store.write_view(&create_view("aaa111")).block_on()?;
+        let view2_id = store.write_view(&create_view("aaa222")).block_on()?;
+        let op1 = create_operation(&view1_id, store.root_operation_id(), "op1");
+        let op1_id = store.write_operation(&op1).block_on()?;
+        let op2 = create_operation(&view2_id, &op1_id, "op2");
+        let op2_id = store.write_operation(&op2).block_on()?;
+        let op3 = create_operation(&view2_id, store.root_operation_id(), "op3");
+        let op3_id = store.write_operation(&op3).block_on()?;
+        // Load the index before the pack gets rewritten.
+        other_store.read_operation(&op3_id).block_on()?;
+
+        // Nothing is removed if all objects are newer.
+        let keep_newer = SystemTime::now() - Duration::from_secs(3600);
+        store.gc(slice_of(&op2_id), keep_newer).block_on()?;
+        assert_eq!(store.read_operation(&op3_id).block_on()?, op3);
+
+        store
+            .gc(
+                slice_of(&op2_id),
+                SystemTime::now() + Duration::from_secs(3600),
+            )
+            .block_on()?;
+        assert_eq!(store.read_operation(&op1_id).block_on()?, op1);
+        assert_eq!(store.read_operation(&op2_id).block_on()?, op2);
+        assert!(store.read_view(&view1_id).block_on().is_ok());
+        assert!(store.read_view(&view2_id).block_on().is_ok());
+        assert!(matches!(
+            store.read_operation(&op3_id).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        ));
+
+        // The other store rebuilds the index from the rewritten pack.
+        let op4 = create_operation(&view1_id, &op2_id, "op4");
+        let op4_id = store.write_operation(&op4).block_on()?;
+        assert_eq!(other_store.read_operation(&op4_id).block_on()?, op4);
+        assert_eq!(other_store.read_operation(&op1_id).block_on()?, op1);
+        assert!(matches!(
+            other_store.read_operation(&op3_id).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        ));
+
+        store
+            .gc(
+                slice_of(&op1_id),
+                SystemTime::now() + Duration::from_secs(3600),
+            )
+            .block_on()?;
+        assert_eq!(store.read_operation(&op1_id).block_on()?, op1);
+        assert!(matches!(
+            store.read_operation(&op4_id).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        ));
+        assert!(store.read_view(&view1_id).block_on().is_ok());
+        assert!(matches!(
+            store.read_view(&view2_id).block_on(),
+            Err(OpStoreError::ObjectNotFound { .. })
+        ));
+        Ok(())
+    }
+
+    fn slice_of(id: &OperationId) -> &[OperationId] {
+        std::slice::from_ref(id)
+    }
+}
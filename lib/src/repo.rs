@@ -82,6 +82,7 @@ use crate::op_store::RemoteRefState;
 use crate::op_store::RootOperationData;
 use crate::op_walk;
 use crate::operation::Operation;
+use crate::packed_op_store::PackedOpStore;
 use crate::ref_name::GitRefName;
 use crate::ref_name::RefName;
 use crate::ref_name::RemoteName;
@@ -202,7 +203,11 @@ impl ReadonlyRepo {
     }
 
     pub fn default_submodule_store_initializer() -> &'static SubmoduleStoreInitializer<'static> {
-        &|_settings, store_path| Ok(Box::new(DefaultSubmoduleStore::init(store_path)))
+        &|settings, store_path| {
+            let store = DefaultSubmoduleStore::init(settings, store_path)
+                .map_err(|err| BackendInitError(err.into()))?;
+            Ok(Box::new(store))
+        }
     }
 
     #[expect(clippy::too_many_arguments)]
@@ -468,6 +473,12 @@ impl Default for StoreFactories {
                 Ok(Box::new(SimpleOpStore::load(store_path, root_data)))
             }),
         );
+        factories.add_op_store(
+            PackedOpStore::name(),
+            Box::new(|_settings, store_path, root_data| {
+                Ok(Box::new(PackedOpStore::load(store_path, root_data)))
+            }),
+        );
 
         // OpHeadsStores
         factories.add_op_heads_store(
@@ -484,7 +495,11 @@ impl Default for StoreFactories {
         // SubmoduleStores
         factories.add_submodule_store(
             DefaultSubmoduleStore::name(),
-            Box::new(|_settings, store_path| Ok(Box::new(DefaultSubmoduleStore::load(store_path)))),
+            Box::new(|settings, store_path| {
+                let store = DefaultSubmoduleStore::load(settings, store_path)
+                    .map_err(|err| BackendLoadError(err.into()))?;
+                Ok(Box::new(store))
+            }),
         );
 
         factories
@@ -26,6 +26,7 @@ use std::sync::LazyLock;
 
 use futures::Stream;
 use futures::StreamExt as _;
+use futures::TryStreamExt as _;
 use futures::stream::LocalBoxStream;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
@@ -159,6 +160,15 @@ pub enum RevsetCommitRef {
         symbol: RemoteRefSymbolExpression,
         remote_ref_state: Option<RemoteRefState>,
     },
+    /// Commits recorded as created in the specified operations.
+    OperationCommits {
+        /// Operation, or head of the operation range if `root` is specified.
+        head: String,
+        /// Root of the operation range, which is excluded from the range.
+        root: Option<String>,
+        /// Whether to select only commits rewritten from predecessors.
+        rewritten_only: bool,
+    },
 }
 
 /// String expressions to match `name@remote` bookmarks/tags.
@@ -173,7 +183,7 @@ pub struct RemoteRefSymbolExpression {
 /// A custom revset filter expression, defined by an extension.
 pub trait RevsetFilterExtension: std::fmt::Debug + Any + Send + Sync {
     /// Returns true iff this filter matches the specified commit.
-    fn matches_commit(&self, commit: &Commit) -> bool;
+    fn matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError>;
 }
 
 impl dyn RevsetFilterExtension {
@@ -461,6 +471,18 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
             remote_ref_state,
         }))
     }
+
+    pub fn operation_commits(
+        head: String,
+        root: Option<String>,
+        rewritten_only: bool,
+    ) -> Arc<Self> {
+        Arc::new(Self::CommitRef(RevsetCommitRef::OperationCommits {
+            head,
+            root,
+            rewritten_only,
+        }))
+    }
 }
 
 // Compound expression
@@ -1189,6 +1211,14 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
             candidates,
         }))
     });
+    map.insert("created_in", |diagnostics, function, _context| {
+        let (head, root) = expect_operation_range_arguments(diagnostics, function)?;
+        Ok(RevsetExpression::operation_commits(head, root, false))
+    });
+    map.insert("rewritten_by", |diagnostics, function, _context| {
+        let (head, root) = expect_operation_range_arguments(diagnostics, function)?;
+        Ok(RevsetExpression::operation_commits(head, root, true))
+    });
     map.insert("coalesce", |diagnostics, function, context| {
         let ([], args) = function.expect_some_arguments()?;
         let expressions: Vec<_> = args
@@ -1220,6 +1250,27 @@ fn expand_optional_files_arg(
     }
 }
 
+/// Parses `(operation)` or `(root_operation, head_operation)` arguments.
+fn expect_operation_range_arguments(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+) -> Result<(String, Option<String>), RevsetParseError> {
+    let ([first_arg], [second_opt_arg]) = function.expect_arguments()?;
+    // TODO: Parse "opset" here if we add proper language support.
+    let mut expect_operation = |node| {
+        revset_parser::catch_aliases(diagnostics, node, |_diagnostics, node| {
+            Ok(node.span.as_str().to_owned())
+        })
+    };
+    let first = expect_operation(first_arg)?;
+    if let Some(second_arg) = second_opt_arg {
+        let second = expect_operation(second_arg)?;
+        Ok((second, Some(first)))
+    } else {
+        Ok((first, None))
+    }
+}
+
 /// Parses the given `node` as a fileset expression.
 pub fn expect_fileset_expression(
     diagnostics: &mut RevsetDiagnostics,
@@ -2620,6 +2671,40 @@ fn reload_repo_at_operation(
         })
 }
 
+fn resolve_operation_commits(
+    repo: &dyn Repo,
+    head_str: &str,
+    root_str: Option<&str>,
+    rewritten_only: bool,
+) -> Result<Vec<CommitId>, RevsetResolutionError> {
+    let base_repo = repo.base_repo();
+    let resolve_op = |op_str| {
+        op_walk::resolve_op_with_repo(base_repo, op_str)
+            .block_on()
+            .map_err(|err| RevsetResolutionError::Other(err.into()))
+    };
+    let head_op = resolve_op(head_str)?;
+    let operations = if let Some(root_str) = root_str {
+        let root_op = resolve_op(root_str)?;
+        op_walk::walk_ancestors_range(&[head_op], &[root_op])
+            .try_collect()
+            .block_on()
+            .map_err(|err| RevsetResolutionError::Other(err.into()))?
+    } else {
+        vec![head_op]
+    };
+    // Old operations may not record the predecessors. Treat them as empty.
+    let commit_ids = operations
+        .iter()
+        .filter_map(|op| op.store_operation().commit_predecessors.as_ref())
+        .flatten()
+        .filter(|(_, predecessors)| !rewritten_only || !predecessors.is_empty())
+        .map(|(commit_id, _)| commit_id.clone())
+        .unique()
+        .collect();
+    Ok(commit_ids)
+}
+
 fn resolve_remote_symbol(
     repo: &dyn Repo,
     symbol: RemoteRefSymbol<'_>,
@@ -3005,6 +3090,11 @@ fn resolve_commit_ref(
                 .collect();
             Ok(commit_ids)
         }
+        RevsetCommitRef::OperationCommits {
+            head,
+            root,
+            rewritten_only,
+        } => resolve_operation_commits(repo, head, root.as_deref(), *rewritten_only),
     }
 }
 
@@ -3558,6 +3648,11 @@ impl<'a> LoweringContext<'a> {
     pub fn symbol_resolvers(&self) -> &'a [impl AsRef<dyn SymbolResolverExtension> + use<>] {
         self.extensions.symbol_resolvers()
     }
+
+    /// Workspace information, if the expression is parsed in a workspace.
+    pub fn workspace(&self) -> Option<RevsetWorkspaceContext<'a>> {
+        self.workspace
+    }
 }
 
 /// Workspace information needed to parse revset expression.
@@ -373,7 +373,7 @@ fn io_to_read_error(err: PathError, id: &impl ObjectId) -> OpStoreError {
     }
 }
 
-fn to_read_error(
+pub(crate) fn to_read_error(
     source: Box<dyn std::error::Error + Send + Sync>,
     id: &impl ObjectId,
 ) -> OpStoreError {
@@ -384,13 +384,37 @@ fn to_read_error(
     }
 }
 
-fn io_to_write_error(err: PathError, object_type: &'static str) -> OpStoreError {
+pub(crate) fn io_to_write_error(err: PathError, object_type: &'static str) -> OpStoreError {
     OpStoreError::WriteObject {
         object_type,
         source: Box::new(err),
     }
 }
 
+/// Serializes the operation in the format of the [`SimpleOpStore`].
+pub(crate) fn encode_operation(operation: &Operation) -> Vec<u8> {
+    operation_to_proto(operation).encode_to_vec()
+}
+
+/// Deserializes the operation in the format of the [`SimpleOpStore`].
+pub(crate) fn decode_operation(
+    buf: &[u8],
+) -> Result<Operation, Box<dyn std::error::Error + Send + Sync>> {
+    let proto = crate::protos::simple_op_store::Operation::decode(buf)?;
+    Ok(operation_from_proto(proto)?)
+}
+
+/// Serializes the view in the format of the [`SimpleOpStore`].
+pub(crate) fn encode_view(view: &View) -> Vec<u8> {
+    view_to_proto(view).encode_to_vec()
+}
+
+/// Deserializes the view in the format of the [`SimpleOpStore`].
+pub(crate) fn decode_view(buf: &[u8]) -> Result<View, Box<dyn std::error::Error + Send + Sync>> {
+    let proto = crate::protos::simple_op_store::View::decode(buf)?;
+    Ok(view_from_proto(proto)?)
+}
+
 #[derive(Debug, Error)]
 enum PostDecodeError {
     #[error("Invalid hash length (expected {expected} bytes, got {actual} bytes)")]
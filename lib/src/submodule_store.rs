@@ -12,10 +12,292 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#![expect(missing_docs)]
+//! Storage of Git submodule repositories.
 
 use std::fmt::Debug;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
 
+use bstr::ByteSlice as _;
+use futures::AsyncReadExt as _;
+use futures::StreamExt as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::TreeValue;
+use crate::matchers::EverythingMatcher;
+use crate::merged_tree::MergedTree;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+
+/// Error that may occur while fetching or checking out a submodule.
+#[derive(Debug, Error)]
+pub enum SubmoduleStoreError {
+    /// The submodule isn't declared in `.gitmodules`.
+    #[error("Submodule at {path:?} is not declared in .gitmodules")]
+    NotDeclared {
+        /// Path to the submodule.
+        path: RepoPathBuf,
+    },
+    /// The pinned commit couldn't be found in the submodule repository.
+    #[error("Commit {commit_id} not found in submodule {name}")]
+    CommitNotFound {
+        /// Name of the submodule.
+        name: String,
+        /// The pinned commit.
+        commit_id: CommitId,
+    },
+    /// The submodule URL is relative to the superproject remote, which isn't
+    /// supported.
+    #[error("Relative URL {url} of submodule {name} is not supported")]
+    RelativeUrl {
+        /// Name of the submodule.
+        name: String,
+        /// The submodule URL.
+        url: String,
+    },
+    /// The submodule URL starts with `-`, which Git could interpret as an
+    /// option, or uses the `ext::` transport, which runs arbitrary commands.
+    #[error("URL {url} of submodule {name} is not allowed")]
+    InvalidUrl {
+        /// Name of the submodule.
+        name: String,
+        /// The submodule URL.
+        url: String,
+    },
+    /// Failed to spawn the Git command.
+    #[error("Could not execute the git process, found in the OS path '{}'", path.display())]
+    GitCommand {
+        /// Path to the Git executable.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: io::Error,
+    },
+    /// The Git command exited with an error.
+    #[error("Git process failed: {0}")]
+    GitProcess(String),
+    /// Failed to access the store or the working copy.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Failed to read the tree.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// Result of [`SubmoduleStore`] operations.
+pub type SubmoduleStoreResult<T> = Result<T, SubmoduleStoreError>;
+
+/// Submodule declared in `.gitmodules`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Submodule {
+    /// Name of the submodule, which is usually the same as the path.
+    pub name: String,
+    /// Path to the submodule in the tree.
+    pub path: RepoPathBuf,
+    /// URL of the submodule repository.
+    pub url: String,
+}
+
+/// Stores the repositories of Git submodules, and checks them out in the
+/// working copy.
 pub trait SubmoduleStore: Send + Sync + Debug {
+    /// Name of the store implementation.
     fn name(&self) -> &str;
+
+    /// Fetches the repository of the `submodule` so that `commit_id` becomes
+    /// available. The repository is cloned first if needed.
+    fn fetch(&self, submodule: &Submodule, commit_id: &CommitId) -> SubmoduleStoreResult<()>;
+
+    /// Returns true if `commit_id` has been fetched for the `submodule`.
+    fn has_commit(&self, submodule: &Submodule, commit_id: &CommitId)
+    -> SubmoduleStoreResult<bool>;
+
+    /// Checks out `commit_id` of the `submodule` in the `dest` directory. The
+    /// commit must have been fetched.
+    fn check_out(
+        &self,
+        submodule: &Submodule,
+        commit_id: &CommitId,
+        dest: &Path,
+    ) -> SubmoduleStoreResult<()>;
+
+    /// Returns the commit checked out in the submodule directory `dest`, or
+    /// `None` if the directory isn't a checkout.
+    fn checked_out_commit(&self, dest: &Path) -> SubmoduleStoreResult<Option<CommitId>>;
+}
+
+/// Parses the content of `.gitmodules` file.
+///
+/// Submodule sections without `path` or `url` are ignored.
+pub fn parse_gitmodules(content: &[u8]) -> Vec<Submodule> {
+    let mut submodules = vec![];
+    let mut current: Option<(String, Option<String>, Option<String>)> = None;
+    let mut flush = |current: Option<(String, Option<String>, Option<String>)>| {
+        if let Some((name, Some(path), Some(url))) = current
+            && let Ok(path) = RepoPathBuf::from_internal_string(path.trim_end_matches('/'))
+        {
+            submodules.push(Submodule { name, path, url });
+        }
+    };
+    for line in content.lines() {
+        let line = line.to_str_lossy();
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            flush(current.take());
+            let header = header.trim_end_matches(']').trim();
+            if let Some(name) = header.strip_prefix("submodule") {
+                let name = name.trim().trim_matches('"');
+                current = Some((name.to_owned(), None, None));
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some((_, path, url)) = &mut current else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_owned();
+        match key.trim() {
+            "path" => *path = Some(value),
+            "url" => *url = Some(value),
+            _ => {}
+        }
+    }
+    flush(current);
+    submodules
+}
+
+/// Reads the submodules declared in the `.gitmodules` file of the `tree`.
+pub async fn read_gitmodules(tree: &MergedTree) -> SubmoduleStoreResult<Vec<Submodule>> {
+    let path = RepoPath::from_internal_string(".gitmodules").unwrap();
+    let value = tree.path_value(path).await?;
+    let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+        return Ok(vec![]);
+    };
+    let mut reader = tree.store().read_file(path, id).await?;
+    let mut content = vec![];
+    reader
+        .read_to_end(&mut content)
+        .await
+        .map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    Ok(parse_gitmodules(&content))
+}
+
+/// Result of [`update_submodules()`].
+#[derive(Debug, Default)]
+pub struct SubmoduleUpdateStats {
+    /// Submodules checked out at the pinned commits.
+    pub updated: Vec<RepoPathBuf>,
+    /// Submodules that couldn't be updated.
+    pub failed: Vec<(RepoPathBuf, SubmoduleStoreError)>,
+}
+
+/// Fetches and checks out the submodules whose pinned commits differ between
+/// `old_tree` and `new_tree`.
+///
+/// The `new_tree` should have been checked out in `workspace_root`. Failures
+/// of individual submodules are reported in the returned stats.
+pub async fn update_submodules(
+    store: &dyn SubmoduleStore,
+    old_tree: Option<&MergedTree>,
+    new_tree: &MergedTree,
+    workspace_root: &Path,
+) -> SubmoduleStoreResult<SubmoduleUpdateStats> {
+    let empty_tree = new_tree.store().empty_merged_tree();
+    let old_tree = old_tree.unwrap_or(&empty_tree);
+    let mut pinned = vec![];
+    let mut diff_stream = old_tree.diff_stream(new_tree, &EverythingMatcher);
+    while let Some(entry) = diff_stream.next().await {
+        let values = entry.values?;
+        if let Some(Some(TreeValue::GitSubmodule(id))) = values.after.as_resolved() {
+            pinned.push((entry.path, id.clone()));
+        }
+    }
+    let mut stats = SubmoduleUpdateStats::default();
+    if pinned.is_empty() {
+        return Ok(stats);
+    }
+
+    let submodules = read_gitmodules(new_tree).await?;
+    for (path, commit_id) in pinned {
+        match update_submodule(store, &submodules, &path, &commit_id, workspace_root) {
+            Ok(()) => stats.updated.push(path),
+            Err(err) => stats.failed.push((path, err)),
+        }
+    }
+    Ok(stats)
+}
+
+fn update_submodule(
+    store: &dyn SubmoduleStore,
+    submodules: &[Submodule],
+    path: &RepoPath,
+    commit_id: &CommitId,
+    workspace_root: &Path,
+) -> SubmoduleStoreResult<()> {
+    let submodule = submodules
+        .iter()
+        .find(|submodule| *submodule.path == *path)
+        .ok_or_else(|| SubmoduleStoreError::NotDeclared {
+            path: path.to_owned(),
+        })?;
+    if !store.has_commit(submodule, commit_id)? {
+        store.fetch(submodule, commit_id)?;
+    }
+    let dest = path.to_fs_path(workspace_root).map_err(io::Error::other)?;
+    store.check_out(submodule, commit_id, &dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_path_buf(value: &str) -> RepoPathBuf {
+        RepoPathBuf::from_internal_string(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_gitmodules() {
+        let content = indoc::indoc! {r#"
+            # comment
+            [submodule "vendor/foo"]
+            	path = vendor/foo
+            	url = https://example.com/foo.git
+            [submodule "bar"]
+            	url = ../bar.git
+            	path = "third_party/bar/"
+            	branch = main
+            [core]
+            	path = ignored
+            [submodule "no-url"]
+            	path = no-url
+        "#};
+        assert_eq!(
+            parse_gitmodules(content.as_bytes()),
+            vec![
+                Submodule {
+                    name: "vendor/foo".to_owned(),
+                    path: repo_path_buf("vendor/foo"),
+                    url: "https://example.com/foo.git".to_owned(),
+                },
+                Submodule {
+                    name: "bar".to_owned(),
+                    path: repo_path_buf("third_party/bar"),
+                    url: "../bar.git".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(parse_gitmodules(b""), vec![]);
+    }
 }
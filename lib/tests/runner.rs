@@ -16,6 +16,7 @@ mod test_converge;
 mod test_default_revset_graph_iterator;
 mod test_eol;
 mod test_evolution_predecessors;
+mod test_fileset;
 mod test_fix;
 mod test_git;
 mod test_git_backend;
@@ -41,5 +42,6 @@ mod test_rewrite_duplicate;
 mod test_rewrite_transform;
 mod test_signing;
 mod test_ssh_signing;
+mod test_submodule_store;
 mod test_view;
 mod test_workspace;
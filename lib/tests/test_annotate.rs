@@ -33,6 +33,7 @@ use jj_lib::revset::RevsetExpression;
 use pollster::FutureExt as _;
 use testutils::CommitBuilderExt as _;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 use testutils::TestResult;
 use testutils::create_tree;
 use testutils::read_file;
@@ -429,3 +430,147 @@ fn test_annotate_file_directory_transition() -> TestResult {
     insta::assert_snapshot!(annotate(tx.repo(), &commit2, file_path2), @"commit2:1 : 2");
     Ok(())
 }
+
+#[test]
+fn test_annotate_renamed_file() -> TestResult {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path1 = repo_path("file1");
+    let file_path2 = repo_path("file2");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path1, "1\n2\n")]);
+    let tree2 = create_tree(repo, &[(file_path2, "1\n2\n")]);
+    let tree3 = create_tree(repo, &[(file_path2, "1\n2\n3\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3);
+    drop(create_commit);
+
+    insta::assert_snapshot!(annotate(tx.repo(), &commit3, file_path2), @"
+    commit1:1 : 1
+    commit1:2 : 2
+    commit3:3 : 3
+    ");
+
+    // Exclude the commit before the rename.
+    let domain = RevsetExpression::commit(commit1.id().clone()).negated();
+    insta::assert_snapshot!(annotate_within(tx.repo(), &commit3, &domain, file_path2), @"
+    commit1:1*: 1
+    commit1:2*: 2
+    commit3:3 : 3
+    ");
+
+    // Lines in the range are followed across the rename.
+    let mut annotator = FileAnnotator::from_commit(&commit3, file_path2).block_on()?;
+    annotator.set_line_range(0..1);
+    let mut resolved = Vec::new();
+    annotator
+        .compute_with(
+            tx.repo(),
+            &RevsetExpression::all(),
+            |line_number, origin| {
+                resolved.push((line_number, origin.unwrap().commit_id.clone()));
+            },
+        )
+        .block_on()?;
+    assert_eq!(resolved, [(0, commit1.id().clone())]);
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @"commit1:1 : 1");
+    Ok(())
+}
+
+#[test]
+fn test_annotate_ignored_revisions() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let content1 = "a\nb\nc\n";
+    let content2 = "A\nb\nc\nnew\n";
+    let content3 = "A\nb\nc\nnew\nd\n";
+    let tree1 = create_tree(repo, &[(file_path, content1)]);
+    let tree2 = create_tree(repo, &[(file_path, content2)]);
+    let tree3 = create_tree(repo, &[(file_path, content3)]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3);
+    drop(create_commit);
+
+    insta::assert_snapshot!(annotate(tx.repo(), &commit3, file_path), @"
+    commit2:1 : A
+    commit1:2 : b
+    commit1:3 : c
+    commit2:4 : new
+    commit3:5 : d
+    ");
+
+    // Modified lines are attributed to the parent of the ignored commit, but
+    // added lines aren't.
+    let mut annotator = FileAnnotator::from_commit(&commit3, file_path).block_on()?;
+    annotator.set_ignored_revisions(RevsetExpression::commit(commit2.id().clone()));
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .block_on()?;
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @"
+    commit1:1 : A
+    commit1:2 : b
+    commit1:3 : c
+    commit2:4 : new
+    commit3:5 : d
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_annotate_line_range() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "1\n2\n3\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "1\n2b\n3\n4\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    drop(create_commit);
+
+    let mut annotator = FileAnnotator::from_commit(&commit2, file_path).block_on()?;
+    annotator.set_line_range(1..3);
+    let mut resolved = Vec::new();
+    annotator
+        .compute_with(
+            tx.repo(),
+            &RevsetExpression::all(),
+            |line_number, origin| {
+                resolved.push((line_number, origin.unwrap().commit_id.clone()));
+            },
+        )
+        .block_on()?;
+    assert_eq!(
+        resolved,
+        [(1, commit2.id().clone()), (2, commit1.id().clone()),]
+    );
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @"
+    commit2:2 : 2b
+    commit1:3 : 3
+    ");
+
+    // The range is clamped to the end of the file.
+    let mut annotator = FileAnnotator::from_commit(&commit2, file_path).block_on()?;
+    annotator.set_line_range(3..10);
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .block_on()?;
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @"commit2:4 : 4");
+    Ok(())
+}
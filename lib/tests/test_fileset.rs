@@ -0,0 +1,195 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::fileset::FilePredicate;
+use jj_lib::fileset::FilesetExpression;
+use jj_lib::fileset::UnresolvedFilesetExpression;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo as _;
+use pollster::FutureExt as _;
+use testutils::TestRepo;
+use testutils::TestResult;
+use testutils::TestThreeWayMergeTreeBuilder;
+use testutils::TestTreeBuilder;
+use testutils::repo_path;
+
+/// Returns the paths in `tree` matched by `expression` after resolving its
+/// predicates against `trees`.
+fn matching_paths(
+    expression: &UnresolvedFilesetExpression,
+    trees: &[MergedTree],
+    tree: &MergedTree,
+) -> Vec<String> {
+    let resolved = expression.resolve_predicates(trees).block_on().unwrap();
+    let matcher = resolved.to_matcher();
+    tree.entries_matching(matcher.as_ref())
+        .map(|(path, _)| path.as_internal_file_string().to_owned())
+        .collect_vec()
+}
+
+#[test]
+fn test_resolve_predicates() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    let mut builder = TestTreeBuilder::new(store.clone());
+    builder
+        .file(repo_path("dir/script"), "#!/bin/sh\n")
+        .executable(true);
+    builder.file(repo_path("dir/text"), "text\n");
+    builder.file(repo_path("large"), "x".repeat(2048));
+    builder.file(repo_path("image.bin"), b"\x89PNG\0\0\0");
+    builder.symlink(repo_path("link"), "dir/text");
+    builder.submodule(repo_path("sub"), CommitId::from_hex("abcdef"));
+    let tree = builder.write_merged_tree();
+    let trees = [tree.clone()];
+
+    let predicate = |predicate| UnresolvedFilesetExpression::predicate(predicate);
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Executable), &trees, &tree),
+        @r#"
+    [
+        "dir/script",
+    ]
+    "#);
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Symlink), &trees, &tree),
+        @r#"
+    [
+        "link",
+    ]
+    "#);
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Submodule), &trees, &tree),
+        @r#"
+    [
+        "sub",
+    ]
+    "#);
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Conflicted), &trees, &tree),
+        @"[]");
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Size(1024..=u64::MAX)), &trees, &tree),
+        @r#"
+    [
+        "large",
+    ]
+    "#);
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Size(0..=8)), &trees, &tree),
+        @r#"
+    [
+        "dir/text",
+        "image.bin",
+    ]
+    "#);
+    insta::assert_debug_snapshot!(
+        matching_paths(&predicate(FilePredicate::Binary), &trees, &tree),
+        @r#"
+    [
+        "image.bin",
+    ]
+    "#);
+
+    // Predicates combine with path patterns
+    let dir = UnresolvedFilesetExpression::from(FilesetExpression::prefix_path(
+        repo_path("dir").to_owned(),
+    ));
+    insta::assert_debug_snapshot!(
+        matching_paths(
+            &dir.clone().difference(predicate(FilePredicate::Executable)),
+            &trees,
+            &tree,
+        ),
+        @r#"
+    [
+        "dir/text",
+    ]
+    "#);
+    let small_in_dir = dir.intersection(predicate(FilePredicate::Size(0..=8)));
+    insta::assert_debug_snapshot!(
+        matching_paths(
+            &UnresolvedFilesetExpression::from(FilesetExpression::all()).difference(small_in_dir),
+            &trees,
+            &tree,
+        ),
+        @r#"
+    [
+        "dir/script",
+        "image.bin",
+        "large",
+        "link",
+        "sub",
+    ]
+    "#);
+    Ok(())
+}
+
+#[test]
+fn test_resolve_predicates_conflicts() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    let mut builder = TestThreeWayMergeTreeBuilder::new(store.clone());
+    builder.base().file(repo_path("conflicted"), "base\n");
+    builder.parent1().file(repo_path("conflicted"), "left\n");
+    builder
+        .parent2()
+        .file(repo_path("conflicted"), "right\n")
+        .executable(true);
+    builder.base().file(repo_path("resolved"), "same\n");
+    builder.parent1().file(repo_path("resolved"), "same\n");
+    builder.parent2().file(repo_path("resolved"), "same\n");
+    let conflicted_tree = builder.write_merged_tree();
+
+    let mut builder = TestTreeBuilder::new(store.clone());
+    builder.file(repo_path("resolved"), "same\n");
+    builder.file(repo_path("other"), "other\n").executable(true);
+    let clean_tree = builder.write_merged_tree();
+
+    // A conflict matches if any of its sides does
+    let trees = [conflicted_tree.clone()];
+    let executable = UnresolvedFilesetExpression::predicate(FilePredicate::Executable);
+    let conflicted = UnresolvedFilesetExpression::predicate(FilePredicate::Conflicted);
+    insta::assert_debug_snapshot!(
+        matching_paths(&executable, &trees, &conflicted_tree),
+        @r#"
+    [
+        "conflicted",
+    ]
+    "#);
+    insta::assert_debug_snapshot!(
+        matching_paths(&conflicted, &trees, &conflicted_tree),
+        @r#"
+    [
+        "conflicted",
+    ]
+    "#);
+
+    // A path matches if it does in any of the trees
+    let trees = [conflicted_tree, clean_tree.clone()];
+    insta::assert_debug_snapshot!(
+        matching_paths(&executable, &trees, &clean_tree),
+        @r#"
+    [
+        "other",
+    ]
+    "#);
+    Ok(())
+}
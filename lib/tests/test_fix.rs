@@ -40,6 +40,7 @@ use jj_lib::transaction::Transaction;
 use pollster::FutureExt as _;
 use testutils::CommitBuilderExt as _;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 use testutils::TestResult;
 use testutils::assert_tree_eq;
 use testutils::create_tree;
@@ -1272,6 +1273,54 @@ fn test_fix_renamed_file() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_fix_renamed_file_with_copy_tracking() -> TestResult {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let path_a = repo_path("file_a");
+    let path_b = repo_path("file_b");
+
+    // Base: file_a = "unformatted\n"
+    let tree1 = create_tree(repo, &[(path_a, "unformatted\n")]);
+    let c1 = create_commit(&mut tx, vec![repo.store().root_commit_id().clone()], tree1);
+
+    // Child: file_a renamed to file_b
+    let tree2 = create_tree(repo, &[(path_b, "unformatted\n")]);
+    let c2 = create_commit(&mut tx, vec![c1.clone()], tree2);
+
+    // Run fix on c2. The renamed file should be diffed against its source.
+    let root_commits = vec![c2.clone()];
+    let mut file_fixer = TestFileFixer::new();
+    file_fixer.add_replacement(
+        path_b,
+        Some(b"unformatted\n"),
+        b"unformatted\n",
+        b"Formatted\n",
+    );
+
+    let include_unchanged_files = false;
+    let summary = fix_files(
+        root_commits,
+        &EverythingMatcher,
+        include_unchanged_files,
+        tx.repo_mut(),
+        &mut file_fixer,
+    )
+    .block_on()?;
+
+    assert_eq!(summary.rewrites.len(), 1);
+    assert!(summary.rewrites.contains_key(&c2));
+
+    let new_c2 = repo
+        .store()
+        .get_commit(summary.rewrites.get(&c2).unwrap())?;
+    let expected_tree = create_tree(repo, &[(path_b, "Formatted\n")]);
+    assert_tree_eq!(new_c2.tree(), expected_tree);
+    Ok(())
+}
+
 #[test]
 fn test_fix_empty_file_not_formatted() -> TestResult {
     let test_repo = TestRepo::init();
@@ -4530,6 +4530,54 @@ fn test_fetch_no_such_remote() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_fetch_commit() -> TestResult {
+    let test_data = GitRepoData::create();
+    let main_git_commit = empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+    let change_git_commit = empty_git_commit(
+        &test_data.origin_repo,
+        "refs/changes/45/12345/1",
+        &[main_git_commit],
+    );
+    let subprocess_options = GitSubprocessOptions::from_settings(test_data.repo.settings())?;
+    let import_options = default_import_options();
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut fetcher = GitFetch::new(tx.repo_mut(), subprocess_options, &import_options)?;
+    let commit_id = fetcher.fetch_commit(
+        "origin".as_ref(),
+        "refs/changes/45/12345/1".as_ref(),
+        &mut NullCallback,
+    )?;
+    assert_eq!(commit_id, Some(jj_id(change_git_commit)));
+    // The temporary ref is removed once the commit is imported
+    assert!(
+        test_data
+            .git_repo
+            .try_find_reference("refs/jj/fetched-commit")?
+            .is_none()
+    );
+    let commit_id = fetcher.fetch_commit(
+        "origin".as_ref(),
+        "refs/changes/45/12345/2".as_ref(),
+        &mut NullCallback,
+    )?;
+    assert_eq!(commit_id, None);
+
+    // The commit is readable, but isn't added to the view, and no bookmarks
+    // are created
+    drop(fetcher);
+    assert!(
+        tx.repo()
+            .store()
+            .get_commit(&jj_id(change_git_commit))
+            .is_ok()
+    );
+    assert!(!tx.repo().view().heads().contains(&jj_id(change_git_commit)));
+    assert_eq!(tx.repo().view().bookmarks().count(), 0);
+    Ok(())
+}
+
 #[test]
 fn test_fetch_multiple_branches() -> TestResult {
     let test_data = GitRepoData::create();
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::slice;
 use std::sync::Arc;
@@ -24,6 +25,7 @@ use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::evolution::walk_predecessors;
+use jj_lib::graph::GraphEdge;
 use jj_lib::index::Index;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::OperationId;
@@ -31,6 +33,8 @@ use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
+use jj_lib::opset::OpsetParseErrorKind;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
@@ -964,6 +968,130 @@ fn test_walk_ancestors() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_evaluate_opset() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo_0 = &test_repo.repo;
+    let op_store = repo_0.op_store();
+
+    let mut tx = repo_0.start_transaction();
+    tx.set_attribute("bot".to_owned(), "fetcher".to_owned());
+    let repo_1 = tx.commit("fetch from git remote(s) origin").block_on()?;
+    let mut tx = repo_1.start_transaction();
+    tx.set_is_snapshot(true);
+    let repo_2 = tx.commit("snapshot working copy").block_on()?;
+    let mut tx = repo_2.start_transaction();
+    tx.set_workspace_name("other".as_ref());
+    let repo_3 = tx.commit("rebase commits").block_on()?;
+
+    // The initial operation of the test repo is the root operation.
+    let op_0 = repo_0.operation();
+    let op_1 = repo_1.operation();
+    let op_2 = repo_2.operation();
+    let op_3 = repo_3.operation();
+    let evaluate = |text: &str| -> Vec<OperationId> {
+        let expression = opset::parse(text).unwrap();
+        let ops = expression
+            .evaluate(op_store, slice::from_ref(op_3))
+            .block_on()
+            .unwrap();
+        ops.iter().map(|op| op.id().clone()).collect()
+    };
+    let ids =
+        |ops: &[&Operation]| -> Vec<OperationId> { ops.iter().map(|op| op.id().clone()).collect() };
+
+    // Symbols and ranges
+    assert_eq!(evaluate("@"), ids(&[op_3]));
+    assert_eq!(evaluate("@--"), ids(&[op_1]));
+    assert_eq!(evaluate(&op_2.id().hex()), ids(&[op_2]));
+    assert_eq!(evaluate("@--..@"), ids(&[op_3, op_2]));
+    assert_eq!(evaluate("@--::@-"), ids(&[op_2, op_1]));
+    assert_eq!(evaluate("@-.."), ids(&[op_3]));
+    assert_eq!(evaluate("@--::"), ids(&[op_3, op_2, op_1]));
+    assert_eq!(evaluate("::@---"), ids(&[op_0]));
+    assert_eq!(evaluate("..@---"), ids(&[]));
+    assert_eq!(evaluate(".."), ids(&[op_3, op_2, op_1]));
+    assert_eq!(evaluate("root()"), ids(&[op_0]));
+    assert_eq!(evaluate("none()"), ids(&[]));
+
+    // Filters
+    assert_eq!(evaluate("snapshot()"), ids(&[op_2]));
+    assert_eq!(evaluate("~snapshot() & @---.."), ids(&[op_3, op_1]));
+    assert_eq!(evaluate(r#"description(glob:"fetch *")"#), ids(&[op_1]));
+    assert_eq!(evaluate("description(substring:commits)"), ids(&[op_3]));
+    assert_eq!(evaluate(r#"attribute(bot, "fetch*")"#), ids(&[op_1]));
+    assert_eq!(evaluate("attribute(bot, other)"), ids(&[]));
+    assert_eq!(evaluate("workspace(other)"), ids(&[op_3]));
+    assert_eq!(evaluate(r#"user("test-username") ~ ..@-"#), ids(&[op_3]));
+    assert_eq!(
+        evaluate(r#"hostname("*.example.com") & (snapshot() | @)"#),
+        ids(&[op_3, op_2])
+    );
+
+    // Operations unreachable from the heads are excluded
+    let expression = opset::parse("all()")?;
+    let ops = expression
+        .evaluate(op_store, slice::from_ref(op_1))
+        .block_on()?;
+    assert_eq!(ops, vec![op_1.clone(), op_0.clone()]);
+    let expression = opset::parse(&op_2.id().hex())?;
+    let ops = expression
+        .evaluate(op_store, slice::from_ref(op_1))
+        .block_on()?;
+    assert_eq!(ops, vec![]);
+
+    // Errors
+    assert_matches!(
+        opset::parse("users(x)").unwrap_err().kind(),
+        OpsetParseErrorKind::NoSuchFunction { .. }
+    );
+    assert_matches!(
+        opset::parse("glob:x").unwrap_err().kind(),
+        OpsetParseErrorKind::Expression(_)
+    );
+    assert_matches!(
+        opset::parse("snapshot(x)").unwrap_err().kind(),
+        OpsetParseErrorKind::InvalidArguments { .. }
+    );
+    assert_matches!(
+        opset::parse("@-----")?
+            .evaluate(op_store, slice::from_ref(op_3))
+            .block_on(),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::EmptyOperations(_)
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_opset_to_range() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo_0 = &test_repo.repo;
+    let repo_1 = repo_0.start_transaction().commit("op 1").block_on()?;
+    let repo_2 = repo_1.start_transaction().commit("op 2").block_on()?;
+    let repo_3 = repo_2.start_transaction().commit("op 3").block_on()?;
+    let [op_0, op_1, op_2, op_3] = [repo_0, &repo_1, &repo_2, &repo_3].map(|repo| repo.operation());
+
+    let range = opset::to_range(&[op_3.clone(), op_2.clone()])
+        .block_on()?
+        .unwrap();
+    assert_eq!(range.heads, vec![op_3.clone()]);
+    assert_eq!(range.roots, vec![op_1.clone()]);
+
+    let range = opset::to_range(slice::from_ref(op_1)).block_on()?.unwrap();
+    assert_eq!(range.heads, vec![op_1.clone()]);
+    assert_eq!(range.roots, vec![op_0.clone()]);
+
+    // Gap between operations
+    assert!(
+        opset::to_range(&[op_3.clone(), op_1.clone()])
+            .block_on()?
+            .is_none()
+    );
+    Ok(())
+}
+
 #[test]
 fn test_gc() -> TestResult {
     let settings = stable_op_id_settings();
@@ -1061,3 +1189,108 @@ fn extract_multiple_operations_error(
         None
     }
 }
+
+#[test]
+fn test_evaluate_opset_graph() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let op_store = repo_0.op_store();
+
+    fn op_parents<const N: usize>(op: &Operation) -> [Operation; N] {
+        let parents = op.parents().block_on().unwrap();
+        parents.try_into().unwrap()
+    }
+
+    // Set up branchy operation graph:
+    // D
+    // |\
+    // | C
+    // B |
+    // |/
+    // A
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>| {
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        tx
+    };
+    let repo_a = random_tx(&repo_0).commit("op A").block_on()?;
+    let tx_b = random_tx(&repo_a);
+    let tx_c = random_tx(&repo_a);
+    let repo_d = testutils::commit_transactions(vec![tx_b, tx_c]);
+    let op_0 = repo_0.operation();
+    let op_a = repo_a.operation();
+    let op_d = repo_d.operation();
+    let [op_b, op_c] = op_parents(repo_d.operation());
+
+    let evaluate_graph = |text: &str| -> HashMap<OperationId, Vec<GraphEdge<OperationId>>> {
+        let expression = opset::parse(text).unwrap();
+        let nodes = expression
+            .evaluate_graph(op_store, slice::from_ref(op_d))
+            .block_on()
+            .unwrap();
+        nodes
+            .map_ok(|(op, edges)| (op.id().clone(), edges))
+            .try_collect()
+            .block_on()
+            .unwrap()
+    };
+
+    // Edges to parents in the set are direct
+    assert_eq!(
+        evaluate_graph("::"),
+        HashMap::from([
+            (
+                op_d.id().clone(),
+                vec![
+                    GraphEdge::direct(op_b.id().clone()),
+                    GraphEdge::direct(op_c.id().clone()),
+                ],
+            ),
+            (
+                op_c.id().clone(),
+                vec![GraphEdge::direct(op_a.id().clone())]
+            ),
+            (
+                op_b.id().clone(),
+                vec![GraphEdge::direct(op_a.id().clone())]
+            ),
+            (
+                op_a.id().clone(),
+                vec![GraphEdge::direct(op_0.id().clone())]
+            ),
+            (op_0.id().clone(), vec![]),
+        ])
+    );
+
+    // Edges skipping operations outside the set are indirect
+    let c_or_0 = format!("{} | root()", op_c.id().hex());
+    assert_eq!(
+        evaluate_graph(&c_or_0),
+        HashMap::from([
+            (
+                op_c.id().clone(),
+                vec![GraphEdge::indirect(op_0.id().clone())]
+            ),
+            (op_0.id().clone(), vec![]),
+        ])
+    );
+
+    // Edges to ancestors reachable through other edges are omitted
+    let d_b_a = format!("@ | {} | {}", op_b.id().hex(), op_a.id().hex());
+    assert_eq!(
+        evaluate_graph(&d_b_a),
+        HashMap::from([
+            (
+                op_d.id().clone(),
+                vec![GraphEdge::direct(op_b.id().clone())]
+            ),
+            (
+                op_b.id().clone(),
+                vec![GraphEdge::direct(op_a.id().clone())]
+            ),
+            (op_a.id().clone(), vec![]),
+        ])
+    );
+    Ok(())
+}
@@ -4284,6 +4284,83 @@ fn test_evaluate_expression_at_operation() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_evaluate_expression_operation_commits() -> TestResult {
+    let test_repo = TestRepo::init();
+    let repo0 = &test_repo.repo;
+
+    let mut tx = repo0.start_transaction();
+    let commit1_op1 = create_random_commit(tx.repo_mut())
+        .set_description("commit1@op1")
+        .write_unwrap();
+    let commit2_op1 = create_random_commit(tx.repo_mut())
+        .set_description("commit2@op1")
+        .write_unwrap();
+    let repo1 = tx.commit("test").block_on()?;
+
+    let mut tx = repo1.start_transaction();
+    let commit1_op2 = tx
+        .repo_mut()
+        .rewrite_commit(&commit1_op1)
+        .set_description("commit1@op2")
+        .write_unwrap();
+    let commit3_op2 = create_random_commit(tx.repo_mut())
+        .set_description("commit3@op2")
+        .write_unwrap();
+    tx.repo_mut().rebase_descendants().block_on()?;
+    let repo2 = tx.commit("test").block_on()?;
+
+    // Single operation:
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "created_in(@)"),
+        vec![commit3_op2.id().clone(), commit1_op2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "rewritten_by(@)"),
+        vec![commit1_op2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "created_in(@-)"),
+        vec![commit2_op1.id().clone(), commit1_op1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "rewritten_by(@-)"),
+        vec![]
+    );
+
+    // Operation range, excluding the root operation:
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "created_in(@--, @)"),
+        vec![
+            commit3_op2.id().clone(),
+            commit1_op2.id().clone(),
+            commit2_op1.id().clone(),
+            commit1_op1.id().clone(),
+        ]
+    );
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "created_in(@-, @)"),
+        resolve_commit_ids(repo2.as_ref(), "created_in(@)")
+    );
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "rewritten_by(@--, @)"),
+        vec![commit1_op2.id().clone()]
+    );
+
+    // Hidden commits can be selected, but are filtered out by ::visible_heads().
+    assert_eq!(
+        resolve_commit_ids(repo2.as_ref(), "::visible_heads() & created_in(@-)"),
+        vec![commit2_op1.id().clone()]
+    );
+
+    // Bad operation:
+    assert_matches!(
+        try_resolve_commit_ids(repo2.as_ref(), "created_in(000000000000-)"),
+        Err(RevsetResolutionError::Other(_))
+    );
+    Ok(())
+}
+
 #[test]
 fn test_evaluate_expression_coalesce() {
     let test_repo = TestRepo::init();
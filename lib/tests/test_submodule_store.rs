@@ -0,0 +1,260 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::CommitId;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::default_submodule_store::DefaultSubmoduleStore;
+use jj_lib::settings::UserSettings;
+use jj_lib::submodule_store::Submodule;
+use jj_lib::submodule_store::SubmoduleStore as _;
+use jj_lib::submodule_store::SubmoduleStoreError;
+use jj_lib::submodule_store::update_submodules;
+use pollster::FutureExt as _;
+use testutils::TestRepo;
+use testutils::create_tree_with;
+use testutils::git;
+use testutils::repo_path;
+use testutils::repo_path_buf;
+
+fn add_source_commit(
+    repo: &gix::Repository,
+    content: &str,
+    parents: &[gix::ObjectId],
+) -> (gix::ObjectId, CommitId) {
+    let commit = git::add_commit(
+        repo,
+        "refs/heads/main",
+        "file",
+        content.as_bytes(),
+        "msg",
+        parents,
+    );
+    let commit_id = CommitId::from_bytes(commit.commit_id.as_bytes());
+    (commit.commit_id, commit_id)
+}
+
+fn init_store(store_path: &Path) -> DefaultSubmoduleStore {
+    DefaultSubmoduleStore::init(&testutils::user_settings(), store_path).unwrap()
+}
+
+/// Creates a store which can fetch from the local source repositories.
+fn init_store_allowing_local_urls(store_path: &Path) -> DefaultSubmoduleStore {
+    let mut config = testutils::base_user_config();
+    let mut layer = ConfigLayer::empty(ConfigSource::User);
+    layer
+        .set_value("working-copy.allow-local-submodule-urls", true)
+        .unwrap();
+    config.add_layer(layer);
+    let settings = UserSettings::from_config(config).unwrap();
+    DefaultSubmoduleStore::init(&settings, store_path).unwrap()
+}
+
+#[test]
+fn test_fetch_and_check_out() {
+    testutils::hermetic_git();
+    let temp_dir = testutils::new_temp_dir();
+    let source_path = temp_dir.path().join("source");
+    let source_repo = git::init(&source_path);
+    let store = init_store_allowing_local_urls(temp_dir.path());
+    let submodule = Submodule {
+        name: "sub".to_owned(),
+        path: repo_path_buf("sub"),
+        url: source_path.to_str().unwrap().to_owned(),
+    };
+    let dest = temp_dir.path().join("workspace").join("sub");
+
+    let (git_id1, commit_id1) = add_source_commit(&source_repo, "1", &[]);
+    assert!(!store.has_commit(&submodule, &commit_id1).unwrap());
+    store.fetch(&submodule, &commit_id1).unwrap();
+    assert!(store.has_commit(&submodule, &commit_id1).unwrap());
+    assert_eq!(store.checked_out_commit(&dest).unwrap(), None);
+    store.check_out(&submodule, &commit_id1, &dest).unwrap();
+    assert_eq!(store.checked_out_commit(&dest).unwrap(), Some(commit_id1));
+    assert_eq!(std::fs::read_to_string(dest.join("file")).unwrap(), "1");
+
+    // Existing checkout should be updated in place
+    let (_, commit_id2) = add_source_commit(&source_repo, "2", &[git_id1]);
+    assert!(!store.has_commit(&submodule, &commit_id2).unwrap());
+    store.fetch(&submodule, &commit_id2).unwrap();
+    store.check_out(&submodule, &commit_id2, &dest).unwrap();
+    assert_eq!(store.checked_out_commit(&dest).unwrap(), Some(commit_id2));
+    assert_eq!(std::fs::read_to_string(dest.join("file")).unwrap(), "2");
+
+    // Unknown commit
+    let missing_id = CommitId::from_hex("0123456789012345678901234567890123456789");
+    assert_matches!(
+        store.fetch(&submodule, &missing_id),
+        Err(SubmoduleStoreError::CommitNotFound { .. })
+    );
+}
+
+#[test]
+fn test_fetch_relative_url() {
+    let temp_dir = testutils::new_temp_dir();
+    let store = init_store(temp_dir.path());
+    let submodule = Submodule {
+        name: "sub".to_owned(),
+        path: repo_path_buf("sub"),
+        url: "../sub.git".to_owned(),
+    };
+    let commit_id = CommitId::from_hex("0123456789012345678901234567890123456789");
+    assert_matches!(
+        store.fetch(&submodule, &commit_id),
+        Err(SubmoduleStoreError::RelativeUrl { .. })
+    );
+}
+
+#[test]
+fn test_fetch_option_like_url() {
+    let temp_dir = testutils::new_temp_dir();
+    let store = init_store(temp_dir.path());
+    let submodule = Submodule {
+        name: "sub".to_owned(),
+        path: repo_path_buf("sub"),
+        url: "--upload-pack=touch pwned".to_owned(),
+    };
+    let commit_id = CommitId::from_hex("0123456789012345678901234567890123456789");
+    assert_matches!(
+        store.fetch(&submodule, &commit_id),
+        Err(SubmoduleStoreError::InvalidUrl { .. })
+    );
+    let dest = temp_dir.path().join("dest");
+    assert_matches!(
+        store.check_out(&submodule, &commit_id, &dest),
+        Err(SubmoduleStoreError::InvalidUrl { .. })
+    );
+    assert!(!dest.exists());
+}
+
+#[test]
+fn test_fetch_ext_url() {
+    let temp_dir = testutils::new_temp_dir();
+    let store = init_store_allowing_local_urls(temp_dir.path());
+    let submodule = Submodule {
+        name: "sub".to_owned(),
+        path: repo_path_buf("sub"),
+        url: "ext::sh -c touch% pwned".to_owned(),
+    };
+    let commit_id = CommitId::from_hex("0123456789012345678901234567890123456789");
+    assert_matches!(
+        store.fetch(&submodule, &commit_id),
+        Err(SubmoduleStoreError::InvalidUrl { .. })
+    );
+}
+
+#[test]
+fn test_update_submodules() {
+    testutils::hermetic_git();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let temp_dir = testutils::new_temp_dir();
+    let source_path = temp_dir.path().join("source");
+    let source_repo = git::init(&source_path);
+    let store = init_store_allowing_local_urls(temp_dir.path());
+    let workspace_root = temp_dir.path().join("workspace");
+    let (git_id1, commit_id1) = add_source_commit(&source_repo, "1", &[]);
+    let (_, commit_id2) = add_source_commit(&source_repo, "2", &[git_id1]);
+
+    let gitmodules = format!(
+        "[submodule \"sub\"]\n\tpath = sub\n\turl = {}\n",
+        source_path.to_str().unwrap()
+    );
+    let tree1 = create_tree_with(repo, |builder| {
+        builder.file(repo_path(".gitmodules"), &gitmodules);
+        builder.submodule(repo_path("sub"), commit_id1.clone());
+        builder.submodule(repo_path("undeclared"), commit_id1.clone());
+    });
+    let tree2 = create_tree_with(repo, |builder| {
+        builder.file(repo_path(".gitmodules"), &gitmodules);
+        builder.submodule(repo_path("sub"), commit_id2.clone());
+        builder.submodule(repo_path("undeclared"), commit_id1.clone());
+    });
+    let dest = workspace_root.join("sub");
+
+    let stats = update_submodules(&store, None, &tree1, &workspace_root)
+        .block_on()
+        .unwrap();
+    assert_eq!(stats.updated, vec![repo_path_buf("sub")]);
+    assert_eq!(stats.failed.len(), 1);
+    assert_eq!(stats.failed[0].0, repo_path_buf("undeclared"));
+    assert_matches!(stats.failed[0].1, SubmoduleStoreError::NotDeclared { .. });
+    assert_eq!(store.checked_out_commit(&dest).unwrap(), Some(commit_id1));
+
+    // Only the changed submodule is updated
+    let stats = update_submodules(&store, Some(&tree1), &tree2, &workspace_root)
+        .block_on()
+        .unwrap();
+    assert_eq!(stats.updated, vec![repo_path_buf("sub")]);
+    assert!(stats.failed.is_empty());
+    assert_eq!(store.checked_out_commit(&dest).unwrap(), Some(commit_id2));
+
+    // Nothing to do
+    let stats = update_submodules(&store, Some(&tree2), &tree2, &workspace_root)
+        .block_on()
+        .unwrap();
+    assert!(stats.updated.is_empty());
+    assert!(stats.failed.is_empty());
+}
+
+#[test]
+fn test_update_submodules_local_urls() {
+    testutils::hermetic_git();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let temp_dir = testutils::new_temp_dir();
+    let source_path = temp_dir.path().join("source");
+    let source_repo = git::init(&source_path);
+    let store = init_store(temp_dir.path());
+    let workspace_root = temp_dir.path().join("workspace");
+    let (_, commit_id) = add_source_commit(&source_repo, "1", &[]);
+
+    // Local repositories listed in .gitmodules are untrusted by default
+    let source_path = source_path.to_str().unwrap();
+    let gitmodules = format!(
+        "[submodule \"path\"]\n\tpath = path\n\turl = {source_path}\n\
+         [submodule \"file\"]\n\tpath = file\n\turl = file://{source_path}\n\
+         [submodule \"ext\"]\n\tpath = ext\n\turl = ext::sh -c touch% pwned\n"
+    );
+    let tree = create_tree_with(repo, |builder| {
+        builder.file(repo_path(".gitmodules"), &gitmodules);
+        builder.submodule(repo_path("path"), commit_id.clone());
+        builder.submodule(repo_path("file"), commit_id.clone());
+        builder.submodule(repo_path("ext"), commit_id.clone());
+    });
+    let stats = update_submodules(&store, None, &tree, &workspace_root)
+        .block_on()
+        .unwrap();
+    assert!(stats.updated.is_empty());
+    assert_eq!(stats.failed.len(), 3);
+    let failed: HashMap<_, _> = stats.failed.into_iter().collect();
+    assert_matches!(
+        failed[repo_path("path")],
+        SubmoduleStoreError::GitProcess(ref message) if message.contains("'file' not allowed")
+    );
+    assert_matches!(
+        failed[repo_path("file")],
+        SubmoduleStoreError::GitProcess(ref message) if message.contains("'file' not allowed")
+    );
+    assert_matches!(
+        failed[repo_path("ext")],
+        SubmoduleStoreError::InvalidUrl { .. }
+    );
+    assert!(!workspace_root.join("pwned").exists());
+    assert!(!temp_dir.path().join("pwned").exists());
+}